@@ -76,8 +76,10 @@ pub fn generate_calls(
             (call_struct, client_fn)
         })
         .unzip();
+    let call_type = type_gen.resolve_type_path(call.ty.id(), &[]);
 
     quote! {
+        pub type Call = #call_type;
         pub mod calls {
             use super::#types_mod_ident;
             #( #call_structs )*
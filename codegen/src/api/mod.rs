@@ -27,6 +27,7 @@ use crate::{
 use codec::Decode;
 use frame_metadata::{
     v14::RuntimeMetadataV14,
+    PalletMetadata,
     RuntimeMetadata,
     RuntimeMetadataPrefixed,
 };
@@ -37,6 +38,7 @@ use quote::{
     format_ident,
     quote,
 };
+use scale_info::form::PortableForm;
 use std::{
     collections::HashMap,
     fs,
@@ -181,25 +183,12 @@ impl RuntimeGenerator {
             }
         });
 
-        let outer_event_variants = self.metadata.pallets.iter().filter_map(|p| {
-            let variant_name = format_ident!("{}", p.name);
-            let mod_name = format_ident!("{}", p.name.to_string().to_snake_case());
-            let index = proc_macro2::Literal::u8_unsuffixed(p.index);
-
-            p.event.as_ref().map(|_| {
-                quote! {
-                    #[codec(index = #index)]
-                    #variant_name(#mod_name::Event),
-                }
-            })
+        let outer_event = self.generate_outer_enum("Event", &derives, |pallet| {
+            pallet.event.as_ref().map(|event| event.ty.id())
+        });
+        let outer_call = self.generate_outer_enum("Call", &derives, |pallet| {
+            pallet.calls.as_ref().map(|calls| calls.ty.id())
         });
-
-        let outer_event = quote! {
-            #derives
-            pub enum Event {
-                #( #outer_event_variants )*
-            }
-        };
 
         let mod_ident = item_mod_ir.ident;
         let pallets_with_storage =
@@ -219,6 +208,7 @@ impl RuntimeGenerator {
             #[allow(dead_code, unused_imports, non_camel_case_types)]
             pub mod #mod_ident {
                 #outer_event
+                #outer_call
                 #( #modules )*
                 #types_mod
 
@@ -232,6 +222,7 @@ impl RuntimeGenerator {
                     type Hash = ::subxt::sp_core::H256;
                     type Hashing = ::subxt::sp_runtime::traits::BlakeTwo256;
                     type AccountId = ::subxt::sp_runtime::AccountId32;
+                    type Balance = u128;
                     type Address = ::subxt::sp_runtime::MultiAddress<Self::AccountId, u32>;
                     type Header = ::subxt::sp_runtime::generic::Header<
                         Self::BlockNumber, ::subxt::sp_runtime::traits::BlakeTwo256
@@ -313,6 +304,150 @@ impl RuntimeGenerator {
                             #pallets_with_calls::calls::TransactionApi::new(self.client)
                         }
                     )*
+
+                    pub async fn submit_dynamic(
+                        &self,
+                        pallet: &str,
+                        call: &str,
+                        args: ::std::vec::Vec<::subxt::value::Value>,
+                        signer: &(dyn ::subxt::Signer<T> + Send + Sync),
+                    ) -> ::core::result::Result<::subxt::TransactionProgress<'a, T>, ::subxt::Error>
+                    where
+                        <<<T as ::subxt::ExtrinsicExtraData<T>>::Extra as ::subxt::SignedExtra<T>>::Extra as ::subxt::sp_runtime::traits::SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+                    {
+                        self.client.submit_dynamic(pallet, call, args, signer).await
+                    }
+
+                    pub async fn watch_calls(
+                        &self,
+                        pallet: &str,
+                        call: &str,
+                    ) -> ::core::result::Result<::subxt::CallSubscription<'a, T>, ::subxt::Error> {
+                        self.client.watch_calls(pallet, call).await
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates the outer `name` enum, with a variant wrapping the `name` enum of each pallet
+    /// for which `pallet_enum` returns its type, at the index of the pallet.
+    ///
+    /// `Encode` and `Decode` are implemented manually so that a pallet or variant unknown to the
+    /// metadata decodes into the `Other` variant instead of failing.
+    fn generate_outer_enum(
+        &self,
+        name: &str,
+        derives: &GeneratedTypeDerives,
+        pallet_enum: impl Fn(&PalletMetadata<PortableForm>) -> Option<u32>,
+    ) -> TokenStream2 {
+        let enum_name = format_ident!("{}", name);
+        let pallets = self
+            .metadata
+            .pallets
+            .iter()
+            .filter_map(|pallet| {
+                let ty = pallet_enum(pallet)?;
+                let variant_name = format_ident!("{}", pallet.name);
+                let mod_name =
+                    format_ident!("{}", pallet.name.to_string().to_snake_case());
+                let index = proc_macro2::Literal::u8_unsuffixed(pallet.index);
+                let variant_indices =
+                    match self.metadata.types.resolve(ty).map(|ty| ty.type_def()) {
+                        Some(scale_info::TypeDef::Variant(variant)) => {
+                            variant
+                                .variants()
+                                .iter()
+                                .map(|variant| {
+                                    proc_macro2::Literal::u8_unsuffixed(variant.index())
+                                })
+                                .collect::<Vec<_>>()
+                        }
+                        _ => {
+                            abort_call_site!(
+                                "{} type {} of pallet {} should be an enum",
+                                name,
+                                ty,
+                                pallet.name
+                            )
+                        }
+                    };
+                Some((variant_name, mod_name, index, variant_indices))
+            })
+            .collect::<Vec<_>>();
+
+        let variants = pallets.iter().map(|(variant_name, mod_name, _, _)| {
+            quote! { #variant_name(#mod_name::#enum_name), }
+        });
+        let encode_arms = pallets.iter().map(|(variant_name, _, index, _)| {
+            quote! {
+                Self::#variant_name(inner) => {
+                    dest.push_byte(#index);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+            }
+        });
+        let decode_arms = pallets
+            .iter()
+            .filter(|(_, _, _, variant_indices)| !variant_indices.is_empty())
+            .map(|(variant_name, _, index, variant_indices)| {
+                quote! {
+                    (#index, #( #variant_indices )|*) => {
+                        ::core::result::Result::Ok(Self::#variant_name(
+                            ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                        ))
+                    }
+                }
+            });
+        let derives = derives.without_codec();
+        let doc = format!(
+            "The {name} enums of all pallets.\n\n\
+             A pallet or variant which is not in the metadata the code was generated from \
+             decodes into [`{name}::Other`] rather than failing. Since the length of its fields \
+             is unknown, `Other` takes the rest of the input, so decode from the bytes of \
+             exactly one `{name}`.",
+            name = name,
+        );
+
+        quote! {
+            #[doc = #doc]
+            #derives
+            pub enum #enum_name {
+                #( #variants )*
+                /// A pallet or variant unknown when this code was generated: the pallet index,
+                /// the variant index and the encoded fields.
+                Other(u8, u8, ::std::vec::Vec<u8>),
+            }
+
+            impl ::subxt::codec::Encode for #enum_name {
+                fn encode_to<O: ::subxt::codec::Output + ?Sized>(&self, dest: &mut O) {
+                    match self {
+                        #( #encode_arms )*
+                        Self::Other(pallet_index, variant_index, fields) => {
+                            dest.push_byte(*pallet_index);
+                            dest.push_byte(*variant_index);
+                            dest.write(fields)
+                        }
+                    }
+                }
+            }
+
+            impl ::subxt::codec::Decode for #enum_name {
+                fn decode<I: ::subxt::codec::Input>(
+                    input: &mut I,
+                ) -> ::core::result::Result<Self, ::subxt::codec::Error> {
+                    let pallet_index = input.read_byte()?;
+                    let variant_index = input.read_byte()?;
+                    match (pallet_index, variant_index) {
+                        #( #decode_arms )*
+                        _ => {
+                            ::core::result::Result::Ok(Self::Other(
+                                pallet_index,
+                                variant_index,
+                                ::subxt::outer_enum::decode_unknown(input)?,
+                            ))
+                        }
+                    }
                 }
             }
         }
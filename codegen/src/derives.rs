@@ -31,6 +31,21 @@ impl GeneratedTypeDerives {
             self.derives.push(derive)
         }
     }
+
+    /// The derives other than `Encode` and `Decode`, for types which implement them manually.
+    pub fn without_codec(&self) -> Self {
+        let derives = self
+            .derives
+            .iter()
+            .filter(|path| {
+                path.segments.last().map_or(true, |segment| {
+                    segment.ident != "Encode" && segment.ident != "Decode"
+                })
+            })
+            .cloned()
+            .collect();
+        Self::new(derives)
+    }
 }
 
 impl Default for GeneratedTypeDerives {
@@ -45,6 +60,9 @@ impl Default for GeneratedTypeDerives {
 impl quote::ToTokens for GeneratedTypeDerives {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let derives = &self.derives;
+        if derives.is_empty() {
+            return
+        }
         tokens.extend(quote::quote! {
             #[derive(#derives)]
         })
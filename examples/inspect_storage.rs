@@ -0,0 +1,69 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! To run this example, a local polkadot node should be running.
+//!
+//! E.g.
+//! ```bash
+//! curl "https://github.com/paritytech/polkadot/releases/download/v0.9.11/polkadot" --output /usr/local/bin/polkadot --location
+//! polkadot --dev --tmp
+//! ```
+//!
+//! Then print any storage value as JSON, passing map keys as numbers or `0x` prefixed bytes:
+//! ```bash
+//! cargo run --example inspect_storage -- System Number
+//! cargo run --example inspect_storage -- System BlockHash 0
+//! ```
+
+use subxt::{
+    value::Value,
+    ClientBuilder,
+};
+
+#[subxt::subxt(runtime_metadata_path = "examples/polkadot_metadata.scale")]
+pub mod polkadot {}
+
+#[async_std::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+
+    let mut args = std::env::args().skip(1);
+    let (pallet, entry) = match (args.next(), args.next()) {
+        (Some(pallet), Some(entry)) => (pallet, entry),
+        _ => return Err("usage: inspect_storage <pallet> <entry> [keys...]".into()),
+    };
+    let keys = args
+        .map(|key| {
+            match key.strip_prefix("0x") {
+                Some(bytes) => Ok(Value::from_bytes(&hex::decode(bytes)?)),
+                None => Ok(Value::u128(key.parse()?)),
+            }
+        })
+        .collect::<Result<_, Box<dyn std::error::Error>>>()?;
+
+    let client = ClientBuilder::new()
+        .build::<polkadot::DefaultConfig>()
+        .await?;
+    let value = client
+        .storage()
+        .fetch_dynamic(&pallet, &entry, keys, None)
+        .await?;
+    match value {
+        Some(value) => println!("{}", serde_json::to_string_pretty(&value.to_json())?),
+        None => println!("null"),
+    }
+    Ok(())
+}
@@ -45,7 +45,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .to_runtime_api::<polkadot::RuntimeApi<polkadot::DefaultConfig>>();
 
     let sub = api.client.rpc().subscribe_events().await?;
-    let decoder = api.client.events_decoder();
+    let decoder = api.client.events_decoder()?;
     let mut sub = EventSubscription::<polkadot::DefaultConfig>::new(sub, decoder);
     sub.filter_event::<polkadot::balances::events::Transfer>();
 
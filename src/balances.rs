@@ -0,0 +1,396 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls, events and storage of the `Balances` pallet which are independent of the runtime,
+//! backing [`crate::Client::transfer`], [`crate::Client::transfer_keep_alive`] and
+//! [`crate::Client::account_balance`].
+
+use codec::{
+    Compact,
+    Decode,
+    Encode,
+};
+use frame_metadata::StorageEntryType;
+use scale_info::TypeDef;
+use sp_runtime::traits::{
+    UniqueSaturatedFrom,
+    UniqueSaturatedInto,
+};
+
+use crate::{
+    metadata::MetadataError,
+    value::{
+        Primitive,
+        Value,
+    },
+    Call,
+    Config,
+    Error,
+    Event,
+    Metadata,
+};
+
+/// The `Balances::transfer` call.
+#[derive(Encode)]
+pub struct Transfer<T: Config> {
+    /// The recipient of the transfer.
+    pub dest: T::Address,
+    /// The amount to transfer.
+    pub value: Compact<u128>,
+}
+
+impl<T: Config> Transfer<T> {
+    /// Create a new transfer of `value` to `dest`, an address or an account.
+    pub fn new<A: Into<T::Address>>(dest: A, value: T::Balance) -> Self {
+        Self {
+            dest: dest.into(),
+            value: Compact(value.unique_saturated_into()),
+        }
+    }
+}
+
+impl<T: Config> Call for Transfer<T> {
+    const PALLET: &'static str = "Balances";
+    const FUNCTION: &'static str = "transfer";
+}
+
+/// The `Balances::transfer_keep_alive` call, which fails rather than reaping the sender's
+/// account if its balance would drop below the existential deposit.
+#[derive(Encode)]
+pub struct TransferKeepAlive<T: Config> {
+    /// The recipient of the transfer.
+    pub dest: T::Address,
+    /// The amount to transfer.
+    pub value: Compact<u128>,
+}
+
+impl<T: Config> TransferKeepAlive<T> {
+    /// Create a new transfer of `value` to `dest`, an address or an account.
+    pub fn new<A: Into<T::Address>>(dest: A, value: T::Balance) -> Self {
+        Self {
+            dest: dest.into(),
+            value: Compact(value.unique_saturated_into()),
+        }
+    }
+}
+
+impl<T: Config> Call for TransferKeepAlive<T> {
+    const PALLET: &'static str = "Balances";
+    const FUNCTION: &'static str = "transfer_keep_alive";
+}
+
+/// The `Balances::Transfer` event.
+#[derive(Debug, Eq, PartialEq, Decode)]
+pub struct Transferred<T: Config> {
+    /// The sender of the transfer.
+    pub from: T::AccountId,
+    /// The recipient of the transfer.
+    pub to: T::AccountId,
+    /// The amount transferred.
+    pub amount: T::Balance,
+}
+
+impl<T: Config> Event for Transferred<T> {
+    const PALLET: &'static str = "Balances";
+    const EVENT: &'static str = "Transfer";
+}
+
+/// Where a runtime stores the balances of an account.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BalanceLayout {
+    /// In the `data` field of `System::Account`, for runtimes where `Balances` uses `System` as
+    /// its account store.
+    System,
+    /// In `Balances::Account`, for runtimes where `System::Account` holds no account data.
+    Balances,
+}
+
+impl BalanceLayout {
+    /// Detect the layout used by the runtime described by `metadata`.
+    pub fn from_metadata(metadata: &Metadata) -> Result<Self, Error> {
+        let system_account = metadata
+            .pallet("System")
+            .and_then(|pallet| pallet.storage("Account"));
+        let value_ty = match system_account {
+            Ok(entry) => {
+                match &entry.ty {
+                    StorageEntryType::Map { value, .. } => value.id(),
+                    StorageEntryType::Plain(_) => {
+                        return Err(MetadataError::StorageTypeError.into())
+                    }
+                }
+            }
+            // Without a `System::Account` entry, fall back to `Balances::Account`.
+            Err(_) => {
+                metadata.pallet("Balances")?.storage("Account")?;
+                return Ok(Self::Balances)
+            }
+        };
+        let data_ty = match metadata.resolve_type(value_ty).map(|ty| ty.type_def()) {
+            Some(TypeDef::Composite(composite)) => {
+                composite
+                    .fields()
+                    .iter()
+                    .find(|field| field.name().map(|name| name.as_str()) == Some("data"))
+                    .map(|field| field.ty().id())
+                    .ok_or(MetadataError::StorageTypeError)?
+            }
+            _ => return Err(MetadataError::StorageTypeError.into()),
+        };
+        let has_data = match metadata.resolve_type(data_ty).map(|ty| ty.type_def()) {
+            Some(TypeDef::Composite(composite)) => !composite.fields().is_empty(),
+            Some(TypeDef::Tuple(tuple)) => !tuple.fields().is_empty(),
+            Some(_) => true,
+            None => return Err(MetadataError::StorageTypeError.into()),
+        };
+        Ok(if has_data {
+            Self::System
+        } else {
+            Self::Balances
+        })
+    }
+
+    /// The name of the pallet whose `Account` storage entry holds the balances.
+    pub fn pallet(&self) -> &'static str {
+        match self {
+            Self::System => "System",
+            Self::Balances => "Balances",
+        }
+    }
+
+    /// The key of `account` in the `Account` storage entry, as a dynamic value.
+    pub(crate) fn account_key<T: Config>(
+        &self,
+        metadata: &Metadata,
+        account: &T::AccountId,
+    ) -> Result<Value, Error> {
+        let entry = metadata.pallet(self.pallet())?.storage("Account")?;
+        match &entry.ty {
+            StorageEntryType::Map { key, .. } => {
+                Value::decode(metadata, key.id(), &mut &account.encode()[..])
+            }
+            StorageEntryType::Plain(_) => Err(MetadataError::StorageTypeError.into()),
+        }
+    }
+}
+
+/// The balances of an account, see [`crate::Client::account_balance`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AccountBalance<T: Config> {
+    /// The balance which can be transferred, although part of it may be frozen.
+    pub free: T::Balance,
+    /// The balance reserved by pallets, which cannot be transferred.
+    pub reserved: T::Balance,
+    /// The part of `free` which cannot be used for anything but transaction fees.
+    pub misc_frozen: T::Balance,
+    /// The part of `free` which cannot be used for transaction fees.
+    pub fee_frozen: T::Balance,
+}
+
+impl<T: Config> AccountBalance<T> {
+    /// The balances of an account which does not exist.
+    pub fn empty() -> Self {
+        Self {
+            free: Default::default(),
+            reserved: Default::default(),
+            misc_frozen: Default::default(),
+            fee_frozen: Default::default(),
+        }
+    }
+
+    /// Read the balances from the decoded value of the `Account` storage entry of `layout`.
+    pub fn from_storage_value(
+        layout: BalanceLayout,
+        value: &Value,
+    ) -> Result<Self, Error> {
+        let composite = |value: &Value| {
+            match value {
+                Value::Composite(composite) => Ok(composite.clone()),
+                _ => Err(Error::Other("Account data is not a composite".into())),
+            }
+        };
+        let data = match layout {
+            BalanceLayout::System => {
+                let data = composite(value)?.field("data").cloned().ok_or_else(|| {
+                    Error::Other("Account info has no data field".into())
+                })?;
+                composite(&data)?
+            }
+            BalanceLayout::Balances => composite(value)?,
+        };
+        let balance = |name: &str| {
+            match data.field(name) {
+                Some(Value::Primitive(Primitive::U128(n))) => {
+                    Ok(T::Balance::unique_saturated_from(*n))
+                }
+                _ => {
+                    Err(Error::Other(format!(
+                        "Account data has no {} balance",
+                        name
+                    )))
+                }
+            }
+        };
+        Ok(Self {
+            free: balance("free")?,
+            reserved: balance("reserved")?,
+            misc_frozen: balance("misc_frozen")?,
+            fee_frozen: balance("fee_frozen")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        pallet,
+        MetadataBuilder,
+        MockConfig,
+    };
+    use frame_metadata::{
+        v14::{
+            PalletMetadata,
+            PalletStorageMetadata,
+            StorageEntryMetadata,
+        },
+        StorageEntryModifier,
+        StorageHasher,
+    };
+    use scale_info::{
+        meta_type,
+        MetaType,
+        TypeInfo,
+    };
+
+    #[derive(Encode, TypeInfo)]
+    struct AccountInfo<Data> {
+        nonce: u32,
+        data: Data,
+    }
+
+    #[derive(Encode, TypeInfo)]
+    struct AccountData {
+        free: u128,
+        reserved: u128,
+        misc_frozen: u128,
+        fee_frozen: u128,
+    }
+
+    const ACCOUNT_DATA: AccountData = AccountData {
+        free: 1000,
+        reserved: 100,
+        misc_frozen: 10,
+        fee_frozen: 1,
+    };
+
+    fn account_entry(value: MetaType) -> PalletStorageMetadata {
+        PalletStorageMetadata {
+            prefix: "",
+            entries: vec![StorageEntryMetadata {
+                name: "Account",
+                modifier: StorageEntryModifier::Default,
+                ty: StorageEntryType::Map {
+                    hashers: vec![StorageHasher::Blake2_128Concat],
+                    key: meta_type::<[u8; 32]>(),
+                    value,
+                },
+                default: vec![],
+                docs: vec![],
+            }],
+        }
+    }
+
+    /// Metadata of a runtime with the given `System::Account` and `Balances::Account` types.
+    fn metadata(system_account: MetaType, balances_account: MetaType) -> Metadata {
+        MetadataBuilder::new()
+            .with_pallet(PalletMetadata {
+                storage: Some(account_entry(system_account)),
+                ..pallet("System", 0)
+            })
+            .with_pallet(PalletMetadata {
+                storage: Some(account_entry(balances_account)),
+                ..pallet("Balances", 5)
+            })
+            .build()
+    }
+
+    /// Decode `encoded` as the value of the `Account` entry of `layout`.
+    fn decode_account(
+        metadata: &Metadata,
+        layout: BalanceLayout,
+        encoded: &[u8],
+    ) -> AccountBalance<MockConfig> {
+        let entry = metadata
+            .pallet(layout.pallet())
+            .unwrap()
+            .storage("Account")
+            .unwrap();
+        let value_ty = match &entry.ty {
+            StorageEntryType::Map { value, .. } => value.id(),
+            StorageEntryType::Plain(_) => unreachable!(),
+        };
+        let value = Value::decode(metadata, value_ty, &mut &encoded[..]).unwrap();
+        AccountBalance::from_storage_value(layout, &value).unwrap()
+    }
+
+    fn expected() -> AccountBalance<MockConfig> {
+        AccountBalance {
+            free: 1000,
+            reserved: 100,
+            misc_frozen: 10,
+            fee_frozen: 1,
+        }
+    }
+
+    #[test]
+    fn balances_in_system_account() {
+        let metadata = metadata(
+            meta_type::<AccountInfo<AccountData>>(),
+            meta_type::<AccountData>(),
+        );
+        let layout = BalanceLayout::from_metadata(&metadata).unwrap();
+        assert_eq!(layout, BalanceLayout::System);
+
+        let encoded = AccountInfo {
+            nonce: 3,
+            data: ACCOUNT_DATA,
+        }
+        .encode();
+        assert_eq!(decode_account(&metadata, layout, &encoded), expected());
+    }
+
+    #[test]
+    fn balances_in_balances_account() {
+        let metadata =
+            metadata(meta_type::<AccountInfo<()>>(), meta_type::<AccountData>());
+        let layout = BalanceLayout::from_metadata(&metadata).unwrap();
+        assert_eq!(layout, BalanceLayout::Balances);
+
+        let encoded = ACCOUNT_DATA.encode();
+        assert_eq!(decode_account(&metadata, layout, &encoded), expected());
+    }
+
+    #[test]
+    fn transfers_wrap_accounts_into_addresses() {
+        let account = sp_runtime::AccountId32::new([1; 32]);
+        let address = sp_runtime::MultiAddress::Id(account.clone());
+        assert_eq!(
+            Transfer::<MockConfig>::new(account, 10).encode(),
+            Transfer::<MockConfig>::new(address, 10).encode(),
+        );
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pack many calls into `Utility::batch_all` extrinsics which stay under a target weight and
+//! length, e.g. to split a bulk airdrop across several blocks, or dispatch them with
+//! `Utility::force_batch` and find out which of them failed.
+
+use codec::{
+    Decode,
+    Encode,
+};
+use sp_runtime::{
+    traits::SignedExtension,
+    DispatchError,
+};
+
+use crate::{
+    Call,
+    Client,
+    Config,
+    Encoded,
+    Error,
+    Event,
+    ExtrinsicExtraData,
+    Metadata,
+    RuntimeError,
+    SignedExtra,
+    Signer,
+    SubmittableExtrinsic,
+    TransactionEvents,
+    Weight,
+};
+
+/// The `Utility::batch_all` call, dispatching already encoded calls atomically.
+#[derive(Clone, Debug, Eq, PartialEq, Encode)]
+pub struct BatchAll {
+    /// The encoded calls, including their pallet and call indices.
+    pub calls: Vec<Encoded>,
+}
+
+impl Call for BatchAll {
+    const PALLET: &'static str = "Utility";
+    const FUNCTION: &'static str = "batch_all";
+}
+
+/// The `Utility::force_batch` call, dispatching already encoded calls one after the other and
+/// carrying on past the calls which fail.
+#[derive(Clone, Debug, Eq, PartialEq, Encode)]
+pub struct ForceBatch {
+    /// The encoded calls, including their pallet and call indices.
+    pub calls: Vec<Encoded>,
+}
+
+impl Call for ForceBatch {
+    const PALLET: &'static str = "Utility";
+    const FUNCTION: &'static str = "force_batch";
+}
+
+/// The `Utility::ItemCompleted` event, emitted for every call of a batch which succeeded.
+#[derive(Clone, Debug, Eq, PartialEq, Decode)]
+pub struct ItemCompleted;
+
+impl Event for ItemCompleted {
+    const PALLET: &'static str = "Utility";
+    const EVENT: &'static str = "ItemCompleted";
+}
+
+/// The `Utility::ItemFailed` event, emitted for every call of a `force_batch` which failed.
+#[derive(Clone, Debug, Eq, PartialEq, Decode)]
+pub struct ItemFailed {
+    /// The error the call failed with.
+    pub error: DispatchError,
+}
+
+impl Event for ItemFailed {
+    const PALLET: &'static str = "Utility";
+    const EVENT: &'static str = "ItemFailed";
+}
+
+/// The substrate default `ExtrinsicBaseWeight`: 125 microseconds.
+const DEFAULT_BASE_EXTRINSIC_WEIGHT: Weight = Weight::from_ref_time(125_000_000);
+
+/// Limits each batch produced by [`pack_calls`] must stay under.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchLimits {
+    max_weight: Weight,
+    max_length: usize,
+    max_calls: usize,
+    base_extrinsic_weight: Weight,
+}
+
+impl BatchLimits {
+    /// Creates new limits with the given target weight per batch extrinsic.
+    ///
+    /// Every dimension of the weight must stay under the target; on runtimes with scalar
+    /// weights use [`Weight::from_ref_time`].
+    pub fn new(max_weight: Weight) -> Self {
+        Self {
+            max_weight,
+            max_length: usize::MAX,
+            max_calls: usize::MAX,
+            base_extrinsic_weight: DEFAULT_BASE_EXTRINSIC_WEIGHT,
+        }
+    }
+
+    /// Set the maximum encoded length of the calls in a batch.
+    pub fn set_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Set the maximum number of calls in a batch, e.g. the `batched_calls_limit` constant of
+    /// the `Utility` pallet.
+    pub fn set_max_calls(mut self, max_calls: usize) -> Self {
+        self.max_calls = max_calls;
+        self
+    }
+
+    /// Set the base weight charged once for every extrinsic, which `payment_queryInfo`
+    /// includes in the weight reported for each individual call.
+    ///
+    /// Defaults to the substrate `ExtrinsicBaseWeight`; use the chain's
+    /// `System::BlockWeights` constant to be exact.
+    pub fn set_base_extrinsic_weight(mut self, weight: Weight) -> Self {
+        self.base_extrinsic_weight = weight;
+        self
+    }
+}
+
+/// Packs `calls` into as few `Utility::batch_all` extrinsics as possible, preserving their
+/// order, such that each stays under the given limits.
+///
+/// The weight of each call is estimated with `payment_queryInfo`, using an extrinsic signed by
+/// `signer`. The returned extrinsics are ready to be signed and submitted sequentially.
+///
+/// Fails if a single call exceeds the limits on its own.
+pub async fn pack_calls<'client, T, C>(
+    client: &'client Client<T>,
+    calls: Vec<C>,
+    signer: &(dyn Signer<T> + Send + Sync),
+    limits: &BatchLimits,
+) -> Result<Vec<SubmittableExtrinsic<'client, T, BatchAll>>, Error>
+where
+    T: Config + ExtrinsicExtraData<T>,
+    C: Call + Send + Sync,
+    <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+{
+    let pallet = client.metadata()?.pallet(C::PALLET)?;
+    let mut weighed = Vec::new();
+    for call in calls {
+        let encoded = pallet.encode_call(&call)?;
+        let extrinsic = SubmittableExtrinsic::new(client, call)
+            .create_signed(signer, Default::default())
+            .await?;
+        let info = client.rpc().payment_query_info(&extrinsic, None).await?;
+        weighed.push((encoded, info.weight));
+    }
+
+    Ok(pack(weighed, limits)?
+        .into_iter()
+        .map(|calls| SubmittableExtrinsic::new(client, BatchAll { calls }))
+        .collect())
+}
+
+/// Packs encoded calls, each with the weight reported for an extrinsic of its own, into
+/// batches staying under `limits`, see [`pack_calls`].
+fn pack(
+    calls: Vec<(Encoded, Weight)>,
+    limits: &BatchLimits,
+) -> Result<Vec<Vec<Encoded>>, Error> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut batch_weight = limits.base_extrinsic_weight;
+    let mut batch_length = 0;
+
+    for (index, (encoded, reported_weight)) in calls.into_iter().enumerate() {
+        let weight = reported_weight.saturating_sub(limits.base_extrinsic_weight);
+        let length = encoded.0.len();
+
+        if !limits
+            .base_extrinsic_weight
+            .saturating_add(weight)
+            .all_lte(limits.max_weight)
+            || length > limits.max_length
+            || limits.max_calls == 0
+        {
+            return Err(Error::Other(format!(
+                "Call {} (weight {:?}, length {}) exceeds the batch limits on its own",
+                index, reported_weight, length
+            )))
+        }
+        if !batch_weight
+            .saturating_add(weight)
+            .all_lte(limits.max_weight)
+            || batch_length + length > limits.max_length
+            || batch.len() == limits.max_calls
+        {
+            batches.push(std::mem::take(&mut batch));
+            batch_weight = limits.base_extrinsic_weight;
+            batch_length = 0;
+        }
+        batch.push(encoded);
+        batch_weight = batch_weight.saturating_add(weight);
+        batch_length += length;
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    Ok(batches)
+}
+
+/// Wraps `calls` into a single `Utility::force_batch` extrinsic, ready to be signed and
+/// submitted.
+///
+/// Use [`item_outcomes`] on the events of the extrinsic to find out which calls failed.
+pub fn force_batch<'client, T, C>(
+    client: &'client Client<T>,
+    calls: Vec<C>,
+) -> Result<SubmittableExtrinsic<'client, T, ForceBatch>, Error>
+where
+    T: Config + ExtrinsicExtraData<T>,
+    C: Call + Send + Sync,
+{
+    let pallet = client.metadata()?.pallet(C::PALLET)?;
+    let calls = calls
+        .iter()
+        .map(|call| pallet.encode_call(call))
+        .collect::<Result<_, _>>()?;
+    Ok(SubmittableExtrinsic::new(client, ForceBatch { calls }))
+}
+
+/// Returns the outcome of every call of a batch from the `ItemCompleted` and `ItemFailed`
+/// events of its extrinsic, in the order of the calls.
+///
+/// The errors of failed calls are decoded with `metadata`, see
+/// [`RuntimeError::from_dispatch`]. Events emitted by the calls themselves are skipped.
+pub fn item_outcomes<T: Config>(
+    metadata: &Metadata,
+    events: &TransactionEvents<T>,
+) -> Result<Vec<Result<(), RuntimeError>>, Error> {
+    let mut outcomes = Vec::new();
+    for event in events.as_slice() {
+        if event.as_event::<ItemCompleted>()?.is_some() {
+            outcomes.push(Ok(()));
+        } else if let Some(failed) = event.as_event::<ItemFailed>()? {
+            outcomes.push(Err(RuntimeError::from_dispatch(metadata, failed.error)?));
+        }
+    }
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Weight = Weight::from_ref_time(10);
+
+    /// A call of `length` bytes, reported to weigh `weight` on top of the base weight.
+    fn call(length: usize, weight: u64) -> (Encoded, Weight) {
+        (
+            Encoded(vec![length as u8; length]),
+            BASE.saturating_add(Weight::from_ref_time(weight)),
+        )
+    }
+
+    fn limits(max_weight: u64) -> BatchLimits {
+        BatchLimits::new(Weight::from_ref_time(max_weight))
+            .set_base_extrinsic_weight(BASE)
+    }
+
+    fn lengths(batches: &[Vec<Encoded>]) -> Vec<Vec<usize>> {
+        batches
+            .iter()
+            .map(|batch| batch.iter().map(|call| call.0.len()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn no_calls_make_no_batches() {
+        assert!(pack(vec![], &limits(100)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn calls_at_exactly_the_limits_fit() {
+        let batches = pack(
+            vec![call(2, 40), call(3, 50)],
+            &limits(100).set_max_length(5),
+        )
+        .unwrap();
+        assert_eq!(lengths(&batches), vec![vec![2, 3]]);
+    }
+
+    #[test]
+    fn a_call_over_the_limits_on_its_own_is_an_error() {
+        let heavy = pack(vec![call(1, 10), call(2, 91)], &limits(100));
+        assert!(matches!(heavy, Err(Error::Other(msg)) if msg.starts_with("Call 1 ")));
+
+        let long = pack(vec![call(6, 10)], &limits(100).set_max_length(5));
+        assert!(matches!(long, Err(Error::Other(_))));
+    }
+
+    #[test]
+    fn the_weight_limit_starts_a_new_batch() {
+        let batches =
+            pack(vec![call(1, 50), call(2, 40), call(3, 10)], &limits(100)).unwrap();
+        assert_eq!(lengths(&batches), vec![vec![1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn the_length_limit_starts_a_new_batch() {
+        let batches = pack(
+            vec![call(3, 1), call(3, 1), call(2, 1)],
+            &limits(100).set_max_length(5),
+        )
+        .unwrap();
+        assert_eq!(lengths(&batches), vec![vec![3], vec![3, 2]]);
+    }
+
+    #[test]
+    fn the_call_count_limit_starts_a_new_batch() {
+        let calls = (1..=5).map(|length| call(length, 1)).collect();
+        let batches = pack(calls, &limits(100).set_max_calls(2)).unwrap();
+        assert_eq!(lengths(&batches), vec![vec![1, 2], vec![3, 4], vec![5]]);
+    }
+}
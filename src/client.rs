@@ -14,34 +14,152 @@
 // You should have received a copy of the GNU General Public License
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
-use futures::future;
-use sp_runtime::traits::Hash;
+use codec::{
+    Decode,
+    Encode,
+};
+use core::fmt::Debug;
+use futures::{
+    future,
+    stream,
+    FutureExt,
+    Stream,
+};
+use sp_core::{
+    crypto::Ss58Codec,
+    Bytes,
+};
 pub use sp_runtime::traits::SignedExtension;
+use sp_runtime::{
+    generic::Era,
+    traits::{
+        Hash,
+        Header,
+        UniqueSaturatedFrom,
+        UniqueSaturatedInto,
+    },
+    transaction_validity::{
+        TransactionSource,
+        TransactionValidity,
+    },
+    FixedPointNumber,
+    FixedU128,
+};
 pub use sp_version::RuntimeVersion;
 
 use crate::{
-    error::Error,
-    events::EventsDecoder,
+    balances::{
+        self,
+        AccountBalance,
+        BalanceLayout,
+        Transferred,
+    },
+    contracts::ContractsClient,
+    dynamic::DynamicApi,
+    error::{
+        Error,
+        TransactionError,
+    },
+    events::{
+        DecodingMode,
+        EventsClient,
+        EventsDecoder,
+    },
     extrinsic::{
         self,
+        decode_extrinsic,
+        DefaultExtra,
+        PlaceholderSigner,
         SignedExtra,
         Signer,
         UncheckedExtrinsic,
     },
+    governance::{
+        GovernancePallet,
+        ReferendumInfo,
+    },
+    intercept::{
+        InterceptedCall,
+        InterceptedExtrinsic,
+        SubmitInterceptor,
+        Verdict,
+    },
+    proof::{
+        self,
+        ExtrinsicInclusionProof,
+    },
     rpc::{
+        OfflineClient,
+        RetryPolicy,
         Rpc,
         RpcClient,
+        RpcLog,
+        RuntimeDispatchInfo,
+        Subscription,
+        SyncState,
         SystemProperties,
     },
+    scheduler::{
+        self,
+        Schedule,
+        ScheduleAfter,
+    },
+    session::{
+        CurrentIndex,
+        SessionIndex,
+        SetKeys,
+    },
+    ss58,
+    staking::{
+        ActiveEra,
+        ActiveEraInfo,
+    },
     storage::StorageClient,
-    transaction::TransactionProgress,
+    subscription::{
+        BestBlockSubscription,
+        CallSubscription,
+    },
+    sudo::Sudo,
+    system::{
+        RemarkWithEvent,
+        Remarked,
+    },
+    timestamp::Now,
+    tip::{
+        BlockLimits,
+        NextFeeMultiplier,
+        PriorityTip,
+    },
+    transaction::{
+        TransactionEvents,
+        TransactionProgress,
+        TransactionStatus,
+    },
+    value::{
+        Composite,
+        Primitive,
+        Value,
+    },
+    weight::BlockResources,
     AccountData,
     Call,
     Config,
+    Encoded,
     ExtrinsicExtraData,
     Metadata,
+    MetadataCache,
+    MetadataError,
+    MetadataProvider,
+    StorageEntry,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
 };
-use std::sync::Arc;
 
 /// ClientBuilder for constructing a Client.
 #[derive(Default)]
@@ -49,6 +167,16 @@ pub struct ClientBuilder {
     url: Option<String>,
     client: Option<RpcClient>,
     page_size: Option<u32>,
+    metadata_optional: bool,
+    track_finalized_head: bool,
+    retry_policy: Option<RetryPolicy>,
+    rpc_logger: Option<Arc<dyn Fn(&RpcLog) + Send + Sync>>,
+    events_decoding_mode: DecodingMode,
+    submit_interceptor: Option<Arc<dyn SubmitInterceptor>>,
+    genesis_hash: Option<Vec<u8>>,
+    subscription_idle_timeout: Option<Duration>,
+    metadata_cache: Option<MetadataCache>,
+    read_at: ReadAt<Vec<u8>>,
 }
 
 impl ClientBuilder {
@@ -58,10 +186,24 @@ impl ClientBuilder {
             url: None,
             client: None,
             page_size: None,
+            metadata_optional: false,
+            track_finalized_head: false,
+            retry_policy: None,
+            rpc_logger: None,
+            events_decoding_mode: DecodingMode::Strict,
+            submit_interceptor: None,
+            genesis_hash: None,
+            subscription_idle_timeout: None,
+            metadata_cache: None,
+            read_at: ReadAt::Best,
         }
     }
 
-    /// Sets the jsonrpsee client.
+    /// Sets the rpc client.
+    ///
+    /// Accepts a jsonrpsee [`WsClient`](jsonrpsee::ws_client::WsClient) or
+    /// [`HttpClient`](jsonrpsee::http_client::HttpClient), or any externally constructed
+    /// transport implementing [`RpcClientT`](crate::RpcClientT) via [`RpcClient::new`].
     pub fn set_client<C: Into<RpcClient>>(mut self, client: C) -> Self {
         self.client = Some(client.into());
         self
@@ -74,8 +216,104 @@ impl ClientBuilder {
     }
 
     /// Set the page size.
+    ///
+    /// A page size of `0` is treated as `1`, since empty pages would end iterations.
     pub fn set_page_size(mut self, size: u32) -> Self {
-        self.page_size = Some(size);
+        self.page_size = Some(size.max(1));
+        self
+    }
+
+    /// Do not fail building the client if the node metadata cannot be fetched or decoded.
+    ///
+    /// This allows connecting to legacy nodes which do not expose V14 metadata. The resulting
+    /// client supports raw RPC operations (e.g. block subscriptions, runtime version and raw
+    /// storage reads), whereas typed operations return [`MetadataError::Unavailable`].
+    pub fn set_metadata_optional(mut self, optional: bool) -> Self {
+        self.metadata_optional = optional;
+        self
+    }
+
+    /// Subscribe to finalized heads when building the client, so that
+    /// [`Client::finalized_head`] can return the latest one without a round trip to the node.
+    pub fn set_track_finalized_head(mut self, track: bool) -> Self {
+        self.track_finalized_head = track;
+        self
+    }
+
+    /// Retry RPC requests which fail with a transient error, such as requests which time out,
+    /// according to the given policy. Subscriptions are not retried.
+    pub fn set_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Call `logger` with every RPC request made by the client and the raw response of the
+    /// node, see [`RpcClient::with_logger`]. Each retry of a request is logged too.
+    pub fn set_rpc_logger<F>(mut self, logger: F) -> Self
+    where
+        F: Fn(&RpcLog) + Send + Sync + 'static,
+    {
+        self.rpc_logger = Some(Arc::new(logger));
+        self
+    }
+
+    /// Set how events which cannot be decoded with the metadata are handled, see
+    /// [`DecodingMode`]. Defaults to [`DecodingMode::Strict`].
+    pub fn set_events_decoding_mode(mut self, mode: DecodingMode) -> Self {
+        self.events_decoding_mode = mode;
+        self
+    }
+
+    /// Set a hook which is called with every extrinsic right before it is submitted, and can
+    /// reject it.
+    pub fn set_submit_interceptor<I: SubmitInterceptor + 'static>(
+        mut self,
+        interceptor: I,
+    ) -> Self {
+        self.submit_interceptor = Some(Arc::new(interceptor));
+        self
+    }
+
+    /// Sign extrinsics with the given genesis hash instead of the one reported by the node, e.g.
+    /// when testing against a fork of a chain.
+    ///
+    /// The hash must decode as the `Hash` type of the [`Config`] the client is built with. A
+    /// warning is logged if it differs from the genesis hash reported by the node, since
+    /// extrinsics signed for another genesis fail with `BadProof`.
+    pub fn set_genesis_hash<H: Encode>(mut self, genesis_hash: H) -> Self {
+        self.genesis_hash = Some(genesis_hash.encode());
+        self
+    }
+
+    /// End subscriptions which receive no notification within `timeout`, so that a node which
+    /// silently stalled is detected rather than appearing quiet.
+    ///
+    /// Applies to all subscriptions made through the client, including event and transaction
+    /// progress subscriptions, which return [`Error::SubscriptionStalled`] when they stall. See
+    /// [`Subscription::with_idle_timeout`](crate::rpc::Subscription::with_idle_timeout).
+    pub fn set_subscription_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.subscription_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the block which storage and event reads default to when they are not given a block
+    /// hash, e.g. [`ReadAt::Finalized`] for reads to be consistent across calls. Defaults to
+    /// [`ReadAt::Best`].
+    pub fn set_read_at<H: Encode>(mut self, read_at: ReadAt<H>) -> Self {
+        self.read_at = match read_at {
+            ReadAt::Best => ReadAt::Best,
+            ReadAt::Finalized => ReadAt::Finalized,
+            ReadAt::At(hash) => ReadAt::At(hash.encode()),
+        };
+        self
+    }
+
+    /// Reuse the metadata cached for the runtime of the node if any, and cache it otherwise.
+    ///
+    /// Share the cache between the builders of clients which connect to the same chains, e.g.
+    /// to reconnect or fail over without fetching and decoding the metadata again.
+    pub fn set_metadata_cache(mut self, cache: MetadataCache) -> Self {
+        self.metadata_cache = Some(cache);
         self
     }
 
@@ -87,40 +325,288 @@ impl ClientBuilder {
             let url = self.url.as_deref().unwrap_or("ws://127.0.0.1:9944");
             RpcClient::try_from_url(url).await?
         };
-        let rpc = Rpc::new(client);
-        let (metadata, genesis_hash, runtime_version, properties) = future::join4(
-            rpc.metadata(),
-            rpc.genesis_hash(),
-            rpc.runtime_version(None),
-            rpc.system_properties(),
-        )
-        .await;
-        let metadata = metadata?;
+        let client = match self.rpc_logger {
+            Some(logger) => client.with_logger(move |log: &RpcLog| logger(log)),
+            None => client,
+        };
+        let client = match self.retry_policy {
+            Some(policy) => client.with_retry_policy(policy),
+            None => client,
+        };
+        let rpc = match self.subscription_idle_timeout {
+            Some(timeout) => Rpc::new(client).with_subscription_idle_timeout(timeout),
+            None => Rpc::new(client),
+        };
+        let (metadata, genesis_hash, runtime_version, properties) =
+            match &self.metadata_cache {
+                Some(cache) => {
+                    let (genesis_hash, runtime_version, properties) = future::join3(
+                        rpc.genesis_hash(),
+                        rpc.runtime_version(None),
+                        rpc.system_properties(),
+                    )
+                    .await;
+                    let metadata = match (&genesis_hash, &runtime_version) {
+                        (Ok(hash), Ok(version)) => {
+                            fetch_cached_metadata(&rpc, cache, hash, version).await
+                        }
+                        _ => rpc.metadata(None).await.map(Arc::new),
+                    };
+                    (metadata, genesis_hash, runtime_version, properties)
+                }
+                None => {
+                    let (metadata, genesis_hash, runtime_version, properties) =
+                        future::join4(
+                            rpc.metadata(None),
+                            rpc.genesis_hash(),
+                            rpc.runtime_version(None),
+                            rpc.system_properties(),
+                        )
+                        .await;
+                    (
+                        metadata.map(Arc::new),
+                        genesis_hash,
+                        runtime_version,
+                        properties,
+                    )
+                }
+            };
+        let metadata = match metadata {
+            Ok(metadata) => Some(metadata),
+            Err(err) if self.metadata_optional => {
+                log::warn!("Failed to fetch metadata, continuing without it: {}", err);
+                None
+            }
+            Err(err) => return Err(err),
+        };
 
-        let events_decoder = EventsDecoder::new(metadata.clone());
+        let events_decoder = metadata.as_ref().map(|metadata| {
+            let mut decoder = EventsDecoder::new(metadata.clone());
+            decoder.set_mode(self.events_decoding_mode);
+            decoder
+        });
+
+        let genesis_hash = match self.genesis_hash {
+            Some(encoded) => {
+                let hash = <T::Hash as codec::DecodeAll>::decode_all(&encoded)?;
+                match genesis_hash {
+                    Ok(chain_hash) if chain_hash != hash => {
+                        log::warn!(
+                            "Overriding the genesis hash {:?} reported by the node with {:?}",
+                            chain_hash,
+                            hash
+                        )
+                    }
+                    Ok(_) => (),
+                    Err(err) => {
+                        log::warn!(
+                            "Failed to fetch the genesis hash, overriding it: {}",
+                            err
+                        )
+                    }
+                }
+                hash
+            }
+            None => genesis_hash?,
+        };
+
+        let read_at = self.read_at.decode::<T>()?;
+
+        let finalized_head = if self.track_finalized_head {
+            let subscription = rpc.subscribe_finalized_blocks().await?;
+            let latest = rpc.finalized_head().await?;
+            Some(Arc::new(Mutex::new(FinalizedHead {
+                subscription: Some(subscription),
+                latest,
+            })))
+        } else {
+            None
+        };
+
+        let runtime_version = runtime_version?;
+        let metadata_provider = MetadataProvider::new(rpc.clone());
+        if let Some(metadata) = &metadata {
+            metadata_provider.insert(runtime_version.spec_version, metadata.clone());
+        }
 
         Ok(Client {
             rpc,
-            genesis_hash: genesis_hash?,
-            metadata: Arc::new(metadata),
+            genesis_hash,
+            metadata,
+            metadata_provider,
             events_decoder,
             properties: properties.unwrap_or_else(|_| Default::default()),
-            runtime_version: runtime_version?,
+            runtime_version,
+            iter_page_size: self.page_size.unwrap_or(10),
+            finalized_head,
+            submit_interceptor: self.submit_interceptor,
+            read_at,
+        })
+    }
+
+    /// Creates a Client which does not connect to a node, from the metadata, genesis hash and
+    /// runtime version of the chain, e.g. cached from an earlier connection.
+    ///
+    /// Extrinsics can be constructed, encoded and signed, given a signer with an explicit
+    /// nonce, and events and storage values decoded, whereas every RPC request fails with an
+    /// error naming the method. The URL, rpc client and other connection options of the
+    /// builder are ignored.
+    pub fn build_offline<T: Config>(
+        self,
+        metadata: Metadata,
+        genesis_hash: T::Hash,
+        runtime_version: RuntimeVersion,
+    ) -> Client<T> {
+        let metadata = Arc::new(metadata);
+        let mut events_decoder = EventsDecoder::new(metadata.clone());
+        events_decoder.set_mode(self.events_decoding_mode);
+        let rpc = Rpc::new(RpcClient::new(OfflineClient));
+        let metadata_provider = MetadataProvider::new(rpc.clone());
+        metadata_provider.insert(runtime_version.spec_version, metadata.clone());
+        Client {
+            rpc,
+            genesis_hash,
+            metadata: Some(metadata),
+            metadata_provider,
+            events_decoder: Some(events_decoder),
+            properties: Default::default(),
+            runtime_version,
             iter_page_size: self.page_size.unwrap_or(10),
+            finalized_head: None,
+            submit_interceptor: self.submit_interceptor,
+            // Every read fails without a node, whichever block it is made at.
+            read_at: ReadAt::Best,
+        }
+    }
+}
+
+/// The block which storage and event reads default to when they are not given a block hash,
+/// see [`ClientBuilder::set_read_at`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReadAt<Hash> {
+    /// The best block of the node.
+    Best,
+    /// The latest finalized block. If the client tracks the finalized head, see
+    /// [`ClientBuilder::set_track_finalized_head`], this is the tracked head, otherwise it is
+    /// requested from the node for every read.
+    Finalized,
+    /// The block with the given hash.
+    At(Hash),
+}
+
+impl<Hash> Default for ReadAt<Hash> {
+    fn default() -> Self {
+        Self::Best
+    }
+}
+
+impl ReadAt<Vec<u8>> {
+    fn decode<T: Config>(self) -> Result<ReadAt<T::Hash>, Error> {
+        Ok(match self {
+            Self::Best => ReadAt::Best,
+            Self::Finalized => ReadAt::Finalized,
+            Self::At(encoded) => {
+                ReadAt::At(<T::Hash as codec::DecodeAll>::decode_all(&encoded)?)
+            }
         })
     }
 }
 
+impl<Hash: Copy> ReadAt<Hash> {
+    /// The hash of the block to read at: `at` if given, otherwise the block of this policy,
+    /// where `None` means the best block.
+    ///
+    /// The latest finalized block is taken from `finalized_head` if the client tracks it.
+    pub(crate) async fn resolve<T: Config<Hash = Hash>>(
+        &self,
+        rpc: &Rpc<T>,
+        finalized_head: Option<&Mutex<FinalizedHead<T>>>,
+        at: Option<Hash>,
+    ) -> Result<Option<Hash>, Error> {
+        match (at, self) {
+            (Some(hash), _) => Ok(Some(hash)),
+            (None, Self::At(hash)) => Ok(Some(*hash)),
+            (None, Self::Best) => Ok(None),
+            (None, Self::Finalized) => {
+                Ok(Some(latest_finalized_head(rpc, finalized_head).await?))
+            }
+        }
+    }
+}
+
 /// Client to interface with a substrate node.
 #[derive(Clone)]
 pub struct Client<T: Config> {
     rpc: Rpc<T>,
     genesis_hash: T::Hash,
-    metadata: Arc<Metadata>,
-    events_decoder: EventsDecoder<T>,
+    metadata: Option<Arc<Metadata>>,
+    metadata_provider: MetadataProvider<T>,
+    events_decoder: Option<EventsDecoder<T>>,
     properties: SystemProperties,
     runtime_version: RuntimeVersion,
     iter_page_size: u32,
+    finalized_head: Option<Arc<Mutex<FinalizedHead<T>>>>,
+    submit_interceptor: Option<Arc<dyn SubmitInterceptor>>,
+    read_at: ReadAt<T::Hash>,
+}
+
+/// The latest finalized head, kept up to date from a finalized heads subscription.
+pub(crate) struct FinalizedHead<T: Config> {
+    subscription: Option<Subscription<T::Header>>,
+    latest: T::Hash,
+}
+
+impl<T: Config> FinalizedHead<T> {
+    /// Apply the notifications received since the last call and return the latest head, or
+    /// `None` if the subscription has ended.
+    fn latest(&mut self) -> Option<T::Hash> {
+        let subscription = self.subscription.as_mut()?;
+        loop {
+            match subscription.next().now_or_never() {
+                // No more notifications are ready.
+                None => return Some(self.latest),
+                Some(Ok(Some(header))) => self.latest = header.hash(),
+                Some(_) => {
+                    self.subscription = None;
+                    return None
+                }
+            }
+        }
+    }
+}
+
+/// Returns the hash of the latest finalized block, taken from `finalized_head` if the client
+/// tracks it, see [`Client::finalized_head`], otherwise requested from the node.
+async fn latest_finalized_head<T: Config>(
+    rpc: &Rpc<T>,
+    finalized_head: Option<&Mutex<FinalizedHead<T>>>,
+) -> Result<T::Hash, Error> {
+    let finalized_head = match finalized_head {
+        Some(finalized_head) => finalized_head,
+        None => return rpc.finalized_head().await,
+    };
+    let latest = finalized_head
+        .lock()
+        .expect("finalized head lock poisoned")
+        .latest();
+    if let Some(hash) = latest {
+        return Ok(hash)
+    }
+
+    log::warn!("Finalized heads subscription ended, resubscribing");
+    let subscription = match rpc.subscribe_finalized_blocks().await {
+        Ok(subscription) => subscription,
+        Err(err) => {
+            log::warn!("Failed to resubscribe to finalized heads: {:?}", err);
+            return rpc.finalized_head().await
+        }
+    };
+    // Look up the head after subscribing, so that no later head is missed.
+    let latest = rpc.finalized_head().await?;
+    let mut finalized_head = finalized_head.lock().expect("finalized head lock poisoned");
+    finalized_head.subscription = Some(subscription);
+    finalized_head.latest = latest;
+    Ok(latest)
 }
 
 impl<T: Config> std::fmt::Debug for Client<T> {
@@ -129,10 +615,14 @@ impl<T: Config> std::fmt::Debug for Client<T> {
             .field("rpc", &"<Rpc>")
             .field("genesis_hash", &self.genesis_hash)
             .field("metadata", &"<Metadata>")
+            .field("metadata_provider", &self.metadata_provider)
             .field("events_decoder", &"<EventsDecoder>")
             .field("properties", &self.properties)
             .field("runtime_version", &self.runtime_version.to_string())
             .field("iter_page_size", &self.iter_page_size)
+            .field("track_finalized_head", &self.finalized_head.is_some())
+            .field("submit_interceptor", &self.submit_interceptor.is_some())
+            .field("read_at", &self.read_at)
             .finish()
     }
 }
@@ -143,23 +633,565 @@ impl<T: Config> Client<T> {
         &self.genesis_hash
     }
 
+    /// Returns the block which reads default to, see [`ClientBuilder::set_read_at`].
+    pub fn read_at(&self) -> ReadAt<T::Hash> {
+        self.read_at
+    }
+
     /// Returns the chain metadata.
-    pub fn metadata(&self) -> &Metadata {
-        &self.metadata
+    ///
+    /// Returns [`MetadataError::Unavailable`] if the client was built without metadata, see
+    /// [`ClientBuilder::set_metadata_optional`].
+    pub fn metadata(&self) -> Result<&Metadata, MetadataError> {
+        self.metadata.as_deref().ok_or(MetadataError::Unavailable)
+    }
+
+    /// Returns the provider of the metadata of the runtime at any block, which is shared by
+    /// the clones of this client.
+    ///
+    /// Events fetched by block hash, dynamic storage reads and constants at a block hash are
+    /// decoded with the metadata it provides, so that they can be read across runtime
+    /// upgrades.
+    pub fn metadata_provider(&self) -> &MetadataProvider<T> {
+        &self.metadata_provider
     }
 
-    /// Returns the properties defined in the chain spec as a JSON object.
+    /// Returns the runtime version, as of the block with hash `at` or, by default, the runtime
+    /// version the client was built with.
+    pub async fn runtime_version(
+        &self,
+        at: Option<T::Hash>,
+    ) -> Result<RuntimeVersion, Error> {
+        match at {
+            Some(_) => self.rpc.runtime_version(at).await,
+            None => Ok(self.runtime_version.clone()),
+        }
+    }
+
+    /// Fetch and decode the value of a pallet constant.
+    ///
+    /// Constants may change across runtime upgrades, so given a block hash `at` the constant is
+    /// resolved using the metadata of the runtime at that block, see
+    /// [`Client::metadata_provider`].
+    pub async fn constant<C: Decode>(
+        &self,
+        pallet: &str,
+        name: &'static str,
+        at: Option<T::Hash>,
+    ) -> Result<C, Error> {
+        let historic = match at {
+            Some(hash) => Some(self.metadata_provider.at(hash).await?),
+            None => None,
+        };
+        let metadata = match &historic {
+            Some(metadata) => &**metadata,
+            None => self.metadata()?,
+        };
+        let constant = metadata.pallet(pallet)?.constant(name)?;
+        Ok(C::decode(&mut &constant.value[..])?)
+    }
+
+    /// Returns the properties defined in the chain spec.
     ///
     /// # Note
     ///
     /// Many chains use this to define common properties such as `token_decimals` and `token_symbol`
-    /// required for UIs, but this is merely a convention. It is up to the library user to
-    /// deserialize the JSON into the appropriate type or otherwise extract the properties defined
-    /// in the target chain's spec.
+    /// required for UIs, but this is merely a convention. Properties which are not defined by the
+    /// target chain's spec are left empty.
     pub fn properties(&self) -> &SystemProperties {
         &self.properties
     }
 
+    /// Returns the SS58 address format prefix of the chain.
+    ///
+    /// This is taken from the chain spec properties, falling back to the `System::SS58Prefix`
+    /// constant and finally to the generic substrate prefix `42`.
+    pub fn ss58_prefix(&self) -> u16 {
+        if let Some(prefix) = self.properties.ss58_format {
+            return prefix
+        }
+        self.metadata()
+            .ok()
+            .and_then(|metadata| metadata.pallet("System").ok())
+            .and_then(|pallet| pallet.constant("SS58Prefix").ok())
+            .and_then(|constant| u16::decode(&mut &constant.value[..]).ok())
+            .unwrap_or(42)
+    }
+
+    /// Format an account as an SS58 address using the chain's prefix.
+    pub fn format_account(&self, account: &T::AccountId) -> String
+    where
+        T::AccountId: Ss58Codec,
+    {
+        ss58::format_account(account, self.ss58_prefix())
+    }
+
+    /// Parse an SS58 address, checking that it was encoded with the chain's prefix.
+    pub fn parse_account(&self, address: &str) -> Result<T::AccountId, Error>
+    where
+        T::AccountId: Ss58Codec,
+    {
+        ss58::parse_account(address, self.ss58_prefix())
+    }
+
+    /// Call a runtime API function, e.g. `TransactionPaymentApi_query_info`, encoding the
+    /// arguments and decoding the result.
+    ///
+    /// Multiple arguments are passed as a tuple, which is encoded as their concatenation.
+    ///
+    /// # Note
+    ///
+    /// V14 metadata does not describe the runtime APIs, so the function name and the
+    /// argument and return types are not checked against the runtime.
+    pub async fn runtime_api_call<A: Encode, R: Decode>(
+        &self,
+        function: &str,
+        args: A,
+        at: Option<T::Hash>,
+    ) -> Result<R, Error> {
+        let bytes = self
+            .rpc
+            .state_call(function, args.encode().into(), at)
+            .await?;
+        Ok(R::decode(&mut &bytes[..])?)
+    }
+
+    /// Fetch how far the node has synced with the network.
+    pub async fn sync_state(&self) -> Result<SyncState, Error> {
+        self.rpc.system_sync_state().await
+    }
+
+    /// Returns `true` if the node is not major syncing and has caught up with the highest
+    /// block it knows of.
+    pub async fn is_synced(&self) -> Result<bool, Error> {
+        let (health, state) =
+            future::try_join(self.rpc.system_health(), self.sync_state()).await?;
+        let caught_up = state
+            .highest_block
+            .map_or(true, |highest| state.current_block >= highest);
+        Ok(!health.is_syncing && caught_up)
+    }
+
+    /// Returns the hash of the latest finalized block.
+    ///
+    /// If the client was built with [`ClientBuilder::set_track_finalized_head`] this is taken
+    /// from the finalized heads subscription, otherwise it is requested from the node.
+    ///
+    /// The notifications of the subscription are only applied when this is called, so the node
+    /// may end the subscription if they pile up in the meantime. It is then renewed, falling
+    /// back to requesting the head from the node if that fails.
+    pub async fn finalized_head(&self) -> Result<T::Hash, Error> {
+        latest_finalized_head(&self.rpc, self.finalized_head.as_deref()).await
+    }
+
+    /// The hash of the block to read at: `at` if given, otherwise the block reads default
+    /// to, where `None` means the best block.
+    pub(crate) async fn resolve_read_at(
+        &self,
+        at: Option<T::Hash>,
+    ) -> Result<Option<T::Hash>, Error> {
+        self.read_at
+            .resolve(&self.rpc, self.finalized_head.as_deref(), at)
+            .await
+    }
+
+    /// Returns `true` if the block with hash `block_hash` is finalized, that is if it is the
+    /// latest finalized block or one of its ancestors.
+    ///
+    /// Blocks unknown to the node, e.g. blocks of abandoned forks which got pruned, are not
+    /// finalized.
+    pub async fn is_finalized(&self, block_hash: T::Hash) -> Result<bool, Error> {
+        let (header, finalized) =
+            future::try_join(self.rpc.header(Some(block_hash)), self.finalized_head())
+                .await?;
+        let number = match header {
+            Some(header) => *header.number(),
+            None => return Ok(false),
+        };
+        let finalized_number = *self
+            .rpc
+            .header(Some(finalized))
+            .await?
+            .ok_or(Error::Transaction(TransactionError::BlockHashNotFound))?
+            .number();
+        if number > finalized_number {
+            return Ok(false)
+        }
+        // Below the finalized head the canonical chain is the finalized chain.
+        let number: u64 = number.unique_saturated_into();
+        let canonical = self.rpc.block_hash(Some(number.into())).await?;
+        Ok(canonical == Some(block_hash))
+    }
+
+    /// Wait until the block `number` is finalized, returning its hash.
+    ///
+    /// Returns right away if the block is already finalized. Otherwise the finalized heads
+    /// subscription is driven until a block with at least that number is finalized.
+    pub async fn wait_for_block(&self, number: T::BlockNumber) -> Result<T::Hash, Error> {
+        // Subscribe before reading the finalized head, so that no block is missed in between.
+        let mut finalized = self.rpc.subscribe_finalized_blocks().await?;
+        let head = self.rpc.finalized_head().await?;
+        let mut head_number = *self
+            .rpc
+            .header(Some(head))
+            .await?
+            .ok_or(Error::Transaction(TransactionError::BlockHashNotFound))?
+            .number();
+        while head_number < number {
+            head_number = match finalized.next().await? {
+                Some(header) => *header.number(),
+                None => {
+                    return Err(Error::Other("Finalized heads subscription ended".into()))
+                }
+            };
+        }
+        let number: u64 = number.unique_saturated_into();
+        self.rpc
+            .block_hash(Some(number.into()))
+            .await?
+            .ok_or(Error::Transaction(TransactionError::BlockHashNotFound))
+    }
+
+    /// Subscribe to the extrinsics of finalized blocks calling `pallet::call`, whoever signed
+    /// them.
+    ///
+    /// Fails if the call does not exist in the metadata.
+    pub async fn watch_calls(
+        &self,
+        pallet: &str,
+        call: &str,
+    ) -> Result<CallSubscription<'_, T>, Error> {
+        let metadata = self.metadata()?;
+        if metadata.pallet(pallet)?.call(call).is_none() {
+            return Err(MetadataError::PalletCallNotFound {
+                pallet: pallet.to_string(),
+                call: call.to_string(),
+            }
+            .into())
+        }
+        let subscription = self.rpc.subscribe_finalized_blocks().await?;
+        Ok(CallSubscription::new(
+            self.rpc.clone(),
+            metadata,
+            subscription,
+            pallet,
+            call,
+        ))
+    }
+
+    /// Fetch the balances of `account`, optionally at the block with hash `at`.
+    ///
+    /// Depending on the runtime, the balances are stored either in `System::Account` or in
+    /// `Balances::Account`. The right entry is chosen using the metadata, see
+    /// [`BalanceLayout::from_metadata`].
+    pub async fn account_balance(
+        &self,
+        account: &T::AccountId,
+        at: Option<T::Hash>,
+    ) -> Result<AccountBalance<T>, Error> {
+        let metadata = self.metadata()?;
+        let layout = BalanceLayout::from_metadata(metadata)?;
+        let key = layout.account_key::<T>(metadata, account)?;
+        match self
+            .storage()
+            .fetch_dynamic(layout.pallet(), "Account", vec![key], at)
+            .await?
+        {
+            Some(value) => AccountBalance::from_storage_value(layout, &value),
+            None => Ok(AccountBalance::empty()),
+        }
+    }
+
+    /// Fetch the balances of each of `accounts`, optionally at the block with hash `at`, with
+    /// a single `state_queryStorageAt` request, see [`Client::account_balance`].
+    ///
+    /// The balances are returned in the order of `accounts`. Accounts which do not exist have
+    /// [`AccountBalance::empty`] balances.
+    pub async fn account_balances(
+        &self,
+        accounts: &[T::AccountId],
+        at: Option<T::Hash>,
+    ) -> Result<Vec<AccountBalance<T>>, Error> {
+        if accounts.is_empty() {
+            return Ok(Vec::new())
+        }
+        let metadata = self.metadata()?;
+        let layout = BalanceLayout::from_metadata(metadata)?;
+        let storage = self.storage();
+        let keys = accounts
+            .iter()
+            .map(|account| {
+                let key = layout.account_key::<T>(metadata, account)?;
+                storage.dynamic_key(layout.pallet(), "Account", &[key])
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let at = self.resolve_read_at(at).await?;
+        let mut values = HashMap::new();
+        for change_set in self.rpc.query_storage_at(&keys, at).await? {
+            values.extend(change_set.changes);
+        }
+        keys.iter()
+            .map(|key| {
+                let data = values.get(key).cloned().flatten();
+                match storage.decode_dynamic(layout.pallet(), "Account", data)? {
+                    Some(value) => AccountBalance::from_storage_value(layout, &value),
+                    None => Ok(AccountBalance::empty()),
+                }
+            })
+            .collect()
+    }
+
+    /// Subscribe to the balances of `account`, starting with its current balances.
+    ///
+    /// A new item is yielded each time the balances change, changes to other parts of the
+    /// account such as its nonce are skipped. The stream ends after the first error.
+    pub async fn subscribe_balance(
+        &self,
+        account: &T::AccountId,
+    ) -> Result<impl Stream<Item = Result<AccountBalance<T>, Error>> + '_, Error> {
+        let metadata = self.metadata()?;
+        let layout = BalanceLayout::from_metadata(metadata)?;
+        let key = layout.account_key::<T>(metadata, account)?;
+        let storage = self.storage();
+        let storage_key = storage.dynamic_key(layout.pallet(), "Account", &[key])?;
+        let subscription = self
+            .rpc
+            .subscribe_storage(vec![storage_key.clone()])
+            .await?;
+        let state = (subscription, None::<AccountBalance<T>>);
+        Ok(stream::unfold(Some(state), move |state| {
+            let storage = storage.clone();
+            let storage_key = storage_key.clone();
+            async move {
+                let (mut subscription, last) = state?;
+                loop {
+                    let change_set = match subscription.next().await {
+                        Ok(Some(change_set)) => change_set,
+                        Ok(None) => return None,
+                        Err(err) => return Some((Err(err), None)),
+                    };
+                    let data = match change_set
+                        .changes
+                        .into_iter()
+                        .rev()
+                        .find(|(key, _)| *key == storage_key)
+                    {
+                        Some((_, data)) => data,
+                        None => continue,
+                    };
+                    let balance = storage
+                        .decode_dynamic(layout.pallet(), "Account", data)
+                        .and_then(|value| {
+                            match value {
+                                Some(value) => {
+                                    AccountBalance::from_storage_value(layout, &value)
+                                }
+                                None => Ok(AccountBalance::empty()),
+                            }
+                        });
+                    let balance = match balance {
+                        Ok(balance) => balance,
+                        Err(err) => return Some((Err(err), None)),
+                    };
+                    let unchanged = last.as_ref().map_or(false, |last| {
+                        (last.free, last.reserved, last.misc_frozen, last.fee_frozen)
+                            == (
+                                balance.free,
+                                balance.reserved,
+                                balance.misc_frozen,
+                                balance.fee_frozen,
+                            )
+                    });
+                    if !unchanged {
+                        let next = (subscription, Some(balance.clone()));
+                        return Some((Ok(balance), Some(next)))
+                    }
+                }
+            }
+        }))
+    }
+
+    /// Fetch the active era of the `Staking` pallet, optionally at the block with hash `at`.
+    ///
+    /// Returns `None` if the runtime has no `Staking` pallet, or before the first era.
+    pub async fn active_era(
+        &self,
+        at: Option<T::Hash>,
+    ) -> Result<Option<ActiveEraInfo>, Error> {
+        if self
+            .metadata()?
+            .storage_entry(ActiveEra::PALLET, ActiveEra::STORAGE)
+            .is_err()
+        {
+            return Ok(None)
+        }
+        self.storage().fetch(&ActiveEra, at).await
+    }
+
+    /// Fetch the weight consumed by each dispatch class in a block, from `System::BlockWeight`,
+    /// and the total length of its extrinsics, e.g. to monitor how close blocks come to the
+    /// limits of [`BlockLimits`].
+    ///
+    /// Reads the block with hash `at`, by default the block reads default to, see
+    /// [`ClientBuilder::set_read_at`].
+    pub async fn block_resources(
+        &self,
+        at: Option<T::Hash>,
+    ) -> Result<BlockResources, Error> {
+        let hash = match self.resolve_read_at(at).await? {
+            Some(hash) => hash,
+            None => {
+                self.rpc
+                    .block_hash(None)
+                    .await?
+                    .expect("didn't pass a block number; qed")
+            }
+        };
+        let (block_weight, block) = future::try_join(
+            self.storage()
+                .fetch_dynamic("System", "BlockWeight", vec![], Some(hash)),
+            self.rpc.block(Some(hash)),
+        )
+        .await?;
+        let block_weight = block_weight
+            .ok_or_else(|| Error::Other("System::BlockWeight has no value".into()))?;
+        let block =
+            block.ok_or_else(|| Error::Other(format!("Block {:?} not found", hash)))?;
+        // `System::AllExtrinsicsLen` is cleared when the block is finalized, so the length is
+        // taken from the body of the block instead.
+        let length = block
+            .block
+            .extrinsics
+            .iter()
+            .map(|extrinsic| extrinsic.encoded_size() as u64)
+            .sum();
+        BlockResources::from_block_weight(&block_weight, length)
+    }
+
+    /// Fetch the status of the referendum with the given index, optionally at the block with
+    /// hash `at`, or `None` if there is no such referendum.
+    ///
+    /// Referendums are read from `Referenda::ReferendumInfoFor` or
+    /// `Democracy::ReferendumInfoOf`, whichever the runtime has, see
+    /// [`GovernancePallet::detect`].
+    pub async fn referendum_info(
+        &self,
+        index: u32,
+        at: Option<T::Hash>,
+    ) -> Result<Option<ReferendumInfo>, Error> {
+        let pallet = GovernancePallet::detect(self.metadata()?)
+            .ok_or_else(|| MetadataError::PalletNotFound("Democracy".into()))?;
+        let info = self
+            .storage()
+            .fetch_dynamic(
+                pallet.name(),
+                pallet.storage(),
+                vec![Value::u128(index.into())],
+                at,
+            )
+            .await?;
+        info.map(|info| {
+            ReferendumInfo::from_value(&info).ok_or_else(|| {
+                Error::Other(format!(
+                    "Unexpected {}::{} value: {:?}",
+                    pallet.name(),
+                    pallet.storage(),
+                    info
+                ))
+            })
+        })
+        .transpose()
+    }
+
+    /// Fetch the time at which a block was produced, in milliseconds since the Unix epoch, from
+    /// `Timestamp::Now`, optionally at the block with hash `at`.
+    ///
+    /// Fails if the runtime has no `Timestamp` pallet.
+    pub async fn timestamp(&self, at: Option<T::Hash>) -> Result<u64, Error> {
+        self.metadata()?.storage_entry(Now::PALLET, Now::STORAGE)?;
+        self.storage().fetch_or_default(&Now, at).await
+    }
+
+    /// Fetch the multiplier of the weight fees of the next block from
+    /// `TransactionPayment::NextFeeMultiplier`, optionally at the block with hash `at`.
+    ///
+    /// The multiplier rises while blocks are fuller than targeted, so it tells how congested
+    /// the chain is. Fails if the runtime has no `TransactionPayment` pallet.
+    pub async fn next_fee_multiplier(
+        &self,
+        at: Option<T::Hash>,
+    ) -> Result<FixedU128, Error> {
+        self.metadata()?
+            .storage_entry(NextFeeMultiplier::PALLET, NextFeeMultiplier::STORAGE)?;
+        self.storage()
+            .fetch_or_default(&NextFeeMultiplier, at)
+            .await
+    }
+
+    /// Fetch the index of the current session of the `Session` pallet, optionally at the block
+    /// with hash `at`.
+    ///
+    /// Returns `None` if the runtime has no `Session` pallet.
+    pub async fn current_session(
+        &self,
+        at: Option<T::Hash>,
+    ) -> Result<Option<SessionIndex>, Error> {
+        if self
+            .metadata()?
+            .storage_entry(CurrentIndex::PALLET, CurrentIndex::STORAGE)
+            .is_err()
+        {
+            return Ok(None)
+        }
+        let index = self.storage().fetch_or_default(&CurrentIndex, at).await?;
+        Ok(Some(index))
+    }
+
+    /// Prove that the extrinsic at `index` is included in the block with hash `block_hash`,
+    /// e.g. to relay it to another chain which has verified the header of the block.
+    ///
+    /// The proof is built from the body of the block against its `extrinsics_root`.
+    pub async fn extrinsic_inclusion_proof(
+        &self,
+        block_hash: T::Hash,
+        index: u32,
+    ) -> Result<ExtrinsicInclusionProof<T>, Error> {
+        let block = self
+            .rpc
+            .block(Some(block_hash))
+            .await?
+            .ok_or(Error::Transaction(TransactionError::BlockHashNotFound))?
+            .block;
+        proof::prove_extrinsic::<T>(
+            block_hash,
+            *block.header.extrinsics_root(),
+            &block.extrinsics,
+            index,
+        )
+    }
+
+    /// Subscribe to the events of each block of the best chain, and to the blocks retracted
+    /// from it by reorgs.
+    ///
+    /// Unlike [`Rpc::subscribe_events`], this lets subscribers roll back the events of blocks
+    /// which turn out not to be canonical.
+    pub async fn subscribe_best_with_reorgs(
+        &self,
+    ) -> Result<BestBlockSubscription<'_, T>, Error> {
+        self.events_decoder()?;
+        let subscription = self.rpc.subscribe_blocks().await?;
+        Ok(BestBlockSubscription::new(self, subscription))
+    }
+
+    /// Close the connection to the node, ending any active subscriptions.
+    ///
+    /// Returns once the requests in flight have completed and the connection is closed, see
+    /// [`RpcClient::disconnect`]. This affects all clones of the client; requests made through
+    /// them afterwards fail.
+    pub async fn disconnect(self) -> Result<(), Error> {
+        self.rpc.client.disconnect().await
+    }
+
     /// Returns the rpc client.
     pub fn rpc(&self) -> &Rpc<T> {
         &self.rpc
@@ -167,7 +1199,32 @@ impl<T: Config> Client<T> {
 
     /// Create a client for accessing runtime storage
     pub fn storage(&self) -> StorageClient<T> {
-        StorageClient::new(&self.rpc, &self.metadata, self.iter_page_size)
+        StorageClient::new(&self.rpc, self.metadata.as_deref(), self.iter_page_size)
+            .with_read_at(self.read_at)
+            .with_metadata_provider(&self.metadata_provider)
+            .with_finalized_head(self.finalized_head.as_deref())
+    }
+
+    /// Create a client for fetching the events emitted in a block.
+    pub fn events(&self) -> EventsClient<T> {
+        EventsClient::new(self)
+    }
+
+    /// Create a client for building calls, fetching storage and decoding events by the names
+    /// given in the runtime metadata, without types generated by the `subxt` macro.
+    pub fn dynamic(&self) -> DynamicApi<T> {
+        DynamicApi::new(self)
+    }
+
+    /// Create a client for dry-running contract calls and instantiations.
+    ///
+    /// The dry-runs fail with [`MetadataError::PalletNotFound`] if the runtime has no
+    /// `Contracts` pallet.
+    pub fn contracts(&self) -> ContractsClient<T>
+    where
+        T::AccountId: serde::Serialize,
+    {
+        ContractsClient::new(&self.rpc, self.metadata().ok())
     }
 
     /// Convert the client to a runtime api wrapper for custom runtime access.
@@ -179,15 +1236,387 @@ impl<T: Config> Client<T> {
     }
 
     /// Returns the events decoder.
-    pub fn events_decoder(&self) -> &EventsDecoder<T> {
-        &self.events_decoder
+    pub fn events_decoder(&self) -> Result<&EventsDecoder<T>, MetadataError> {
+        self.events_decoder
+            .as_ref()
+            .ok_or(MetadataError::Unavailable)
+    }
+
+    /// Pass `extrinsic` to the submit interceptor, if any, failing if it is rejected.
+    fn intercept_submission<E: Encode>(&self, extrinsic: &E) -> Result<(), Error> {
+        let interceptor = match &self.submit_interceptor {
+            Some(interceptor) => interceptor,
+            None => return Ok(()),
+        };
+        let encoded = extrinsic.encode();
+        let decoded = self
+            .metadata()
+            .ok()
+            .and_then(|metadata| decode_extrinsic::<T>(metadata, &encoded).ok());
+        let intercepted = InterceptedExtrinsic {
+            encoded: &encoded,
+            call: decoded.as_ref().map(|decoded| {
+                InterceptedCall {
+                    pallet: &decoded.pallet,
+                    name: &decoded.call,
+                    args: &decoded.args,
+                }
+            }),
+        };
+        match interceptor.intercept(&intercepted) {
+            Verdict::Allow => Ok(()),
+            Verdict::Reject(reason) => Err(Error::SubmissionRejected(reason)),
+        }
+    }
+}
+
+impl<T> Client<T>
+where
+    T: Config + ExtrinsicExtraData<T>,
+    T::Address: Send + Sync,
+{
+    /// Transfer `amount` to `to`, waiting for the transfer to be finalized.
+    ///
+    /// Returns the `Balances::Transfer` event, or the runtime error if the transfer failed,
+    /// e.g. because of an insufficient balance or an amount below the existential deposit.
+    pub async fn transfer(
+        &self,
+        to: T::AccountId,
+        amount: T::Balance,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<Transferred<T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let call = balances::Transfer::<T>::new(to, amount);
+        self.submit_transfer(call, signer).await
+    }
+
+    /// Like [`Client::transfer`], but fails rather than reaping the sender's account if its
+    /// balance would drop below the existential deposit.
+    pub async fn transfer_keep_alive(
+        &self,
+        to: T::AccountId,
+        amount: T::Balance,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<Transferred<T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let call = balances::TransferKeepAlive::<T>::new(to, amount);
+        self.submit_transfer(call, signer).await
+    }
+
+    async fn submit_transfer<C: Call + Send + Sync>(
+        &self,
+        call: C,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<Transferred<T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        SubmittableExtrinsic::new(self, call)
+            .sign_and_submit_then_watch(signer)
+            .await?
+            .wait_for_finalized_success()
+            .await?
+            .find_first_event::<Transferred<T>>()?
+            .ok_or_else(|| Error::Other("No Balances::Transfer event found".into()))
+    }
+}
+
+impl<T> Client<T>
+where
+    T: Config + ExtrinsicExtraData<T>,
+{
+    /// Put `remark` on chain with `System::remark_with_event`, waiting for it to be finalized.
+    ///
+    /// Returns the `System::Remarked` event, after checking that it holds the hash of `remark`
+    /// and the account of `signer`.
+    pub async fn remark_with_event(
+        &self,
+        remark: Vec<u8>,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<Remarked<T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let hash = T::Hashing::hash(&remark);
+        let event = SubmittableExtrinsic::new(self, RemarkWithEvent { remark })
+            .sign_and_submit_then_watch(signer)
+            .await?
+            .wait_for_finalized_success()
+            .await?
+            .find_first_event::<Remarked<T>>()?
+            .ok_or_else(|| Error::Other("No System::Remarked event found".into()))?;
+        if event.hash != hash || &event.sender != signer.account_id() {
+            return Err(Error::Other(format!(
+                "System::Remarked event (sender {:?}, hash {:?}) does not match the remark",
+                event.sender, event.hash
+            )))
+        }
+        Ok(event)
+    }
+
+    /// Set the session keys of the validator controlled by `signer` with `Session::set_keys`,
+    /// waiting for it to be finalized.
+    ///
+    /// `keys` are the SCALE encoded session keys of the runtime, as returned by
+    /// [`Rpc::rotate_keys`], and are submitted with an empty ownership proof. The `Session`
+    /// pallet emits no event of its own for this, so the events of the extrinsic are returned
+    /// as the confirmation, including `System::ExtrinsicSuccess`.
+    pub async fn set_session_keys(
+        &self,
+        keys: Bytes,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<TransactionEvents<T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let call = SetKeys {
+            keys: Encoded(keys.0),
+            proof: Vec::new(),
+        };
+        SubmittableExtrinsic::new(self, call)
+            .sign_and_submit_then_watch(signer)
+            .await?
+            .wait_for_finalized_success()
+            .await
+    }
+
+    /// Generate new session keys in the keystore of the node with [`Rpc::rotate_keys`] and
+    /// set them as the session keys of the validator controlled by `signer`, see
+    /// [`Client::set_session_keys`].
+    ///
+    /// Returns the new session keys along with the events of the extrinsic setting them.
+    pub async fn rotate_session_keys(
+        &self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<(Bytes, TransactionEvents<T>), Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let keys = self.rpc.rotate_keys().await?;
+        let events = self.set_session_keys(keys.clone(), signer).await?;
+        Ok((keys, events))
+    }
+
+    /// Sign and submit a call by the names of its pallet and function, encoding `args` as the
+    /// call arguments described by the metadata, e.g. for chains whose types are only known at
+    /// runtime.
+    ///
+    /// Returns [`Error::InvalidCallArgs`] if the call does not exist, or if `args` do not
+    /// match its arguments in number or type.
+    pub async fn submit_dynamic(
+        &self,
+        pallet: &str,
+        call: &str,
+        args: Vec<Value>,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<TransactionProgress<'_, T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        self.dynamic()
+            .tx(pallet, call, &args)?
+            .sign_and_submit_then_watch(signer)
+            .await
+    }
+
+    /// Sign an already encoded call with `additional_params`, using the runtime version,
+    /// genesis hash and metadata of the client and the nonce given by [`signing_nonce`].
+    pub(crate) async fn create_signed_encoded(
+        &self,
+        call: Encoded,
+        signer: &(dyn Signer<T> + Send + Sync),
+        additional_params: <T::Extra as SignedExtra<T>>::Parameters,
+    ) -> Result<UncheckedExtrinsic<T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let nonce = signing_nonce(self, signer, &additional_params).await?;
+        extrinsic::create_signed(
+            &self.runtime_version,
+            self.genesis_hash,
+            self.metadata().ok(),
+            nonce,
+            call,
+            signer,
+            additional_params,
+        )
+        .await
+    }
+
+    /// Submit a signed extrinsic and watch its progress, explaining why it was rejected if
+    /// the node refuses it.
+    pub(crate) async fn submit_and_watch_signed(
+        &self,
+        extrinsic: &UncheckedExtrinsic<T>,
+    ) -> Result<TransactionProgress<'_, T>, Error> {
+        self.intercept_submission(extrinsic)?;
+        let ext_hash = T::Hashing::hash_of(extrinsic);
+        let sub = match self.rpc().watch_extrinsic(extrinsic).await {
+            Ok(sub) => sub,
+            Err(err) => return Err(explain_rejection(self, extrinsic, err).await),
+        };
+        Ok(TransactionProgress::new(sub, self, ext_hash))
+    }
+
+    /// Submit a signed extrinsic without watching its progress, explaining why it was
+    /// rejected if the node refuses it.
+    pub(crate) async fn submit_signed(
+        &self,
+        extrinsic: &UncheckedExtrinsic<T>,
+    ) -> Result<T::Hash, Error> {
+        self.intercept_submission(extrinsic)?;
+        match self.rpc().submit_extrinsic(extrinsic).await {
+            Ok(hash) => Ok(hash),
+            Err(err) => Err(explain_rejection(self, extrinsic, err).await),
+        }
     }
 }
 
 /// A constructed call ready to be signed and submitted.
-pub struct SubmittableExtrinsic<'client, T: Config, C> {
+pub struct SubmittableExtrinsic<'client, T: Config + ExtrinsicExtraData<T>, C> {
     client: &'client Client<T>,
     call: C,
+    additional_params: <T::Extra as SignedExtra<T>>::Parameters,
+}
+
+impl<'client, T, C> SubmittableExtrinsic<'client, T, C>
+where
+    T: Config + Debug + Eq + ExtrinsicExtraData<T, Extra = DefaultExtra<T>>,
+{
+    /// Include a tip for the block author, to gain additional priority in the transaction queue.
+    pub fn tip(mut self, tip: T::Balance) -> Self {
+        self.additional_params = self.additional_params.tip(tip);
+        self
+    }
+
+    /// Sign with `nonce` rather than the nonce of the signer or the next nonce of its account,
+    /// e.g. to replace a transaction stuck in the pool at that nonce with one paying a higher
+    /// tip.
+    ///
+    /// This sets the nonce of the parameters, see
+    /// [`DefaultExtraParams::nonce`](crate::DefaultExtraParams::nonce), so parameters set
+    /// afterwards with [`SubmittableExtrinsic::with_params`] replace it. Without a nonce in
+    /// the parameters, the nonce of the signer is used if it has one, see [`Signer::nonce`],
+    /// and otherwise the next nonce of its account is fetched from the chain.
+    pub fn nonce(mut self, nonce: T::Index) -> Self {
+        self.additional_params = self.additional_params.nonce(nonce);
+        self
+    }
+
+    /// Set the mortality of the transaction, and the hash of the block it is anchored to.
+    ///
+    /// See [`DefaultExtraParams::era`](crate::DefaultExtraParams::era).
+    pub fn era(mut self, era: Era, checkpoint: T::Hash) -> Self {
+        self.additional_params = self.additional_params.era(era, checkpoint);
+        self
+    }
+
+    /// Include a tip of `base_tip` scaled by the next fee multiplier, see
+    /// [`Client::next_fee_multiplier`], so that the tip keeps up with the fees as the chain
+    /// gets congested.
+    pub async fn tip_scaled_by_fee_multiplier(
+        self,
+        base_tip: T::Balance,
+    ) -> Result<Self, Error> {
+        let multiplier = self.client.next_fee_multiplier(None).await?;
+        let base_tip: u128 = base_tip.unique_saturated_into();
+        let tip = multiplier.saturating_mul_int(base_tip);
+        Ok(self.tip(T::Balance::unique_saturated_from(tip)))
+    }
+
+    /// Set the tip giving the transaction a priority in the transaction queue above the target
+    /// of `priority`.
+    ///
+    /// The tip is computed from the weight, class and length reported by
+    /// [`SubmittableExtrinsic::payment_info`] and the block limits in the metadata, see
+    /// [`PriorityTip::tip`].
+    pub async fn tip_for_priority(
+        self,
+        signer: &(dyn Signer<T> + Send + Sync),
+        priority: PriorityTip,
+    ) -> Result<Self, Error>
+    where
+        C: Call + Send + Sync,
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+    {
+        let metadata = self.client.metadata()?;
+        let mut info = self.payment_info(signer).await?;
+        // Leave room for the compact encoding of a tip of up to 128 bits.
+        info.len += 16;
+        let limits = BlockLimits::from_metadata(metadata, info.class)?;
+        let tip = priority.tip(&limits, &info);
+        Ok(self.tip(T::Balance::unique_saturated_from(tip)))
+    }
+
+    /// Check that the free balance of `signer` covers the estimated fee and tip, plus the
+    /// existential deposit and, for `Balances::transfer` and `Balances::transfer_keep_alive`,
+    /// the transferred value.
+    ///
+    /// Returns [`Error::InsufficientFunds`] if it does not, so that transactions which are
+    /// sure to fail are not submitted. Only the fee estimate and balances are queried.
+    pub async fn check_funds(
+        self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<Self, Error>
+    where
+        C: Call + Send + Sync,
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+    {
+        let (info, balance, existential_deposit) = future::try_join3(
+            self.payment_info(signer),
+            self.client.account_balance(signer.account_id(), None),
+            self.client
+                .constant::<T::Balance>("Balances", "ExistentialDeposit", None),
+        )
+        .await?;
+        let to_u128 = |balance: T::Balance| -> u128 { balance.unique_saturated_into() };
+        let required = info
+            .partial_fee
+            .saturating_add(to_u128(self.additional_params.get_tip()))
+            .saturating_add(to_u128(existential_deposit))
+            .saturating_add(self.transferred_value()?);
+        let available = to_u128(balance.free);
+        if available < required {
+            return Err(Error::InsufficientFunds {
+                required,
+                available,
+            })
+        }
+        Ok(self)
+    }
+
+    /// The value transferred by a `Balances` transfer call, or zero for any other call.
+    fn transferred_value(&self) -> Result<u128, Error>
+    where
+        C: Call,
+    {
+        if C::PALLET != "Balances"
+            || !matches!(C::FUNCTION, "transfer" | "transfer_keep_alive")
+        {
+            return Ok(0)
+        }
+        let metadata = self.client.metadata()?;
+        let pallet = metadata.pallet(C::PALLET)?;
+        let call = pallet
+            .call(C::FUNCTION)
+            .ok_or(MetadataError::CallNotFound(C::FUNCTION))?;
+        let args =
+            Composite::decode(metadata, call.fields(), &mut &self.call.encode()[..])?;
+        match args.field("value") {
+            Some(Value::Primitive(Primitive::U128(value))) => Ok(*value),
+            _ => {
+                Err(Error::Other(format!(
+                    "{}::{} has no value",
+                    C::PALLET,
+                    C::FUNCTION
+                )))
+            }
+        }
+    }
 }
 
 impl<'client, T, C> SubmittableExtrinsic<'client, T, C>
@@ -197,47 +1626,239 @@ where
 {
     /// Create a new [`SubmittableExtrinsic`].
     pub fn new(client: &'client Client<T>, call: C) -> Self {
-        Self { client, call }
+        Self {
+            client,
+            call,
+            additional_params: Default::default(),
+        }
+    }
+
+    /// Set the additional parameters used to construct the signed extra, replacing any which
+    /// were previously set.
+    pub fn with_params(
+        mut self,
+        additional_params: <T::Extra as SignedExtra<T>>::Parameters,
+    ) -> Self {
+        self.additional_params = additional_params;
+        self
+    }
+
+    /// Returns the additional parameters used to construct the signed extra.
+    pub(crate) fn additional_params(&self) -> &<T::Extra as SignedExtra<T>>::Parameters {
+        &self.additional_params
+    }
+
+    /// Wrap the call in `Sudo::sudo`, so that it is dispatched with `Root` origin when signed
+    /// by the sudo key.
+    ///
+    /// Use [`crate::TransactionInBlock::wait_for_sudo_success`] to check the result of the
+    /// wrapped call.
+    pub fn as_sudo(self) -> Result<SubmittableExtrinsic<'client, T, Sudo>, Error> {
+        let call = self.encode_call()?;
+        Ok(SubmittableExtrinsic {
+            client: self.client,
+            call: Sudo { call },
+            additional_params: self.additional_params,
+        })
+    }
+
+    /// Wrap the call in `Scheduler::schedule`, so that it is dispatched at block `when`. With
+    /// `maybe_periodic` set to `Some((period, count))` it is dispatched again every `period`
+    /// blocks, `count` times in total.
+    ///
+    /// Scheduling usually requires `Root` origin, so the result is then typically wrapped
+    /// with [`SubmittableExtrinsic::as_sudo`]. The address of the task is given by the
+    /// [`scheduler::Scheduled`] event of the extrinsic.
+    pub fn schedule(
+        self,
+        when: T::BlockNumber,
+        maybe_periodic: Option<(T::BlockNumber, u32)>,
+        priority: u8,
+    ) -> Result<SubmittableExtrinsic<'client, T, Schedule<T>>, Error> {
+        let call = self.encode_scheduled_call(Schedule::<T>::FUNCTION)?;
+        Ok(SubmittableExtrinsic {
+            client: self.client,
+            call: Schedule {
+                when,
+                maybe_periodic,
+                priority,
+                call,
+            },
+            additional_params: self.additional_params,
+        })
+    }
+
+    /// Wrap the call in `Scheduler::schedule_after`, so that it is dispatched `after` blocks
+    /// from the block the extrinsic is included in, see [`SubmittableExtrinsic::schedule`].
+    pub fn schedule_after(
+        self,
+        after: T::BlockNumber,
+        maybe_periodic: Option<(T::BlockNumber, u32)>,
+        priority: u8,
+    ) -> Result<SubmittableExtrinsic<'client, T, ScheduleAfter<T>>, Error> {
+        let call = self.encode_scheduled_call(ScheduleAfter::<T>::FUNCTION)?;
+        Ok(SubmittableExtrinsic {
+            client: self.client,
+            call: ScheduleAfter {
+                after,
+                maybe_periodic,
+                priority,
+                call,
+            },
+            additional_params: self.additional_params,
+        })
+    }
+
+    fn encode_scheduled_call(&self, function: &'static str) -> Result<Encoded, Error> {
+        let call = self.encode_call()?;
+        scheduler::encode_scheduled_call(self.client.metadata()?, function, call)
+    }
+
+    /// Encode the call, including the indices of its pallet and function.
+    fn encode_call(&self) -> Result<Encoded, Error> {
+        let call = self
+            .client
+            .metadata()?
+            .pallet(C::PALLET)
+            .and_then(|pallet| pallet.encode_call(&self.call))?;
+        Ok(call)
     }
 
     /// Creates and signs an extrinsic and submits it to the chain.
     ///
     /// Returns a [`TransactionProgress`], which can be used to track the status of the transaction
-    /// and obtain details about it, once it has made it into a block.
+    /// and obtain details about it, once it has made it into a block. Use
+    /// [`SubmittableExtrinsic::sign_and_submit`] instead to broadcast the extrinsic without
+    /// subscribing to its progress.
     pub async fn sign_and_submit_then_watch(
-        self,
+        mut self,
         signer: &(dyn Signer<T> + Send + Sync),
     ) -> Result<TransactionProgress<'client, T>, Error>
     where
         <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
     {
         // Sign the call data to create our extrinsic.
-        let extrinsic = self.create_signed(signer, Default::default()).await?;
-        // Get a hash of the extrinsic (we'll need this later).
-        let ext_hash = T::Hashing::hash_of(&extrinsic);
+        let additional_params = std::mem::take(&mut self.additional_params);
+        let extrinsic = self.create_signed(signer, additional_params).await?;
         // Submit and watch for transaction progress.
-        let sub = self.client.rpc().watch_extrinsic(extrinsic).await?;
+        self.client.submit_and_watch_signed(&extrinsic).await
+    }
 
-        Ok(TransactionProgress::new(sub, self.client, ext_hash))
+    /// Creates and signs an extrinsic and submits it to the chain, like
+    /// [`SubmittableExtrinsic::sign_and_submit_then_watch`].
+    ///
+    /// Returns the hash of the extrinsic right away, along with a stream of its statuses, see
+    /// [`TransactionProgress::into_stream`].
+    pub async fn sign_and_submit_watch(
+        self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<
+        (
+            T::Hash,
+            impl Stream<Item = Result<TransactionStatus<'client, T>, Error>> + 'client,
+        ),
+        Error,
+    >
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let progress = self.sign_and_submit_then_watch(signer).await?;
+        Ok((progress.extrinsic_hash(), progress.into_stream()))
     }
 
     /// Creates and signs an extrinsic and submits to the chain for block inclusion.
     ///
-    /// Returns `Ok` with the extrinsic hash if it is valid extrinsic.
+    /// Returns `Ok` with the extrinsic hash if it is valid extrinsic. This uses a single
+    /// `author_submitExtrinsic` request, so is cheaper than
+    /// [`SubmittableExtrinsic::sign_and_submit_then_watch`] when the outcome is not needed.
     ///
     /// # Note
     ///
     /// Success does not mean the extrinsic has been included in the block, just that it is valid
     /// and has been included in the transaction pool.
     pub async fn sign_and_submit(
-        self,
+        mut self,
         signer: &(dyn Signer<T> + Send + Sync),
     ) -> Result<T::Hash, Error>
     where
         <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
     {
-        let extrinsic = self.create_signed(signer, Default::default()).await?;
-        self.client.rpc().submit_extrinsic(extrinsic).await
+        let additional_params = std::mem::take(&mut self.additional_params);
+        let extrinsic = self.create_signed(signer, additional_params).await?;
+        self.client.submit_signed(&extrinsic).await
+    }
+
+    /// Creates and signs an extrinsic and estimates its fee, without submitting it.
+    ///
+    /// Returns the weight and dispatch class of the call, the inclusion fee not including the
+    /// tip, and the length of the signed extrinsic.
+    pub async fn payment_info(
+        &self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<RuntimeDispatchInfo, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+        <T::Extra as SignedExtra<T>>::Parameters: Clone,
+    {
+        let extrinsic = self
+            .create_signed(signer, self.additional_params.clone())
+            .await?;
+        self.client.rpc().payment_query_info(&extrinsic, None).await
+    }
+
+    /// Estimates the fee of the extrinsic signed by `account_id`, without its key, e.g. to show
+    /// the fee before asking for a signature.
+    ///
+    /// The extrinsic is given an all zero placeholder signature, which `payment_queryInfo` does
+    /// not check, so the estimate is that of [`SubmittableExtrinsic::payment_info`] as long as
+    /// the placeholder has the length of the real signature.
+    ///
+    /// # Note
+    ///
+    /// With a `MultiSignature` the placeholder is an `Ed25519` signature, which has the length
+    /// of an `Sr25519` one but is one byte shorter than an `Ecdsa` one. The fee of extrinsics
+    /// signed with `Ecdsa` keys is therefore slightly underestimated; use
+    /// [`SubmittableExtrinsic::payment_info`] with the signer instead for an exact fee.
+    pub async fn estimate_fee(
+        &self,
+        account_id: &T::AccountId,
+    ) -> Result<RuntimeDispatchInfo, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+        <T::Extra as SignedExtra<T>>::Parameters: Clone,
+        T::Signature: Decode,
+    {
+        let signer = PlaceholderSigner::<T>::new(account_id.clone());
+        self.payment_info(&signer).await
+    }
+
+    /// Creates the payload `signer` would sign to create the extrinsic, without signing it, e.g.
+    /// to show it for review before signing with a hardware wallet.
+    ///
+    /// The payload is the SCALE encoding of the call, the signed extra and the additional
+    /// signed data, as built from the parameters set with
+    /// [`SubmittableExtrinsic::with_params`]. Like the signature, payloads longer than 256 bytes
+    /// are replaced by their `blake2_256` hash.
+    pub async fn signer_payload(
+        &self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<Vec<u8>, Error>
+    where
+        <T::Extra as SignedExtra<T>>::Parameters: Clone,
+    {
+        let account_nonce =
+            signing_nonce(self.client, signer, &self.additional_params).await?;
+        let call = self.encode_call()?;
+
+        let payload = extrinsic::create_signed_payload::<T>(
+            &self.client.runtime_version,
+            self.client.genesis_hash,
+            self.client.metadata().ok(),
+            account_nonce,
+            call,
+            self.additional_params.clone(),
+        )?;
+        Ok(payload.encode())
     }
 
     /// Creates a signed extrinsic.
@@ -249,35 +1870,90 @@ where
     where
         <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
     {
-        let account_nonce = if let Some(nonce) = signer.nonce() {
-            nonce
-        } else {
-            let account_storage_entry =
-                <<T as ExtrinsicExtraData<T>>::AccountData as AccountData<T>>::storage_entry(signer.account_id().clone());
-            let account_data = self
-                .client
-                .storage()
-                .fetch_or_default(&account_storage_entry, None)
-                .await?;
-            <<T as ExtrinsicExtraData<T>>::AccountData as AccountData<T>>::nonce(
-                &account_data,
-            )
-        };
-        let call = self
-            .client
-            .metadata()
-            .pallet(C::PALLET)
-            .and_then(|pallet| pallet.encode_call(&self.call))?;
+        let call = self.encode_call()?;
+        self.client
+            .create_signed_encoded(call, signer, additional_params)
+            .await
+    }
+}
 
-        let signed = extrinsic::create_signed(
-            &self.client.runtime_version,
-            self.client.genesis_hash,
-            account_nonce,
-            call,
-            signer,
-            additional_params,
-        )
+/// The nonce to sign with, in order of precedence:
+///
+/// 1. the nonce set in `additional_params`, see [`SignedExtra::explicit_nonce`], e.g. with
+///    [`SubmittableExtrinsic::nonce`],
+/// 2. the nonce of the signer, see [`Signer::nonce`],
+/// 3. the next nonce of the account of the signer, fetched from the chain.
+pub(crate) async fn signing_nonce<T: Config + ExtrinsicExtraData<T>>(
+    client: &Client<T>,
+    signer: &(dyn Signer<T> + Send + Sync),
+    additional_params: &<T::Extra as SignedExtra<T>>::Parameters,
+) -> Result<T::Index, Error> {
+    match <T::Extra as SignedExtra<T>>::explicit_nonce(additional_params) {
+        Some(nonce) => Ok(nonce),
+        None => account_nonce(client, signer).await,
+    }
+}
+
+pub(crate) async fn account_nonce<T: Config + ExtrinsicExtraData<T>>(
+    client: &Client<T>,
+    signer: &(dyn Signer<T> + Send + Sync),
+) -> Result<T::Index, Error> {
+    if let Some(nonce) = signer.nonce() {
+        return Ok(nonce)
+    }
+    let account_storage_entry =
+        <<T as ExtrinsicExtraData<T>>::AccountData as AccountData<T>>::storage_entry(
+            signer.account_id().clone(),
+        );
+    let account_data = client
+        .storage()
+        .fetch_or_default(&account_storage_entry, None)
         .await?;
-        Ok(signed)
+    Ok(<<T as ExtrinsicExtraData<T>>::AccountData as AccountData<
+        T,
+    >>::nonce(&account_data))
+}
+
+/// Ask the runtime why the node refused to accept `extrinsic`, so that invalid transactions
+/// (e.g. with an expired era or a bad signature) surface as [`Error::Invalid`] rather than as an
+/// opaque rpc error. Returns `err` unchanged if the runtime considers the extrinsic valid.
+async fn explain_rejection<T: Config, E: Encode>(
+    client: &Client<T>,
+    extrinsic: &E,
+    err: Error,
+) -> Error {
+    if !matches!(err, Error::Rpc(_)) {
+        return err
+    }
+    let best_hash = match client.rpc().block_hash(None).await {
+        Ok(Some(hash)) => hash,
+        _ => return err,
+    };
+    let validity: Result<TransactionValidity, Error> = client
+        .runtime_api_call(
+            "TaggedTransactionQueue_validate_transaction",
+            (TransactionSource::External, extrinsic, best_hash),
+            Some(best_hash),
+        )
+        .await;
+    match validity {
+        Ok(Err(invalid)) => invalid.into(),
+        _ => err,
+    }
+}
+
+/// The metadata of the runtime `version`, from `cache` if it is cached there.
+async fn fetch_cached_metadata<T: Config>(
+    rpc: &Rpc<T>,
+    cache: &MetadataCache,
+    genesis_hash: &T::Hash,
+    version: &RuntimeVersion,
+) -> Result<Arc<Metadata>, Error> {
+    let genesis_hash = genesis_hash.encode();
+    if let Some(metadata) = cache.get(&genesis_hash, version.spec_version) {
+        return Ok(metadata)
     }
+    let metadata = Arc::new(rpc.metadata(None).await?);
+    cache.insert(&genesis_hash, version.spec_version, metadata.clone());
+    Ok(metadata)
 }
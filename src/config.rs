@@ -26,6 +26,7 @@ use codec::{
 use core::fmt::Debug;
 use sp_runtime::traits::{
     AtLeast32Bit,
+    AtLeast32BitUnsigned,
     Extrinsic,
     Hash,
     Header,
@@ -46,7 +47,8 @@ pub trait Config: Clone + Sized + Send + Sync + 'static {
         + Default
         + Copy
         + core::hash::Hash
-        + core::str::FromStr;
+        + core::str::FromStr
+        + AtLeast32BitUnsigned;
 
     /// The output of the `Hashing` function.
     type Hash: Parameter
@@ -66,8 +68,21 @@ pub trait Config: Clone + Sized + Send + Sync + 'static {
     /// The user account identifier type for the runtime.
     type AccountId: Parameter + Member;
 
+    /// The balance of an account.
+    type Balance: Parameter
+        + Member
+        + Default
+        + Copy
+        + AtLeast32BitUnsigned
+        + scale_info::TypeInfo;
+
     /// The address type. This instead of `<frame_system::Trait::Lookup as StaticLookup>::Source`.
-    type Address: Codec + Clone + PartialEq;
+    ///
+    /// Accounts are wrapped into addresses with `From`, e.g. as `MultiAddress::Id` for most
+    /// chains, so that calls can take an `AccountId` directly. Chains addressing accounts
+    /// differently, e.g. with `MultiAddress::Address20`, use an address type converting
+    /// accordingly.
+    type Address: Codec + Clone + PartialEq + From<Self::AccountId>;
 
     /// The block header.
     type Header: Parameter
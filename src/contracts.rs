@@ -0,0 +1,297 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for interacting with `pallet-contracts`, independent of the runtime.
+
+use jsonrpsee::types::{
+    to_json_value,
+    JsonValue,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_core::{
+    crypto::UncheckedFrom,
+    Bytes,
+    U256,
+};
+use sp_runtime::traits::{
+    Hash,
+    UniqueSaturatedInto,
+};
+
+use crate::{
+    metadata::{
+        Metadata,
+        MetadataError,
+    },
+    rpc::{
+        NumberOrHex,
+        Rpc,
+    },
+    weight::WeightShape,
+    Config,
+    Error,
+    Weight,
+};
+
+/// The upper bound on the gas limit accepted by the contracts dry-run RPCs: 5 seconds of
+/// execution and the 5 MiB maximum proof size of a block.
+const DRY_RUN_GAS_LIMIT: Weight =
+    Weight::from_parts(5 * 1_000_000_000_000, 5 * 1024 * 1024);
+
+/// The code to instantiate a contract from.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Code<Hash> {
+    /// The wasm blob to upload.
+    Upload(Bytes),
+    /// The hash of code which has already been uploaded.
+    Existing(Hash),
+}
+
+/// Predict the address of a contract instantiated by `deployer` from `code_hash` with `salt`.
+///
+/// This replicates the `DefaultAddressGenerator` of `pallet-contracts`, which hashes the
+/// concatenation of the deploying account, the code hash and the salt. The constructor input
+/// does not contribute to the address.
+pub fn derive_address<T: Config>(
+    deployer: &T::AccountId,
+    code_hash: &T::Hash,
+    salt: &[u8],
+) -> T::AccountId
+where
+    T::AccountId: AsRef<[u8]> + UncheckedFrom<T::Hash>,
+{
+    let buf: Vec<u8> = deployer
+        .as_ref()
+        .iter()
+        .chain(code_hash.as_ref())
+        .chain(salt)
+        .cloned()
+        .collect();
+    UncheckedFrom::unchecked_from(T::Hashing::hash(&buf))
+}
+
+/// The gas used by a dry-run of a contract call or instantiation.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GasEstimate {
+    /// The gas consumed by the execution.
+    pub gas_consumed: Weight,
+    /// The gas limit required for the execution to succeed, which can be higher than
+    /// [`GasEstimate::gas_consumed`] as some gas is refunded at the end of the execution.
+    pub gas_required: Weight,
+}
+
+/// Request for `contracts_call`.
+///
+/// # Note
+///
+/// This is copied from `pallet-contracts-rpc` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallRequest<AccountId> {
+    origin: AccountId,
+    dest: AccountId,
+    value: NumberOrHex,
+    gas_limit: JsonValue,
+    storage_deposit_limit: Option<NumberOrHex>,
+    input_data: Bytes,
+}
+
+/// Request for `contracts_instantiate`.
+///
+/// # Note
+///
+/// This is copied from `pallet-contracts-rpc` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InstantiateRequest<AccountId, Hash> {
+    origin: AccountId,
+    value: NumberOrHex,
+    gas_limit: JsonValue,
+    storage_deposit_limit: Option<NumberOrHex>,
+    code: Code<Hash>,
+    data: Bytes,
+    salt: Bytes,
+}
+
+/// The subset of `pallet_contracts_primitives::ContractResult` needed to estimate gas.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContractResult {
+    gas_consumed: Weight,
+    gas_required: Weight,
+    debug_message: Bytes,
+    result: JsonValue,
+}
+
+impl ContractResult {
+    fn into_estimate(self) -> Result<GasEstimate, Error> {
+        if let Some(err) = self.result.get("Err") {
+            return Err(Error::Other(format!(
+                "Contract dry-run failed: {} {}",
+                err,
+                String::from_utf8_lossy(&self.debug_message)
+            )))
+        }
+        Ok(GasEstimate {
+            gas_consumed: self.gas_consumed,
+            gas_required: self.gas_required,
+        })
+    }
+}
+
+/// The name of `pallet-contracts` in the metadata.
+const PALLET: &str = "Contracts";
+
+/// Client for dry-running contract calls and instantiations.
+pub struct ContractsClient<'a, T: Config> {
+    rpc: &'a Rpc<T>,
+    metadata: Option<&'a Metadata>,
+    weight_shape: WeightShape,
+}
+
+impl<'a, T: Config> ContractsClient<'a, T>
+where
+    T::AccountId: Serialize,
+{
+    /// Create a new [`ContractsClient`].
+    ///
+    /// Gas limits are passed to the node as weights of the shape described by `metadata`. If
+    /// `metadata` lacks the `Contracts` pallet, every dry-run fails with
+    /// [`MetadataError::PalletNotFound`] instead of reaching the node.
+    pub fn new(rpc: &'a Rpc<T>, metadata: Option<&'a Metadata>) -> Self {
+        let weight_shape =
+            metadata.map_or(WeightShape::Scalar, WeightShape::from_metadata);
+        Self {
+            rpc,
+            metadata,
+            weight_shape,
+        }
+    }
+
+    /// Check that the runtime has the `Contracts` pallet, if the metadata is known.
+    fn ensure_pallet(&self) -> Result<(), Error> {
+        match self.metadata {
+            Some(metadata) if !metadata.has_pallet(PALLET) => {
+                Err(MetadataError::PalletNotFound(PALLET.into()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Dry-run a contract call, returning the gas it requires.
+    pub async fn estimate_gas_call(
+        &self,
+        origin: T::AccountId,
+        dest: T::AccountId,
+        value: T::Balance,
+        input_data: Vec<u8>,
+        at: Option<T::Hash>,
+    ) -> Result<GasEstimate, Error> {
+        self.ensure_pallet()?;
+        let request = CallRequest {
+            origin,
+            dest,
+            value: balance::<T>(value),
+            gas_limit: DRY_RUN_GAS_LIMIT.to_json(self.weight_shape),
+            storage_deposit_limit: None,
+            input_data: input_data.into(),
+        };
+        let params = &[to_json_value(request)?, to_json_value(at)?];
+        let result: ContractResult =
+            self.rpc.client.request("contracts_call", params).await?;
+        result.into_estimate()
+    }
+
+    /// Dry-run a contract instantiation, returning the gas it requires.
+    pub async fn estimate_gas_instantiate(
+        &self,
+        origin: T::AccountId,
+        value: T::Balance,
+        code: Code<T::Hash>,
+        data: Vec<u8>,
+        salt: Vec<u8>,
+        at: Option<T::Hash>,
+    ) -> Result<GasEstimate, Error> {
+        self.ensure_pallet()?;
+        let request = InstantiateRequest {
+            origin,
+            value: balance::<T>(value),
+            gas_limit: DRY_RUN_GAS_LIMIT.to_json(self.weight_shape),
+            storage_deposit_limit: None,
+            code,
+            data: data.into(),
+            salt: salt.into(),
+        };
+        let params = &[to_json_value(request)?, to_json_value(at)?];
+        let result: ContractResult = self
+            .rpc
+            .client
+            .request("contracts_instantiate", params)
+            .await?;
+        result.into_estimate()
+    }
+}
+
+fn balance<T: Config>(value: T::Balance) -> NumberOrHex {
+    let value: u128 = value.unique_saturated_into();
+    NumberOrHex::Hex(U256::from(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::{
+            OfflineClient,
+            RpcClient,
+        },
+        test_utils::{
+            empty_metadata,
+            pallet,
+            MetadataBuilder,
+            MockConfig,
+        },
+    };
+
+    #[test]
+    fn missing_pallet_is_reported() {
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(OfflineClient));
+        let metadata = empty_metadata();
+        let contracts = ContractsClient::new(&rpc, Some(&metadata));
+        match contracts.ensure_pallet() {
+            Err(Error::Metadata(MetadataError::PalletNotFound(name))) => {
+                assert_eq!(name, PALLET)
+            }
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn present_pallet_is_accepted() {
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(OfflineClient));
+        let metadata = MetadataBuilder::new()
+            .with_pallet(pallet(PALLET, 8))
+            .build();
+        let contracts = ContractsClient::new(&rpc, Some(&metadata));
+        assert!(contracts.ensure_pallet().is_ok());
+    }
+}
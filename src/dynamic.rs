@@ -0,0 +1,212 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Access to the calls, storage and events of any runtime through its metadata alone, for
+//! tools connecting to chains which no code has been generated for with the `subxt` macro.
+//!
+//! Calls, storage keys and values and event fields are [`Value`]s, checked against and
+//! encoded or decoded as the types described by the metadata type registry.
+
+use futures::Stream;
+use sp_runtime::traits::SignedExtension;
+use std::fmt::Debug;
+
+use crate::{
+    events::DynamicEvent,
+    extrinsic::{
+        DefaultExtra,
+        SignedExtra,
+        Signer,
+        UncheckedExtrinsic,
+    },
+    transaction::{
+        TransactionProgress,
+        TransactionStatus,
+    },
+    value::{
+        self,
+        Value,
+    },
+    Client,
+    Config,
+    Encoded,
+    Error,
+    ExtrinsicExtraData,
+};
+
+/// Builds calls, fetches storage entries and decodes events by the names of their pallet and
+/// item in the runtime metadata, see [`Client::dynamic`].
+pub struct DynamicApi<'a, T: Config> {
+    client: &'a Client<T>,
+}
+
+impl<'a, T: Config> DynamicApi<'a, T> {
+    /// Create a new [`DynamicApi`].
+    pub fn new(client: &'a Client<T>) -> Self {
+        Self { client }
+    }
+
+    /// Fetch a storage entry by the names of its pallet and entry, building the key from
+    /// `keys` and decoding the value using the metadata of the runtime at the block, see
+    /// [`crate::storage::StorageClient::fetch_dynamic`].
+    pub async fn storage(
+        &self,
+        pallet: &str,
+        entry: &str,
+        keys: &[Value],
+        hash: Option<T::Hash>,
+    ) -> Result<Option<Value>, Error> {
+        self.client
+            .storage()
+            .fetch_dynamic(pallet, entry, keys.to_vec(), hash)
+            .await
+    }
+
+    /// Fetch and decode the events emitted in the block with hash `hash` or, by default, in
+    /// the block which reads default to, see [`crate::EventsClient::latest`].
+    pub async fn events(
+        &self,
+        hash: Option<T::Hash>,
+    ) -> Result<Vec<DynamicEvent>, Error> {
+        let events = match hash {
+            Some(hash) => self.client.events().at(hash).await?,
+            None => self.client.events().latest().await?,
+        };
+        events.iter_dynamic().collect()
+    }
+}
+
+impl<'a, T: Config + ExtrinsicExtraData<T>> DynamicApi<'a, T> {
+    /// Build a call by the names of its pallet and function, encoding `args` as the call
+    /// arguments described by the metadata.
+    ///
+    /// Returns [`Error::InvalidCallArgs`] if the call does not exist, or if `args` do not
+    /// match its arguments in number or type.
+    pub fn tx(
+        &self,
+        pallet: &str,
+        call: &str,
+        args: &[Value],
+    ) -> Result<DynamicExtrinsic<'a, T>, Error> {
+        let call = value::encode_call(self.client.metadata()?, pallet, call, args)?;
+        Ok(DynamicExtrinsic {
+            client: self.client,
+            call,
+            additional_params: Default::default(),
+        })
+    }
+}
+
+/// A call built from the runtime metadata, ready to be signed and submitted, see
+/// [`DynamicApi::tx`].
+pub struct DynamicExtrinsic<'a, T: Config + ExtrinsicExtraData<T>> {
+    client: &'a Client<T>,
+    call: Encoded,
+    additional_params: <T::Extra as SignedExtra<T>>::Parameters,
+}
+
+impl<'a, T> DynamicExtrinsic<'a, T>
+where
+    T: Config + Debug + Eq + ExtrinsicExtraData<T, Extra = DefaultExtra<T>>,
+{
+    /// Sign with `nonce` rather than the nonce of the signer or the next nonce of its account,
+    /// see [`crate::SubmittableExtrinsic::nonce`].
+    pub fn nonce(mut self, nonce: T::Index) -> Self {
+        self.additional_params = self.additional_params.nonce(nonce);
+        self
+    }
+}
+
+impl<'a, T: Config + ExtrinsicExtraData<T>> DynamicExtrinsic<'a, T> {
+    /// Returns the encoded call, including the indices of its pallet and function.
+    pub fn call_data(&self) -> &[u8] {
+        &self.call.0
+    }
+
+    /// Set the additional parameters used to construct the signed extra, replacing any which
+    /// were previously set.
+    pub fn with_params(
+        mut self,
+        additional_params: <T::Extra as SignedExtra<T>>::Parameters,
+    ) -> Self {
+        self.additional_params = additional_params;
+        self
+    }
+
+    /// Creates a signed extrinsic.
+    pub async fn create_signed(
+        &self,
+        signer: &(dyn Signer<T> + Send + Sync),
+        additional_params: <T::Extra as SignedExtra<T>>::Parameters,
+    ) -> Result<UncheckedExtrinsic<T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        self.client
+            .create_signed_encoded(self.call.clone(), signer, additional_params)
+            .await
+    }
+
+    /// Creates and signs an extrinsic and submits it to the chain, returning a
+    /// [`TransactionProgress`] to track its status, see
+    /// [`crate::SubmittableExtrinsic::sign_and_submit_then_watch`].
+    pub async fn sign_and_submit_then_watch(
+        mut self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<TransactionProgress<'a, T>, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let additional_params = std::mem::take(&mut self.additional_params);
+        let extrinsic = self.create_signed(signer, additional_params).await?;
+        self.client.submit_and_watch_signed(&extrinsic).await
+    }
+
+    /// Creates and signs an extrinsic and submits it to the chain, returning its hash right
+    /// away along with a stream of all the statuses reported by the node, see
+    /// [`crate::SubmittableExtrinsic::sign_and_submit_watch`].
+    pub async fn sign_and_submit_watch(
+        self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<
+        (
+            T::Hash,
+            impl Stream<Item = Result<TransactionStatus<'a, T>, Error>> + 'a,
+        ),
+        Error,
+    >
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let progress = self.sign_and_submit_then_watch(signer).await?;
+        Ok((progress.extrinsic_hash(), progress.into_stream()))
+    }
+
+    /// Creates and signs an extrinsic and submits it to the chain for block inclusion,
+    /// returning its hash once it is in the transaction pool, see
+    /// [`crate::SubmittableExtrinsic::sign_and_submit`].
+    pub async fn sign_and_submit(
+        mut self,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<T::Hash, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let additional_params = std::mem::take(&mut self.additional_params);
+        let extrinsic = self.create_signed(signer, additional_params).await?;
+        self.client.submit_signed(&extrinsic).await
+    }
+}
@@ -20,14 +20,22 @@ use crate::{
         InvalidMetadataError,
         MetadataError,
     },
+    value::Value,
     Metadata,
 };
 use jsonrpsee::types::Error as RequestError;
+use scale_info::{
+    form::PortableForm,
+    Type,
+};
 use sp_core::crypto::SecretStringError;
 use sp_runtime::{
     transaction_validity::TransactionValidityError,
+    ArithmeticError,
     DispatchError,
+    TokenError,
 };
+use std::time::Duration;
 use thiserror::Error;
 
 /// Error enum.
@@ -42,11 +50,14 @@ pub enum Error {
     /// Rpc error.
     #[error("Rpc error: {0}")]
     Rpc(#[from] RequestError),
+    /// A subscription received no notification within its idle timeout, and was closed.
+    #[error("Subscription stalled: no notification received for {0:?}")]
+    SubscriptionStalled(Duration),
     /// Serde serialization error
     #[error("Serde json error: {0}")]
     Serialization(#[from] serde_json::error::Error),
     /// Secret string error.
-    #[error("Secret String Error")]
+    #[error("Secret string error: {0:?}")]
     SecretString(SecretStringError),
     /// Extrinsic validity error
     #[error("Transaction Validity Error: {0:?}")]
@@ -66,6 +77,49 @@ pub enum Error {
     /// Transaction progress error.
     #[error("Transaction error: {0}")]
     Transaction(#[from] TransactionError),
+    /// Invalid SS58 address.
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+    /// Dynamic call arguments do not match the call signature in the metadata.
+    #[error("Invalid call arguments: {0}")]
+    InvalidCallArgs(String),
+    /// The chain requires a signed extension which is not supported.
+    #[error("Unsupported signed extension {0}")]
+    UnsupportedSignedExtension(String),
+    /// An extrinsic has a format version which cannot be decoded.
+    #[error("Unsupported extrinsic version {0}")]
+    UnsupportedExtrinsicVersion(u8),
+    /// The node serves metadata of a version which cannot be decoded, only V14 is supported.
+    #[error("Unsupported metadata version V{0}, only V14 is supported")]
+    UnsupportedMetadataVersion(u8),
+    /// A [`SubmitInterceptor`](crate::intercept::SubmitInterceptor) rejected the extrinsic.
+    #[error("Submission rejected: {0}")]
+    SubmissionRejected(String),
+    /// Storage proof verification error.
+    #[error("Invalid storage proof: {0}")]
+    InvalidProof(String),
+    /// A value could not be decoded.
+    #[error("{0}")]
+    Decode(#[from] DecodeError),
+    /// A transaction was not finalized within the timeout set with
+    /// [`TransactionProgress::with_timeout`](crate::TransactionProgress::with_timeout).
+    #[error("Timed out watching the transaction, last status: {0:?}")]
+    WatchTimeout(WatchedStatus),
+    /// The signer cannot afford the transaction.
+    #[error("Insufficient funds: {required} required, {available} available")]
+    InsufficientFunds {
+        /// The estimated fee and tip, plus the existential deposit and any transferred value.
+        required: u128,
+        /// The free balance of the signer.
+        available: u128,
+    },
+    /// The node has pruned the state of the block, so it can only be read from an archive
+    /// node.
+    #[error("State of {block} is pruned by the node")]
+    StatePruned {
+        /// The hash of the block, or `best block` if none was given.
+        block: String,
+    },
     /// Other error.
     #[error("Other error: {0}")]
     Other(String),
@@ -116,6 +170,12 @@ pub enum RuntimeError {
     /// Cannot lookup.
     #[error("Cannot lookup some information required to validate the transaction.")]
     CannotLookup,
+    /// An error to do with tokens, e.g. the account has insufficient funds.
+    #[error("Token error: {0:?}")]
+    Token(TokenError),
+    /// An arithmetic error, e.g. an overflow.
+    #[error("Arithmetic error: {0:?}")]
+    Arithmetic(ArithmeticError),
     /// Other error.
     #[error("Other error: {0}")]
     Other(String),
@@ -145,13 +205,72 @@ impl RuntimeError {
             DispatchError::ConsumerRemaining => Ok(Self::ConsumerRemaining),
             DispatchError::NoProviders => Ok(Self::NoProviders),
             DispatchError::TooManyConsumers => Ok(Self::TooManyConsumers),
-            DispatchError::Arithmetic(_math_error) => {
-                Ok(Self::Other("math_error".into()))
-            }
-            DispatchError::Token(_token_error) => Ok(Self::Other("token error".into())),
+            DispatchError::Arithmetic(math_error) => Ok(Self::Arithmetic(math_error)),
+            DispatchError::Token(token_error) => Ok(Self::Token(token_error)),
             DispatchError::Other(msg) => Ok(Self::Other(msg.to_string())),
         }
     }
+
+    /// Converts a `DispatchError` decoded with the metadata into a subxt error, e.g. one of a
+    /// runtime whose `DispatchError` has variants unknown to [`DispatchError`].
+    ///
+    /// Module errors and the variants without fields are converted like
+    /// [`RuntimeError::from_dispatch`] does. Any other variant is returned as
+    /// [`RuntimeError::Other`], named by the path of its variants, e.g.
+    /// `Token::FundsUnavailable`.
+    pub fn from_dispatch_value(
+        metadata: &Metadata,
+        value: &Value,
+    ) -> Result<Self, Error> {
+        let (name, fields) = match value {
+            Value::Variant { name, fields } => (name.as_str(), fields),
+            _ => return Err(Error::Other(format!("Invalid DispatchError: {:?}", value))),
+        };
+        let byte = |field| fields.field(field).and_then(first_byte);
+        match (name, byte("index"), byte("error")) {
+            ("Module", Some(index), Some(error)) => {
+                Self::from_dispatch(
+                    metadata,
+                    DispatchError::Module {
+                        index,
+                        error,
+                        message: None,
+                    },
+                )
+            }
+            ("BadOrigin", ..) => Ok(Self::BadOrigin),
+            ("CannotLookup", ..) => Ok(Self::CannotLookup),
+            ("ConsumerRemaining", ..) => Ok(Self::ConsumerRemaining),
+            ("NoProviders", ..) => Ok(Self::NoProviders),
+            ("TooManyConsumers", ..) => Ok(Self::TooManyConsumers),
+            _ => Ok(Self::Other(variant_path(value))),
+        }
+    }
+}
+
+/// The first byte of an integer or byte array, e.g. the error index of a module error, which
+/// newer runtimes encode as four bytes.
+fn first_byte(value: &Value) -> Option<u8> {
+    match value {
+        Value::Sequence(values) => values.first().and_then(first_byte),
+        Value::Composite(fields) => fields.values().first().and_then(|v| first_byte(v)),
+        value => u8::try_from(value.as_u128()?).ok(),
+    }
+}
+
+/// The names of `value` and of the variants nested in it, e.g. `Arithmetic::Overflow`.
+fn variant_path(value: &Value) -> String {
+    match value {
+        Value::Variant { name, fields } => {
+            match fields.values().as_slice() {
+                [inner @ Value::Variant { .. }] => {
+                    format!("{}::{}", name, variant_path(inner))
+                }
+                _ => name.clone(),
+            }
+        }
+        _ => format!("{:?}", value),
+    }
 }
 
 /// Module error.
@@ -166,6 +285,113 @@ pub struct PalletError {
     pub description: Vec<String>,
 }
 
+/// A value which could not be decoded, and where decoding failed.
+#[derive(Debug, Error)]
+#[error("Failed to decode {}: {error}", decode_location(.type_path, .fields, *.offset))]
+pub struct DecodeError {
+    /// The path of the innermost named type being decoded, e.g.
+    /// `pallet_contracts::storage::RawContractInfo`, or the name of the Rust type being
+    /// decoded. Empty if no type on the way had a path, e.g. for primitives.
+    pub type_path: String,
+    /// The fields leading from the outermost value to the one which failed to decode.
+    /// Unnamed fields and sequence elements are given by their index, enum variants by name.
+    pub fields: Vec<String>,
+    /// The offset of the byte at which decoding failed, from the start of the input.
+    pub offset: usize,
+    /// Why decoding failed.
+    pub error: Box<Error>,
+}
+
+fn decode_location(type_path: &str, fields: &[String], offset: usize) -> String {
+    let mut location = if type_path.is_empty() {
+        "value".to_string()
+    } else {
+        type_path.to_string()
+    };
+    if !fields.is_empty() {
+        location.push_str(&format!(" at field `{}`", fields.join(".")));
+    }
+    location.push_str(&format!(" (byte offset {})", offset));
+    location
+}
+
+impl DecodeError {
+    /// Attribute `err`, raised after `offset` bytes of the input were consumed, to a value of
+    /// type `type_path`, unless it was already attributed to an inner named type.
+    pub(crate) fn within(err: Error, type_path: &str, offset: usize) -> Error {
+        match err {
+            Error::Decode(mut err) => {
+                if err.type_path.is_empty() {
+                    err.type_path = type_path.to_string();
+                }
+                Error::Decode(err)
+            }
+            err @ (Error::Codec(_) | Error::EventsDecoding(_) | Error::Other(_)) => {
+                Error::Decode(DecodeError {
+                    type_path: type_path.to_string(),
+                    fields: Vec::new(),
+                    offset,
+                    error: Box::new(err),
+                })
+            }
+            err => err,
+        }
+    }
+
+    /// Like [`DecodeError::within`], for a type of the metadata type registry.
+    pub(crate) fn within_type(
+        err: Error,
+        ty: &Type<PortableForm>,
+        offset: usize,
+    ) -> Error {
+        Self::within(err, &ty.path().segments().join("::"), offset)
+    }
+
+    /// Record that `err` was raised while decoding the field `name` of a value.
+    pub(crate) fn within_field<S: Into<String>>(err: Error, name: S) -> Error {
+        match err {
+            Error::Decode(mut err) => {
+                err.fields.insert(0, name.into());
+                Error::Decode(err)
+            }
+            err => err,
+        }
+    }
+}
+
+/// The last status of a transaction whose watch timed out, see [`Error::WatchTimeout`].
+///
+/// Block hashes are SCALE encoded, since errors are not generic over the [`crate::Config`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WatchedStatus {
+    /// No status was received.
+    Unknown,
+    /// The transaction is part of the "future" queue.
+    Future,
+    /// The transaction is part of the "ready" queue.
+    Ready,
+    /// The transaction has been broadcast to the given peers.
+    Broadcast(Vec<String>),
+    /// The transaction has been included in the block with the given hash, which was not
+    /// finalized in time.
+    InBlock(Vec<u8>),
+    /// The block with the given hash which the transaction was included in was retracted.
+    Retracted(Vec<u8>),
+    /// The transaction has been replaced in the pool by the transaction with the given hash.
+    Usurped(Vec<u8>),
+    /// The transaction has been dropped from the pool because of the limit.
+    Dropped,
+    /// The transaction is no longer valid in the current state.
+    Invalid,
+}
+
+impl WatchedStatus {
+    /// Returns `true` if the transaction got into a block, but was not finalized in time.
+    pub fn is_in_block(&self) -> bool {
+        matches!(self, Self::InBlock(_))
+    }
+}
+
 /// Transaction error.
 #[derive(Clone, Debug, Eq, Error, PartialEq)]
 pub enum TransactionError {
@@ -177,4 +403,71 @@ pub enum TransactionError {
     /// This is probably because the block was retracted before being finalized.
     #[error("The block containing the transaction can no longer be found (perhaps it was on a non-finalized fork?)")]
     BlockHashNotFound,
+    /// The transaction succeeded but did not emit the expected event.
+    #[error("The transaction did not emit a {pallet}::{event} event")]
+    EventNotFound {
+        /// Name of the pallet emitting the expected event.
+        pallet: &'static str,
+        /// Name of the expected event.
+        event: &'static str,
+    },
+    /// The events of the transaction follow an event which could not be decoded.
+    #[error("The events of the transaction follow undecodable event {pallet_index}:{variant_index}")]
+    UndecodedEvents {
+        /// The index of the pallet of the undecodable event.
+        pallet_index: u8,
+        /// The index of the variant of the undecodable event.
+        variant_index: u8,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::empty_metadata,
+        value::Composite,
+    };
+
+    fn variant(name: &str, fields: Vec<Value>) -> Value {
+        Value::Variant {
+            name: name.to_string(),
+            fields: Composite::Unnamed(fields),
+        }
+    }
+
+    #[test]
+    fn token_and_arithmetic_errors_are_named() {
+        let metadata = empty_metadata();
+        let error = RuntimeError::from_dispatch(
+            &metadata,
+            DispatchError::Token(TokenError::NoFunds),
+        )
+        .unwrap();
+        assert_eq!(error, RuntimeError::Token(TokenError::NoFunds));
+        assert_eq!(error.to_string(), "Token error: NoFunds");
+
+        let error = RuntimeError::from_dispatch(
+            &metadata,
+            DispatchError::Arithmetic(ArithmeticError::Overflow),
+        )
+        .unwrap();
+        assert_eq!(error.to_string(), "Arithmetic error: Overflow");
+    }
+
+    #[test]
+    fn unknown_dispatch_errors_are_named_by_their_variants() {
+        let metadata = empty_metadata();
+        let value = variant("Transactional", vec![variant("LimitReached", vec![])]);
+        assert_eq!(
+            RuntimeError::from_dispatch_value(&metadata, &value).unwrap(),
+            RuntimeError::Other("Transactional::LimitReached".into())
+        );
+        assert_eq!(
+            RuntimeError::from_dispatch_value(&metadata, &variant("BadOrigin", vec![]))
+                .unwrap(),
+            RuntimeError::BadOrigin
+        );
+        assert!(RuntimeError::from_dispatch_value(&metadata, &Value::u128(1)).is_err());
+    }
 }
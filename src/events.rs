@@ -14,21 +14,55 @@
 // You should have received a copy of the GNU General Public License
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
+//! Decoding the events of blocks, see [`EventsClient`] and [`EventsDecoder`].
+//!
+//! The [`prelude`] collects the most used events which are independent of the runtime, to
+//! look up in the events of a transaction without generating code for the runtime.
+
 use codec::{
     Codec,
     Compact,
     Decode,
+    DecodeAll,
     Encode,
     Error as CodecError,
     Input,
 };
-use std::marker::PhantomData;
+use futures::{
+    stream,
+    Stream,
+    StreamExt,
+    TryStreamExt,
+};
+use sp_runtime::traits::{
+    CheckedAdd,
+    Header,
+    One,
+    Saturating,
+    UniqueSaturatedInto,
+};
+use std::{
+    collections::HashMap,
+    marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+};
 
 use crate::{
+    balances,
+    error::DecodeError,
     metadata::{
         EventMetadata,
         MetadataError,
     },
+    rpc::Subscription,
+    scheduler,
+    subscription::SystemEvents,
+    sudo,
+    system,
+    value::Composite,
+    weight::WeightShape,
+    Client,
     Config,
     Error,
     Event,
@@ -36,14 +70,19 @@ use crate::{
     Phase,
 };
 use scale_info::{
+    form::PortableForm,
+    Type,
     TypeDef,
     TypeDefPrimitive,
 };
-use sp_core::Bytes;
+use sp_core::{
+    storage::StorageKey,
+    Bytes,
+};
 
 /// Raw bytes for an Event
-#[derive(Debug)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Clone, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
 pub struct RawEvent {
     /// The name of the pallet from whence the Event originated.
     pub pallet: String,
@@ -55,23 +94,163 @@ pub struct RawEvent {
     pub variant_index: u8,
     /// The raw Event data
     pub data: Bytes,
+    /// The Event exactly as encoded on chain: the pallet and variant indices followed by the
+    /// Event data.
+    pub bytes: Bytes,
 }
 
 impl RawEvent {
     /// Attempt to decode this [`RawEvent`] into a specific event.
+    ///
+    /// The event data has been delimited using the types from the metadata registry, so the
+    /// event type must consume it exactly; any mismatch with the on-chain encoding (e.g. of
+    /// the fields' account types) is returned as an error rather than silently ignored.
     pub fn as_event<E: Event>(&self) -> Result<Option<E>, CodecError> {
         if self.pallet == E::PALLET && self.variant == E::EVENT {
-            Ok(Some(E::decode(&mut &self.data[..])?))
+            Ok(Some(E::decode_all(&self.data[..])?))
         } else {
             Ok(None)
         }
     }
 }
 
+/// The most used events which are independent of the runtime.
+pub mod prelude {
+    pub use super::WellKnownEvent;
+    pub use crate::{
+        balances::Transferred,
+        scheduler::Scheduled,
+        sudo::Sudid,
+        system::{
+            ExtrinsicFailed,
+            ExtrinsicSuccess,
+            KilledAccount,
+            NewAccount,
+            Remarked,
+        },
+    };
+}
+
+/// One of the events of the [`prelude`], see [`WellKnownEvent::from_raw`].
+#[derive(Debug, Eq, PartialEq)]
+pub enum WellKnownEvent<T: Config> {
+    /// A `System::ExtrinsicSuccess` event.
+    ExtrinsicSuccess(system::ExtrinsicSuccess),
+    /// A `System::ExtrinsicFailed` event.
+    ExtrinsicFailed(system::ExtrinsicFailed),
+    /// A `System::NewAccount` event.
+    NewAccount(system::NewAccount<T>),
+    /// A `System::KilledAccount` event.
+    KilledAccount(system::KilledAccount<T>),
+    /// A `System::Remarked` event.
+    Remarked(system::Remarked<T>),
+    /// A `Balances::Transfer` event.
+    Transferred(balances::Transferred<T>),
+    /// A `Sudo::Sudid` event.
+    Sudid(sudo::Sudid),
+    /// A `Scheduler::Scheduled` event.
+    Scheduled(scheduler::Scheduled<T>),
+}
+
+impl<T: Config> WellKnownEvent<T> {
+    /// Decode `raw` if it is one of the events of the [`prelude`], or return `None` if it is
+    /// another event.
+    ///
+    /// The weights of the `System` events are expected to be scalar, use
+    /// [`WellKnownEvent::from_raw_with_shape`] for runtimes with two dimensional weights.
+    pub fn from_raw(raw: &RawEvent) -> Result<Option<Self>, CodecError> {
+        macro_rules! decode_as {
+            ($($variant:ident),*) => {
+                $(
+                    if let Some(event) = raw.as_event()? {
+                        return Ok(Some(Self::$variant(event)))
+                    }
+                )*
+            };
+        }
+        decode_as!(
+            ExtrinsicSuccess,
+            ExtrinsicFailed,
+            NewAccount,
+            KilledAccount,
+            Remarked,
+            Transferred,
+            Sudid,
+            Scheduled
+        );
+        Ok(None)
+    }
+
+    /// Like [`WellKnownEvent::from_raw`], decoding the weights of the `System` events in the
+    /// given shape, see [`WeightShape::from_metadata`].
+    pub fn from_raw_with_shape(
+        raw: &RawEvent,
+        shape: WeightShape,
+    ) -> Result<Option<Self>, CodecError> {
+        if raw.pallet != system::ExtrinsicSuccess::PALLET {
+            return Self::from_raw(raw)
+        }
+        let input = &mut &raw.data[..];
+        let event = if raw.variant == system::ExtrinsicSuccess::EVENT {
+            Self::ExtrinsicSuccess(system::ExtrinsicSuccess::decode_with_shape(
+                input, shape,
+            )?)
+        } else if raw.variant == system::ExtrinsicFailed::EVENT {
+            Self::ExtrinsicFailed(system::ExtrinsicFailed::decode_with_shape(
+                input, shape,
+            )?)
+        } else {
+            return Self::from_raw(raw)
+        };
+        // Like `RawEvent::as_event`, the event must consume its data exactly.
+        if !input.is_empty() {
+            return Err("Input buffer has still data left after decoding!".into())
+        }
+        Ok(Some(event))
+    }
+}
+
+/// How an [`EventsDecoder`] handles an event which cannot be decoded with the metadata, e.g.
+/// because the metadata predates a runtime upgrade.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DecodingMode {
+    /// Fail to decode all of the events.
+    Strict,
+    /// Return the events preceding the undecodable one, followed by an [`EventRecord::Raw`]
+    /// holding the undecodable event and the rest of the events.
+    SkipUnknown,
+}
+
+impl Default for DecodingMode {
+    fn default() -> Self {
+        DecodingMode::Strict
+    }
+}
+
+/// A decoded event, or the remaining bytes of the events from an event which could not be
+/// decoded.
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone))]
+pub enum EventRecord {
+    /// An event delimited using the metadata.
+    Decoded(RawEvent),
+    /// An event which could not be decoded. Since its length is unknown, `bytes` holds the
+    /// data of this event and all of the events following it.
+    Raw {
+        /// The index of the pallet from whence the Event originated.
+        pallet_index: u8,
+        /// The index of the pallet Event variant.
+        variant_index: u8,
+        /// The remaining bytes.
+        bytes: Bytes,
+    },
+}
+
 /// Events decoder.
 #[derive(Debug, Clone)]
 pub struct EventsDecoder<T> {
-    metadata: Metadata,
+    metadata: Arc<Metadata>,
+    mode: DecodingMode,
     marker: PhantomData<T>,
 }
 
@@ -80,18 +259,56 @@ where
     T: Config,
 {
     /// Creates a new `EventsDecoder`.
-    pub fn new(metadata: Metadata) -> Self {
+    pub fn new(metadata: impl Into<Arc<Metadata>>) -> Self {
         Self {
-            metadata,
+            metadata: metadata.into(),
+            mode: Default::default(),
             marker: Default::default(),
         }
     }
 
+    /// Set how events which cannot be decoded are handled.
+    pub fn set_mode(&mut self, mode: DecodingMode) {
+        self.mode = mode;
+    }
+
     /// Decode events.
+    ///
+    /// With [`DecodingMode::SkipUnknown`] the events following an undecodable event are
+    /// dropped; use [`EventsDecoder::decode_event_records`] to retain their bytes.
     pub fn decode_events(
         &self,
         input: &mut &[u8],
     ) -> Result<Vec<(Phase, RawEvent)>, Error> {
+        Ok(self
+            .decode_event_records(input)?
+            .into_iter()
+            .filter_map(|(phase, record)| {
+                match record {
+                    EventRecord::Decoded(event) => Some((phase, event)),
+                    EventRecord::Raw {
+                        pallet_index,
+                        variant_index,
+                        bytes,
+                    } => {
+                        log::warn!(
+                            "Skipping undecodable event {}:{} and the following {} bytes",
+                            pallet_index,
+                            variant_index,
+                            bytes.len()
+                        );
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+
+    /// Decode events, handling an undecodable event according to the [`DecodingMode`].
+    pub fn decode_event_records(
+        &self,
+        input: &mut &[u8],
+    ) -> Result<Vec<(Phase, EventRecord)>, Error> {
         let compact_len = <Compact<u32>>::decode(input)?;
         let len = compact_len.0 as usize;
         log::debug!("decoding {} events", len);
@@ -100,6 +317,7 @@ where
         for _ in 0..len {
             // decode EventRecord
             let phase = Phase::decode(input)?;
+            let event_start = *input;
             let pallet_index = input.read_byte()?;
             let variant_index = input.read_byte()?;
             log::debug!(
@@ -110,27 +328,48 @@ where
             );
             log::debug!("remaining input: {}", hex::encode(&input));
 
-            let event_metadata = self.metadata.event(pallet_index, variant_index)?;
-
+            let remaining = *input;
             let mut event_data = Vec::<u8>::new();
-            let result = self.decode_raw_event(event_metadata, input, &mut event_data);
+            let result = self
+                .metadata
+                .event(pallet_index, variant_index)
+                .map_err(Error::from)
+                .and_then(|event_metadata| {
+                    self.decode_raw_event(event_metadata, input, &mut event_data)?;
+                    Ok(event_metadata)
+                });
             let raw = match result {
-                Ok(()) => {
+                Ok(event_metadata) => {
                     log::debug!("raw bytes: {}", hex::encode(&event_data),);
 
+                    let event_len = event_start.len() - input.len();
                     let event = RawEvent {
                         pallet: event_metadata.pallet().to_string(),
                         pallet_index,
                         variant: event_metadata.event().to_string(),
                         variant_index,
                         data: event_data.into(),
+                        bytes: event_start[..event_len].to_vec().into(),
                     };
 
                     // topics come after the event data in EventRecord
                     let topics = Vec::<T::Hash>::decode(input)?;
                     log::debug!("topics: {:?}", topics);
 
-                    event
+                    EventRecord::Decoded(event)
+                }
+                Err(err) if self.mode == DecodingMode::SkipUnknown => {
+                    log::debug!("Failed to decode event: {}", err);
+                    r.push((
+                        phase,
+                        EventRecord::Raw {
+                            pallet_index,
+                            variant_index,
+                            bytes: remaining.to_vec().into(),
+                        },
+                    ));
+                    *input = &[];
+                    break
                 }
                 Err(err) => return Err(err),
             };
@@ -150,24 +389,47 @@ where
             event_metadata.pallet(),
             event_metadata.event()
         );
-        for arg in event_metadata.variant().fields() {
+        let total = input.len();
+        for (i, arg) in event_metadata.variant().fields().iter().enumerate() {
             let type_id = arg.ty().id();
-            self.decode_type(type_id, input, output)?
+            self.decode_type(type_id, input, output, total)
+                .map_err(|err| {
+                    let name = arg.name().cloned().unwrap_or_else(|| i.to_string());
+                    let event = format!(
+                        "{}::{}",
+                        event_metadata.pallet(),
+                        event_metadata.event()
+                    );
+                    let err = DecodeError::within_field(err, name);
+                    DecodeError::within(err, &event, total - input.len())
+                })?
         }
         Ok(())
     }
 
+    /// Decode a value from `input`, which holds the last bytes of an input of `total` bytes.
     fn decode_type(
         &self,
         type_id: u32,
         input: &mut &[u8],
         output: &mut Vec<u8>,
+        total: usize,
     ) -> Result<(), Error> {
         let ty = self
             .metadata
             .resolve_type(type_id)
             .ok_or(MetadataError::TypeNotFound(type_id))?;
+        self.decode_type_def(ty, input, output, total)
+            .map_err(|err| DecodeError::within_type(err, ty, total - input.len()))
+    }
 
+    fn decode_type_def(
+        &self,
+        ty: &Type<PortableForm>,
+        input: &mut &[u8],
+        output: &mut Vec<u8>,
+        total: usize,
+    ) -> Result<(), Error> {
         fn decode_raw<T: Codec>(
             input: &mut &[u8],
             output: &mut Vec<u8>,
@@ -179,43 +441,59 @@ where
 
         match ty.type_def() {
             TypeDef::Composite(composite) => {
-                for field in composite.fields() {
-                    self.decode_type(field.ty().id(), input, output)?
+                for (i, field) in composite.fields().iter().enumerate() {
+                    self.decode_type(field.ty().id(), input, output, total)
+                        .map_err(|err| {
+                            let name =
+                                field.name().cloned().unwrap_or_else(|| i.to_string());
+                            DecodeError::within_field(err, name)
+                        })?
                 }
                 Ok(())
             }
             TypeDef::Variant(variant) => {
                 let variant_index = u8::decode(input)?;
                 variant_index.encode_to(output);
-                let variant =
-                    variant
-                        .variants()
-                        .get(variant_index as usize)
-                        .ok_or_else(|| {
-                            Error::Other(format!("Variant {} not found", variant_index))
-                        })?;
-                for field in variant.fields() {
-                    self.decode_type(field.ty().id(), input, output)?;
+                // Variant indices need not be contiguous, e.g. the variants of the outer
+                // runtime `Call` and `Event` enums are indexed by pallet index.
+                let variant = variant
+                    .variants()
+                    .iter()
+                    .find(|v| v.index() == variant_index)
+                    .ok_or_else(|| {
+                        Error::Other(format!("Variant {} not found", variant_index))
+                    })?;
+                for (i, field) in variant.fields().iter().enumerate() {
+                    self.decode_type(field.ty().id(), input, output, total)
+                        .map_err(|err| {
+                            let name =
+                                field.name().cloned().unwrap_or_else(|| i.to_string());
+                            DecodeError::within_field(err, name)
+                        })
+                        .map_err(|err| DecodeError::within_field(err, variant.name()))?;
                 }
                 Ok(())
             }
             TypeDef::Sequence(seq) => {
                 let len = <Compact<u32>>::decode(input)?;
                 len.encode_to(output);
-                for _ in 0..len.0 {
-                    self.decode_type(seq.type_param().id(), input, output)?;
+                for i in 0..len.0 {
+                    self.decode_type(seq.type_param().id(), input, output, total)
+                        .map_err(|err| DecodeError::within_field(err, i.to_string()))?;
                 }
                 Ok(())
             }
             TypeDef::Array(arr) => {
-                for _ in 0..arr.len() {
-                    self.decode_type(arr.type_param().id(), input, output)?;
+                for i in 0..arr.len() {
+                    self.decode_type(arr.type_param().id(), input, output, total)
+                        .map_err(|err| DecodeError::within_field(err, i.to_string()))?;
                 }
                 Ok(())
             }
             TypeDef::Tuple(tuple) => {
-                for field in tuple.fields() {
-                    self.decode_type(field.id(), input, output)?;
+                for (i, field) in tuple.fields().iter().enumerate() {
+                    self.decode_type(field.id(), input, output, total)
+                        .map_err(|err| DecodeError::within_field(err, i.to_string()))?;
                 }
                 Ok(())
             }
@@ -253,11 +531,12 @@ where
                     }
                 }
             }
-            TypeDef::Compact(_compact) => {
+            TypeDef::Compact(compact) => {
+                let inner_id = compact.type_param().id();
                 let inner = self
                     .metadata
-                    .resolve_type(type_id)
-                    .ok_or(MetadataError::TypeNotFound(type_id))?;
+                    .resolve_type(inner_id)
+                    .ok_or(MetadataError::TypeNotFound(inner_id))?;
                 let mut decode_compact_primitive = |primitive: &TypeDefPrimitive| {
                     match primitive {
                         TypeDefPrimitive::U8 => decode_raw::<Compact<u8>>(input, output),
@@ -327,6 +606,468 @@ where
     }
 }
 
+/// Client for fetching the events emitted in a block.
+pub struct EventsClient<'a, T: Config> {
+    client: &'a Client<T>,
+}
+
+impl<'a, T: Config> EventsClient<'a, T> {
+    /// Create a new [`EventsClient`].
+    pub fn new(client: &'a Client<T>) -> Self {
+        Self { client }
+    }
+
+    /// Fetch all of the events emitted in the block with the given hash.
+    ///
+    /// The events are decoded with the metadata of the runtime at that block, provided by
+    /// [`Client::metadata_provider`].
+    pub async fn at(&self, hash: T::Hash) -> Result<BlockEvents<T>, Error> {
+        let key = StorageKey::from(SystemEvents::new());
+        let (metadata, raw_events) = futures::future::try_join(
+            self.client.metadata_provider().at(hash),
+            self.client.rpc().storage(&key, Some(hash)),
+        )
+        .await?;
+        let raw_events = raw_events.map(|s| s.0).unwrap_or_else(Vec::new);
+        let events = decoder_for(self.client, metadata.clone())
+            .decode_event_records(&mut &*raw_events)?;
+        Ok(BlockEvents {
+            metadata,
+            block_hash: hash,
+            events,
+        })
+    }
+
+    /// Fetch all of the events emitted in the block which reads default to, the best block
+    /// unless set otherwise with [`crate::ClientBuilder::set_read_at`].
+    pub async fn latest(&self) -> Result<BlockEvents<T>, Error> {
+        let rpc = self.client.rpc();
+        let hash = match self.client.resolve_read_at(None).await? {
+            Some(hash) => hash,
+            None => {
+                rpc.block_hash(None)
+                    .await?
+                    .expect("didn't pass a block number; qed")
+            }
+        };
+        self.at(hash).await
+    }
+}
+
+impl<'a, T: Config> EventsClient<'a, T> {
+    /// Fetch the events of every block from `from` to `to` inclusive, with the default
+    /// [`ScanOptions`].
+    ///
+    /// See [`EventsClient::scan_with_options`].
+    pub fn scan(
+        &self,
+        from: T::BlockNumber,
+        to: T::BlockNumber,
+    ) -> impl Stream<Item = Result<(T::Hash, Vec<(Phase, EventRecord)>), Error>> + 'a
+    {
+        self.scan_with_options(from, to, ScanOptions::default())
+    }
+
+    /// Fetch the events of every block from `from` to `to` inclusive, yielding them in block
+    /// order.
+    ///
+    /// Blocks are fetched in chunks, with up to `concurrency` requests in flight. The events of
+    /// each block are decoded with the metadata of the runtime at that block, so the scan may
+    /// span runtime upgrades. The stream ends after the first error.
+    pub fn scan_with_options(
+        &self,
+        from: T::BlockNumber,
+        to: T::BlockNumber,
+        options: ScanOptions,
+    ) -> impl Stream<Item = Result<(T::Hash, Vec<(Phase, EventRecord)>), Error>> + 'a
+    {
+        let scan = EventScan {
+            client: self.client,
+            next: if from <= to { Some(from) } else { None },
+            to,
+            options,
+            decoders: HashMap::new(),
+        };
+        stream::unfold(Some(scan), |scan| {
+            async move {
+                let mut scan = scan?;
+                let from = scan.next?;
+                let chunk_size = T::BlockNumber::from(scan.options.chunk_size as u32);
+                let to = from.saturating_add(chunk_size - One::one()).min(scan.to);
+                match scan.fetch_chunk(from, to).await {
+                    Ok(blocks) => {
+                        scan.next =
+                            to.checked_add(&One::one()).filter(|next| *next <= scan.to);
+                        Some((Ok(blocks), Some(scan)))
+                    }
+                    Err(err) => Some((Err(err), None)),
+                }
+            }
+        })
+        .flat_map(|chunk| {
+            let blocks = match chunk {
+                Ok(blocks) => blocks.into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            };
+            stream::iter(blocks)
+        })
+    }
+
+    /// Subscribe to the events of finalized blocks, resuming after a checkpoint if `from` is
+    /// given, with the default [`ScanOptions`].
+    ///
+    /// See [`EventsClient::subscribe_finalized_with_options`].
+    pub fn subscribe_finalized(
+        &self,
+        from: Option<T::BlockNumber>,
+    ) -> impl Stream<Item = Result<(T::Hash, Vec<(Phase, EventRecord)>), Error>> + 'a
+    {
+        self.subscribe_finalized_with_options(from, ScanOptions::default())
+    }
+
+    /// Subscribe to the events of finalized blocks, yielding them in block order.
+    ///
+    /// With `from` set, the events of the finalized blocks from `from` on are fetched like
+    /// [`EventsClient::scan_with_options`] does, before those of the blocks finalized since.
+    /// Otherwise the events start with the next block finalization reported by the node.
+    ///
+    /// Either way no block is skipped, even if the node reports the finalization of several
+    /// blocks at once, so an indexer can resume after the last block it handled. If the node
+    /// ends the subscription, e.g. because its notifications piled up during a long catch
+    /// up, it is renewed and the blocks finalized in the meantime are caught up with. The
+    /// stream ends after the first error.
+    pub fn subscribe_finalized_with_options(
+        &self,
+        from: Option<T::BlockNumber>,
+        options: ScanOptions,
+    ) -> impl Stream<Item = Result<(T::Hash, Vec<(Phase, EventRecord)>), Error>> + 'a
+    {
+        stream::once(FinalizedEvents::subscribe(self.client, from, options)).flat_map(
+            |events| {
+                match events {
+                    Ok(events) => events.into_stream().left_stream(),
+                    Err(err) => stream::once(async { Err(err) }).right_stream(),
+                }
+            },
+        )
+    }
+}
+
+/// Options for [`EventsClient::scan_with_options`].
+#[derive(Clone, Copy, Debug)]
+pub struct ScanOptions {
+    concurrency: usize,
+    chunk_size: usize,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            chunk_size: 64,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Creates the default options, fetching chunks of 64 blocks with up to 8 requests in
+    /// flight.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of blocks fetched concurrently. At least one.
+    pub fn set_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set the number of blocks fetched before any of them are yielded. At least one.
+    pub fn set_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, u32::MAX as usize);
+        self
+    }
+}
+
+/// The state of a scan started with [`EventsClient::scan_with_options`].
+struct EventScan<'a, T: Config> {
+    client: &'a Client<T>,
+    next: Option<T::BlockNumber>,
+    to: T::BlockNumber,
+    options: ScanOptions,
+    /// Decoders for the runtimes encountered so far, by spec version.
+    decoders: HashMap<u32, EventsDecoder<T>>,
+}
+
+impl<'a, T: Config> EventScan<'a, T> {
+    async fn fetch_chunk(
+        &mut self,
+        from: T::BlockNumber,
+        to: T::BlockNumber,
+    ) -> Result<Vec<(T::Hash, Vec<(Phase, EventRecord)>)>, Error> {
+        let rpc = self.client.rpc();
+        let key = StorageKey::from(SystemEvents::new());
+        let key = &key;
+        let numbers = std::iter::successors(Some(from), |number| {
+            (*number < to).then(|| *number + One::one())
+        });
+        let fetched: Vec<(T::Hash, u32, Vec<u8>)> = stream::iter(numbers)
+            .map(|number| {
+                async move {
+                    let block_number: u64 = number.unique_saturated_into();
+                    let hash = rpc
+                        .block_hash(Some(block_number.into()))
+                        .await?
+                        .ok_or_else(|| {
+                            Error::Other(format!("Block {:?} not found", number))
+                        })?;
+                    let (version, events) = futures::future::try_join(
+                        rpc.runtime_version(Some(hash)),
+                        rpc.storage(key, Some(hash)),
+                    )
+                    .await?;
+                    let events = events.map(|data| data.0).unwrap_or_default();
+                    Ok::<_, Error>((hash, version.spec_version, events))
+                }
+            })
+            .buffered(self.options.concurrency)
+            .try_collect()
+            .await?;
+
+        let mut blocks = Vec::with_capacity(fetched.len());
+        for (hash, spec_version, events) in fetched {
+            let decoder = self.decoder(spec_version, hash).await?;
+            blocks.push((hash, decoder.decode_event_records(&mut &*events)?));
+        }
+        Ok(blocks)
+    }
+
+    /// Returns the decoder for the runtime with the given spec version, with the metadata
+    /// provided by [`Client::metadata_provider`].
+    async fn decoder(
+        &mut self,
+        spec_version: u32,
+        hash: T::Hash,
+    ) -> Result<&EventsDecoder<T>, Error> {
+        if !self.decoders.contains_key(&spec_version) {
+            let metadata = self
+                .client
+                .metadata_provider()
+                .for_spec_version(spec_version, hash)
+                .await?;
+            self.decoders
+                .insert(spec_version, decoder_for(self.client, metadata));
+        }
+        Ok(&self.decoders[&spec_version])
+    }
+}
+
+/// The state of a subscription started with
+/// [`EventsClient::subscribe_finalized_with_options`].
+struct FinalizedEvents<'a, T: Config> {
+    client: &'a Client<T>,
+    headers: Subscription<T::Header>,
+    /// Whether `headers` was renewed and no block was yielded or finalized since.
+    resubscribed: bool,
+    /// The number of the next block to yield the events of, once known.
+    next: Option<T::BlockNumber>,
+    options: ScanOptions,
+    /// The events of the blocks finalized but not yet yielded.
+    pending: Pin<
+        Box<dyn Stream<Item = Result<(T::Hash, Vec<(Phase, EventRecord)>), Error>> + 'a>,
+    >,
+}
+
+impl<'a, T: Config> FinalizedEvents<'a, T> {
+    async fn subscribe(
+        client: &'a Client<T>,
+        from: Option<T::BlockNumber>,
+        options: ScanOptions,
+    ) -> Result<FinalizedEvents<'a, T>, Error> {
+        // Subscribe before looking up the finalized head, so that no block is missed in
+        // between.
+        let headers = client.rpc().subscribe_finalized_blocks().await?;
+        let mut events = Self {
+            client,
+            headers,
+            resubscribed: false,
+            next: None,
+            options,
+            pending: Box::pin(stream::empty()),
+        };
+        if let Some(from) = from {
+            let finalized = events.finalized_number().await?;
+            events.catch_up(from, finalized);
+            events.next = Some(from.max(finalized.saturating_add(One::one())));
+        }
+        Ok(events)
+    }
+
+    /// Renew the headers subscription after the node ended it, catching up with the blocks
+    /// finalized since the last header.
+    async fn resubscribe(&mut self) -> Result<(), Error> {
+        if self.resubscribed {
+            return Err(Error::Other(
+                "Finalized heads subscription ended right after renewing it".into(),
+            ))
+        }
+        log::warn!("Finalized heads subscription ended, resubscribing");
+        // Subscribe before looking up the finalized head, so that no block is missed in
+        // between.
+        self.headers = self.client.rpc().subscribe_finalized_blocks().await?;
+        self.resubscribed = true;
+        if let Some(next) = self.next {
+            let finalized = self.finalized_number().await?;
+            if finalized >= next {
+                self.catch_up(next, finalized);
+                self.next = Some(finalized.saturating_add(One::one()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Look up the number of the latest finalized block.
+    async fn finalized_number(&self) -> Result<T::BlockNumber, Error> {
+        let hash = self.client.rpc().finalized_head().await?;
+        let header = self
+            .client
+            .rpc()
+            .header(Some(hash))
+            .await?
+            .ok_or_else(|| Error::Other(format!("Block {:?} not found", hash)))?;
+        Ok(*header.number())
+    }
+
+    /// Fetch the events of the blocks from `from` to `to` inclusive.
+    fn catch_up(&mut self, from: T::BlockNumber, to: T::BlockNumber) {
+        self.pending = Box::pin(EventsClient::new(self.client).scan_with_options(
+            from,
+            to,
+            self.options,
+        ));
+    }
+
+    fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<(T::Hash, Vec<(Phase, EventRecord)>), Error>> + 'a
+    {
+        stream::unfold(Some(self), |events| {
+            async move {
+                let mut events = events?;
+                loop {
+                    if let Some(block) = events.pending.next().await {
+                        events.resubscribed = false;
+                        let events = if block.is_ok() { Some(events) } else { None };
+                        return Some((block, events))
+                    }
+                    let header = match events.headers.next().await {
+                        Ok(Some(header)) => header,
+                        Ok(None) => {
+                            match events.resubscribe().await {
+                                Ok(()) => continue,
+                                Err(err) => return Some((Err(err), None)),
+                            }
+                        }
+                        Err(err) => return Some((Err(err), None)),
+                    };
+                    events.resubscribed = false;
+                    let finalized = *header.number();
+                    let from = events.next.unwrap_or(finalized);
+                    if finalized >= from {
+                        events.catch_up(from, finalized);
+                        events.next = Some(finalized.saturating_add(One::one()));
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Returns a decoder using `metadata`, handling undecodable events like the decoder of the
+/// client.
+pub(crate) fn decoder_for<T: Config>(
+    client: &Client<T>,
+    metadata: Arc<Metadata>,
+) -> EventsDecoder<T> {
+    let mode = client
+        .events_decoder()
+        .map_or(DecodingMode::Strict, |decoder| decoder.mode);
+    let mut decoder = EventsDecoder::new(metadata);
+    decoder.set_mode(mode);
+    decoder
+}
+
+/// The events emitted in a block.
+#[derive(Debug)]
+pub struct BlockEvents<T: Config> {
+    metadata: Arc<Metadata>,
+    block_hash: T::Hash,
+    events: Vec<(Phase, EventRecord)>,
+}
+
+impl<T: Config> BlockEvents<T> {
+    /// Return the hash of the block the events were emitted in.
+    pub fn block_hash(&self) -> T::Hash {
+        self.block_hash
+    }
+
+    /// Return the metadata of the runtime the events were decoded with.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Iterate over the event records and the phase in which each was emitted, including
+    /// the bytes of any events which could not be decoded with
+    /// [`DecodingMode::SkipUnknown`].
+    pub fn iter_records(&self) -> impl Iterator<Item = &(Phase, EventRecord)> {
+        self.events.iter()
+    }
+
+    /// Iterate over the raw events and the phase in which each was emitted.
+    pub fn iter_raw(&self) -> impl Iterator<Item = (&Phase, &RawEvent)> {
+        self.events.iter().filter_map(|(phase, record)| {
+            match record {
+                EventRecord::Decoded(raw) => Some((phase, raw)),
+                EventRecord::Raw { .. } => None,
+            }
+        })
+    }
+
+    /// Iterate over the events, decoding the fields of each using the metadata type
+    /// registry. This works for the events of any pallet, including those which no
+    /// types have been generated for.
+    pub fn iter_dynamic(&self) -> impl Iterator<Item = Result<DynamicEvent, Error>> + '_ {
+        self.iter_raw().map(move |(phase, raw)| {
+            let event_metadata =
+                self.metadata.event(raw.pallet_index, raw.variant_index)?;
+            let fields = Composite::decode(
+                &self.metadata,
+                event_metadata.variant().fields(),
+                &mut &raw.data[..],
+            )?;
+            Ok(DynamicEvent {
+                phase: phase.clone(),
+                pallet: raw.pallet.clone(),
+                variant: raw.variant.clone(),
+                fields,
+            })
+        })
+    }
+}
+
+/// An event decoded without compile time knowledge of its type.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DynamicEvent {
+    /// The phase of block execution in which the event was emitted.
+    pub phase: Phase,
+    /// The name of the pallet from whence the event originated.
+    pub pallet: String,
+    /// The name of the pallet event variant.
+    pub variant: String,
+    /// The decoded event fields.
+    pub fields: Composite,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum EventsDecodingError {
     /// Unsupported primitive type
@@ -339,6 +1080,261 @@ pub enum EventsDecodingError {
     InvalidCompactType(String),
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        empty_metadata,
+        pallet,
+        MetadataBuilder,
+        MockConfig,
+    };
+    use frame_metadata::v14::{
+        PalletEventMetadata,
+        PalletMetadata,
+    };
+    use sp_runtime::AccountId32;
+
+    #[derive(Debug, PartialEq, Decode)]
+    struct Transfer {
+        from: AccountId32,
+        to: AccountId32,
+        amount: u128,
+    }
+
+    impl Event for Transfer {
+        const PALLET: &'static str = "Balances";
+        const EVENT: &'static str = "Transfer";
+    }
+
+    #[derive(Debug, PartialEq, Decode)]
+    struct MismatchedTransfer {
+        from: [u8; 32],
+        to: [u8; 32],
+        amount: u64,
+    }
+
+    impl Event for MismatchedTransfer {
+        const PALLET: &'static str = "Balances";
+        const EVENT: &'static str = "Transfer";
+    }
+
+    /// Metadata of a `Test` pallet at index 7, emitting events of type `E`.
+    fn metadata_with_events<E: scale_info::TypeInfo + 'static>() -> Metadata {
+        MetadataBuilder::new()
+            .with_pallet(PalletMetadata {
+                event: Some(PalletEventMetadata {
+                    ty: scale_info::meta_type::<E>(),
+                }),
+                ..pallet("Test", 7)
+            })
+            .build()
+    }
+
+    #[test]
+    fn skip_unknown_returns_undecodable_events_as_raw() {
+        // One `ApplyExtrinsic(0)` event of pallet 9, variant 1.
+        let mut events = Compact(1u32).encode();
+        events.extend((0u8, 0u32).encode());
+        events.extend([9, 1, 42, 43]);
+
+        let mut decoder = EventsDecoder::<MockConfig>::new(empty_metadata());
+        assert!(decoder.decode_event_records(&mut &events[..]).is_err());
+
+        decoder.set_mode(DecodingMode::SkipUnknown);
+        let records = decoder.decode_event_records(&mut &events[..]).unwrap();
+        assert_eq!(
+            records,
+            vec![(
+                Phase::ApplyExtrinsic(0),
+                EventRecord::Raw {
+                    pallet_index: 9,
+                    variant_index: 1,
+                    bytes: vec![42, 43].into(),
+                }
+            )]
+        );
+        assert!(decoder.decode_events(&mut &events[..]).unwrap().is_empty());
+    }
+
+    #[derive(Encode, scale_info::TypeInfo)]
+    enum TestEvent {
+        #[codec(index = 4)]
+        Counted(Compact<u64>, bool),
+    }
+
+    #[test]
+    fn raw_events_keep_their_on_chain_bytes() {
+        let metadata = metadata_with_events::<TestEvent>();
+        let event = (7u8, TestEvent::Counted(Compact(300), true)).encode();
+        let mut events = Compact(1u32).encode();
+        // `Phase::Finalization`
+        events.push(1);
+        events.extend(&event);
+        events.extend(Vec::<sp_core::H256>::new().encode());
+
+        let decoder = EventsDecoder::<MockConfig>::new(metadata);
+        let decoded = decoder.decode_events(&mut &events[..]).unwrap();
+        assert_eq!(decoded.len(), 1);
+        let (_, raw) = &decoded[0];
+        assert_eq!(
+            (raw.pallet.as_str(), raw.variant.as_str()),
+            ("Test", "Counted")
+        );
+        assert_eq!(raw.bytes.0, event);
+        assert_eq!(raw.data.0, event[2..].to_vec());
+    }
+
+    #[test]
+    fn event_records_with_topics_are_delimited() {
+        let metadata = metadata_with_events::<TestEvent>();
+        let topics = vec![sp_core::H256::from([1; 32]), sp_core::H256::from([2; 32])];
+        let mut events = Compact(3u32).encode();
+        for (n, topics) in [(1u64, &topics[..]), (2, &topics[..0]), (3, &topics[..1])] {
+            // `Phase::ApplyExtrinsic(0)`
+            events.extend((0u8, 0u32).encode());
+            events.extend((7u8, TestEvent::Counted(Compact(n), true)).encode());
+            events.extend(topics.encode());
+        }
+
+        let decoder = EventsDecoder::<MockConfig>::new(metadata);
+        let mut input = &events[..];
+        let decoded = decoder.decode_events(&mut input).unwrap();
+        assert!(input.is_empty());
+        let counts = decoded
+            .iter()
+            .map(|(_, raw)| <(Compact<u64>, bool)>::decode(&mut &raw.data[..]).unwrap())
+            .map(|(n, _)| n.0)
+            .collect::<Vec<_>>();
+        assert_eq!(counts, vec![1, 2, 3]);
+    }
+
+    #[derive(Encode, scale_info::TypeInfo)]
+    struct Deposit {
+        who: [u8; 4],
+        amount: u128,
+    }
+
+    #[derive(Encode, scale_info::TypeInfo)]
+    enum DepositEvent {
+        Deposited(Deposit),
+    }
+
+    #[test]
+    fn decode_errors_locate_the_failing_field() {
+        let metadata = metadata_with_events::<DepositEvent>();
+        let deposit = Deposit {
+            who: [1; 4],
+            amount: 10,
+        };
+        let mut event = (7u8, DepositEvent::Deposited(deposit)).encode();
+        // Cut the `amount` short.
+        event.truncate(event.len() - 8);
+        let mut events = Compact(1u32).encode();
+        // `Phase::Finalization`
+        events.push(1);
+        events.extend(&event);
+
+        let decoder = EventsDecoder::<MockConfig>::new(metadata);
+        match decoder.decode_event_records(&mut &events[..]) {
+            Err(Error::Decode(err)) => {
+                assert!(err.type_path.ends_with("::Deposit"), "{}", err.type_path);
+                assert_eq!(err.fields, vec!["0".to_string(), "amount".to_string()]);
+                assert_eq!(err.offset, 4);
+            }
+            other => panic!("expected a decode error, got {:?}", other),
+        }
+    }
+
+    fn transfer_event() -> RawEvent {
+        let data = (
+            AccountId32::new([1; 32]),
+            AccountId32::new([2; 32]),
+            10_000u128,
+        );
+        RawEvent {
+            pallet: "Balances".to_string(),
+            pallet_index: 5,
+            variant: "Transfer".to_string(),
+            variant_index: 2,
+            data: data.encode().into(),
+            bytes: (5u8, 2u8, data).encode().into(),
+        }
+    }
+
+    #[test]
+    fn as_event_decodes_account_fields() {
+        let event = transfer_event().as_event::<Transfer>().unwrap();
+        assert_eq!(
+            event,
+            Some(Transfer {
+                from: AccountId32::new([1; 32]),
+                to: AccountId32::new([2; 32]),
+                amount: 10_000,
+            })
+        );
+    }
+
+    #[test]
+    fn well_known_event_decodes_prelude_events_only() {
+        let event = WellKnownEvent::<MockConfig>::from_raw(&transfer_event()).unwrap();
+        match event {
+            Some(WellKnownEvent::Transferred(transfer)) => {
+                assert_eq!(transfer.to, AccountId32::new([2; 32]));
+                assert_eq!(transfer.amount, 10_000);
+            }
+            _ => panic!("expected a transfer"),
+        }
+
+        let mut other = transfer_event();
+        other.variant = "Deposit".to_string();
+        assert!(WellKnownEvent::<MockConfig>::from_raw(&other)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn well_known_events_decode_weights_of_either_shape() {
+        let dispatch_info = |weight: Vec<u8>| {
+            let data = [weight, vec![0, 0]].concat();
+            RawEvent {
+                pallet: "System".to_string(),
+                pallet_index: 0,
+                variant: "ExtrinsicSuccess".to_string(),
+                variant_index: 0,
+                bytes: [vec![0, 0], data.clone()].concat().into(),
+                data: data.into(),
+            }
+        };
+        let scalar = dispatch_info(1_000u64.encode());
+        let v2 = dispatch_info((Compact(1_000u64), Compact(64u64)).encode());
+
+        let decode = |raw: &RawEvent, shape| {
+            match WellKnownEvent::<MockConfig>::from_raw_with_shape(raw, shape) {
+                Ok(Some(WellKnownEvent::ExtrinsicSuccess(event))) => {
+                    Ok(event.dispatch_info.weight)
+                }
+                Ok(other) => panic!("Unexpected event {:?}", other),
+                Err(err) => Err(err),
+            }
+        };
+        assert_eq!(
+            decode(&scalar, WeightShape::Scalar).unwrap(),
+            crate::Weight::from_ref_time(1_000)
+        );
+        assert_eq!(
+            decode(&v2, WeightShape::V2).unwrap(),
+            crate::Weight::from_parts(1_000, 64)
+        );
+        assert!(decode(&v2, WeightShape::Scalar).is_err());
+    }
+
+    #[test]
+    fn as_event_rejects_types_not_matching_the_encoding() {
+        assert!(transfer_event().as_event::<MismatchedTransfer>().is_err());
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use super::*;
@@ -0,0 +1,232 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decode extrinsics, e.g. from the body of a block, using the metadata type registry.
+
+use codec::{
+    Compact,
+    Decode,
+};
+
+use crate::{
+    value::{
+        Composite,
+        Value,
+    },
+    Config,
+    Error,
+    Metadata,
+    MetadataError,
+};
+
+/// The extrinsic format version supported by V14 metadata.
+const EXTRINSIC_VERSION: u8 = 4;
+/// The bit of the version byte set for signed extrinsics.
+const SIGNED_FLAG: u8 = 0b1000_0000;
+/// The bits of the version byte holding the format version.
+const VERSION_MASK: u8 = 0b0111_1111;
+
+/// An extrinsic whose call was decoded using the metadata type registry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DecodedExtrinsic<T: Config> {
+    /// The address of the signer, or `None` for an unsigned extrinsic.
+    pub signer: Option<T::Address>,
+    /// The name of the pallet of the call.
+    pub pallet: String,
+    /// The name of the call.
+    pub call: String,
+    /// The call arguments.
+    pub args: Composite,
+}
+
+/// Decode a length prefixed extrinsic, as found in the body of a block.
+///
+/// The compact length prefix is followed by a version byte, whose top bit is set if the
+/// extrinsic is signed; versions other than 4 fail with [`Error::UnsupportedExtrinsicVersion`].
+/// The signed extensions are skipped using their types from the metadata, and the call is
+/// decoded generically; extrinsics which do not consume `input` exactly are rejected.
+pub fn decode_extrinsic<T: Config>(
+    metadata: &Metadata,
+    input: &[u8],
+) -> Result<DecodedExtrinsic<T>, Error> {
+    let input = &mut &input[..];
+    let len = <Compact<u32>>::decode(input)?.0 as usize;
+    if input.len() != len {
+        return Err(Error::Other(format!(
+            "Extrinsic length prefix {} does not match its length {}",
+            len,
+            input.len()
+        )))
+    }
+
+    let version = u8::decode(input)?;
+    if version & VERSION_MASK != EXTRINSIC_VERSION {
+        return Err(Error::UnsupportedExtrinsicVersion(version & VERSION_MASK))
+    }
+    let signer = if version & SIGNED_FLAG != 0 {
+        let address = T::Address::decode(input)?;
+        let _signature = T::Signature::decode(input)?;
+        for extension in &metadata.runtime_metadata().extrinsic.signed_extensions {
+            Value::decode(metadata, extension.ty.id(), input)?;
+        }
+        Some(address)
+    } else {
+        None
+    };
+
+    let pallet_index = u8::decode(input)?;
+    let call_index = u8::decode(input)?;
+    let pallet = metadata.pallet_by_index(pallet_index)?;
+    let call =
+        pallet
+            .call_by_index(call_index)
+            .ok_or(MetadataError::CallIndexNotFound {
+                pallet: pallet_index,
+                call: call_index,
+            })?;
+    let args = Composite::decode(metadata, call.fields(), input)?;
+    if !input.is_empty() {
+        return Err(Error::Other(format!(
+            "{} bytes left over after decoding {}::{}",
+            input.len(),
+            pallet.name(),
+            call.name()
+        )))
+    }
+
+    Ok(DecodedExtrinsic {
+        signer,
+        pallet: pallet.name().to_string(),
+        call: call.name().clone(),
+        args,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        pallet,
+        MetadataBuilder,
+        MockConfig,
+    };
+    use frame_metadata::v14::{
+        PalletCallMetadata,
+        PalletMetadata,
+        SignedExtensionMetadata,
+    };
+    use sp_runtime::{
+        AccountId32,
+        MultiAddress,
+    };
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(scale_info::TypeInfo)]
+    enum SystemCall {
+        remark { remark: Vec<u8> },
+    }
+
+    /// Metadata of a `System` pallet with a `remark` call, and a `CheckNonce` extension.
+    fn metadata() -> Metadata {
+        MetadataBuilder::new()
+            .with_pallet(PalletMetadata {
+                calls: Some(PalletCallMetadata {
+                    ty: scale_info::meta_type::<SystemCall>(),
+                }),
+                ..pallet("System", 0)
+            })
+            .with_signed_extension(SignedExtensionMetadata {
+                identifier: "CheckNonce",
+                ty: scale_info::meta_type::<Compact<u32>>(),
+                additional_signed: scale_info::meta_type::<()>(),
+            })
+            .set_extrinsic_version(EXTRINSIC_VERSION)
+            .build()
+    }
+
+    /// `System::remark(vec![1, 2, 3])`
+    const CALL: [u8; 6] = [0x00, 0x00, 0x0c, 0x01, 0x02, 0x03];
+
+    #[test]
+    fn decodes_unsigned_v4_extrinsic() {
+        let mut encoded = vec![0x1c, 0x04];
+        encoded.extend(CALL);
+
+        let decoded = decode_extrinsic::<MockConfig>(&metadata(), &encoded).unwrap();
+        assert_eq!(decoded.signer, None);
+        assert_eq!(
+            (decoded.pallet.as_str(), decoded.call.as_str()),
+            ("System", "remark")
+        );
+        assert_eq!(
+            decoded.args.field("remark"),
+            Some(&Value::from_bytes(&[1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn decodes_signed_v4_extrinsic() {
+        // A two byte compact length prefix of 106, then the signed version byte.
+        let mut encoded = vec![0xa9, 0x01, 0x84];
+        // `MultiAddress::Id`
+        encoded.push(0x00);
+        encoded.extend([1; 32]);
+        // `MultiSignature::Sr25519`
+        encoded.push(0x01);
+        encoded.extend([2; 64]);
+        // `CheckNonce(5)`
+        encoded.push(0x14);
+        encoded.extend(CALL);
+
+        let decoded = decode_extrinsic::<MockConfig>(&metadata(), &encoded).unwrap();
+        assert_eq!(
+            decoded.signer,
+            Some(MultiAddress::Id(AccountId32::new([1; 32])))
+        );
+        assert_eq!(
+            (decoded.pallet.as_str(), decoded.call.as_str()),
+            ("System", "remark")
+        );
+        assert_eq!(
+            decoded.args.field("remark"),
+            Some(&Value::from_bytes(&[1, 2, 3]))
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        for version in [0x05, 0x85, 0x03] {
+            let mut encoded = vec![0x1c, version];
+            encoded.extend(CALL);
+
+            let err = decode_extrinsic::<MockConfig>(&metadata(), &encoded).unwrap_err();
+            assert!(
+                matches!(err, Error::UnsupportedExtrinsicVersion(v) if v == version & 0x7f),
+                "{:?}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_mismatched_length_prefix() {
+        let mut encoded = vec![0x18, 0x04];
+        encoded.extend(CALL);
+
+        let err = decode_extrinsic::<MockConfig>(&metadata(), &encoded).unwrap_err();
+        assert!(err.to_string().contains("length prefix"), "{}", err);
+    }
+}
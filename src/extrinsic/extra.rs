@@ -28,11 +28,17 @@ use sp_runtime::{
     traits::{
         DispatchInfoOf,
         SignedExtension,
+        UniqueSaturatedInto,
     },
     transaction_validity::TransactionValidityError,
 };
 
-use crate::Config;
+use super::ExtensionParams;
+use crate::{
+    Config,
+    Error,
+    Metadata,
+};
 
 /// Extra type.
 // pub type Extra<T> = <<T as Config>::Extra as SignedExtra<T>>::Extra;
@@ -166,15 +172,15 @@ where
 ///
 /// # Note
 ///
-/// This is modified from the substrate version to allow passing in of the genesis hash, which is
-/// returned via `additional_signed()`. It assumes therefore `Era::Immortal` (The transaction is
-/// valid forever)
+/// This is modified from the substrate version to allow passing in of the hash of the block the
+/// era is anchored to (the genesis hash for `Era::Immortal`), which is returned via
+/// `additional_signed()`.
 #[derive(Encode, Decode, Clone, Eq, PartialEq, Debug, TypeInfo)]
 #[scale_info(skip_type_params(T))]
 pub struct CheckMortality<T: Config>(
     /// The default structure for the Extra encoding
     pub (Era, PhantomData<T>),
-    /// Local genesis hash to be used for `AdditionalSigned`
+    /// Local checkpoint block hash to be used for `AdditionalSigned`
     #[codec(skip)]
     pub T::Hash,
 );
@@ -298,6 +304,76 @@ impl SignedExtension for ChargeAssetTxPayment {
     }
 }
 
+/// A standard signed extension which can be disabled, for chains which omit it.
+///
+/// When disabled, neither the extension nor its `AdditionalSigned` contribute any bytes to the
+/// extrinsic or the signed payload. Decoding therefore always yields an enabled extension, see
+/// the `Decode` implementation.
+#[derive(Clone, Eq, PartialEq, Debug, TypeInfo)]
+pub struct Toggle<E>(pub Option<E>);
+
+impl<E> Toggle<E> {
+    /// Creates the extension if `enabled`, otherwise leaves it out.
+    pub fn new(enabled: bool, extension: E) -> Self {
+        Self(if enabled { Some(extension) } else { None })
+    }
+}
+
+impl<E: Encode> Encode for Toggle<E> {
+    fn size_hint(&self) -> usize {
+        self.0.as_ref().map_or(0, Encode::size_hint)
+    }
+
+    fn encode_to<O: codec::Output + ?Sized>(&self, dest: &mut O) {
+        if let Some(extension) = &self.0 {
+            extension.encode_to(dest)
+        }
+    }
+}
+
+/// Decodes an enabled extension.
+///
+/// A disabled extension encodes to no bytes at all, so whether an extension is present can not
+/// be told from the encoding, only from the signed extensions the chain declares in its
+/// metadata. `Decode` is only implemented because [`SignedExtension`] requires it; decode the
+/// extrinsics of chains omitting extensions with [`crate::extrinsic::decode_extrinsic`], which
+/// skips the extensions declared in the metadata.
+impl<E: Decode> Decode for Toggle<E> {
+    fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        Ok(Self(Some(E::decode(input)?)))
+    }
+}
+
+impl<E> SignedExtension for Toggle<E>
+where
+    E: SignedExtension,
+{
+    const IDENTIFIER: &'static str = E::IDENTIFIER;
+    type AccountId = E::AccountId;
+    type Call = E::Call;
+    type AdditionalSigned = Toggle<E::AdditionalSigned>;
+    type Pre = ();
+    fn additional_signed(
+        &self,
+    ) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        Ok(Toggle(
+            self.0
+                .as_ref()
+                .map(SignedExtension::additional_signed)
+                .transpose()?,
+        ))
+    }
+    fn pre_dispatch(
+        self,
+        _who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        Ok(())
+    }
+}
+
 /// Trait for implementing transaction extras for a runtime.
 pub trait SignedExtra<T: Config>: SignedExtension {
     /// The type the extras.
@@ -314,10 +390,146 @@ pub trait SignedExtra<T: Config>: SignedExtension {
         additional_params: Self::Parameters,
     ) -> Self;
 
+    /// Returns the nonce set in `additional_params`, if any.
+    ///
+    /// When it is set, the client signs with it rather than with the nonce of the signer or
+    /// of its account, which it then does not look up, so that extrinsics can be signed
+    /// offline. The nonce the client signs with is passed to [`SignedExtra::new`]. By default
+    /// the parameters cannot set the nonce.
+    fn explicit_nonce(_additional_params: &Self::Parameters) -> Option<T::Index> {
+        None
+    }
+
+    /// Adapt the extras to the chain described by `metadata`, if it is available.
+    ///
+    /// Called before [`SignedExtra::extra`] when creating a signed extrinsic. By default the
+    /// extras do not depend on the metadata.
+    fn with_metadata(self, _metadata: Option<&Metadata>) -> Result<Self, Error> {
+        Ok(self)
+    }
+
     /// Returns the transaction extra.
     fn extra(&self) -> Self::Extra;
 }
 
+/// Additional parameters for [`DefaultExtra`], which are provided by the caller rather than
+/// fetched from the chain.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DefaultExtraParams<T: Config> {
+    tip: T::Balance,
+    nonce: Option<T::Index>,
+    era: Era,
+    era_checkpoint: Option<T::Hash>,
+    extensions: StandardExtensions,
+}
+
+impl<T: Config> Default for DefaultExtraParams<T> {
+    fn default() -> Self {
+        Self {
+            tip: Default::default(),
+            nonce: None,
+            era: Era::Immortal,
+            era_checkpoint: None,
+            extensions: Default::default(),
+        }
+    }
+}
+
+impl<T: Config> DefaultExtraParams<T> {
+    /// Include a tip for the block author, to gain additional priority in the transaction queue.
+    pub fn tip(mut self, tip: T::Balance) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    /// The tip for the block author.
+    pub(crate) fn get_tip(&self) -> T::Balance {
+        self.tip
+    }
+
+    /// Sign with `nonce` rather than the nonce of the signer or of its account, e.g. to sign
+    /// offline.
+    pub fn nonce(mut self, nonce: T::Index) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Set the mortality of the transaction, and the hash of the block it is anchored to.
+    ///
+    /// For a mortal era the checkpoint must be the hash of the block whose number the era's
+    /// phase was computed from, e.g. with [`crate::mortality::mortal`]. Transactions are
+    /// immortal by default, anchored to the genesis block.
+    pub fn era(mut self, era: Era, checkpoint: T::Hash) -> Self {
+        self.era = era;
+        self.era_checkpoint = Some(checkpoint);
+        self
+    }
+
+    /// Enable or disable the [`CheckSpecVersion`] extension.
+    pub fn check_spec_version(mut self, enabled: bool) -> Self {
+        self.extensions.spec_version = enabled;
+        self
+    }
+
+    /// Enable or disable the [`CheckTxVersion`] extension.
+    pub fn check_tx_version(mut self, enabled: bool) -> Self {
+        self.extensions.tx_version = enabled;
+        self
+    }
+
+    /// Enable or disable the [`CheckGenesis`] extension.
+    pub fn check_genesis(mut self, enabled: bool) -> Self {
+        self.extensions.genesis = enabled;
+        self
+    }
+
+    /// Enable or disable the [`CheckMortality`] extension.
+    pub fn check_mortality(mut self, enabled: bool) -> Self {
+        self.extensions.mortality = enabled;
+        self
+    }
+
+    /// The values the extensions are built from, given those fetched from the chain and the
+    /// nonce to sign with.
+    pub(crate) fn extension_params(
+        &self,
+        spec_version: u32,
+        tx_version: u32,
+        nonce: T::Index,
+        genesis_hash: T::Hash,
+    ) -> ExtensionParams<T> {
+        ExtensionParams {
+            spec_version,
+            tx_version,
+            nonce,
+            genesis_hash,
+            tip: self.tip,
+            era: self.era,
+            era_checkpoint: self.era_checkpoint.unwrap_or(genesis_hash),
+        }
+    }
+}
+
+/// Which of the optional standard extensions are included by [`DefaultExtra`].
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Debug, TypeInfo)]
+struct StandardExtensions {
+    spec_version: bool,
+    tx_version: bool,
+    genesis: bool,
+    mortality: bool,
+}
+
+impl Default for StandardExtensions {
+    fn default() -> Self {
+        Self {
+            spec_version: true,
+            tx_version: true,
+            genesis: true,
+            mortality: true,
+        }
+    }
+}
+
 /// Default `SignedExtra` for substrate runtimes.
 #[derive(Encode, Decode, Clone, Eq, PartialEq, Debug, TypeInfo)]
 #[scale_info(skip_type_params(T))]
@@ -326,45 +538,78 @@ pub struct DefaultExtra<T: Config> {
     tx_version: u32,
     nonce: T::Index,
     genesis_hash: T::Hash,
+    tip: T::Balance,
+    era: Era,
+    era_checkpoint: T::Hash,
+    extensions: StandardExtensions,
 }
 
 impl<T: Config + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for DefaultExtra<T> {
     type Extra = (
-        CheckSpecVersion<T>,
-        CheckTxVersion<T>,
-        CheckGenesis<T>,
-        CheckMortality<T>,
+        Toggle<CheckSpecVersion<T>>,
+        Toggle<CheckTxVersion<T>>,
+        Toggle<CheckGenesis<T>>,
+        Toggle<CheckMortality<T>>,
         CheckNonce<T>,
         CheckWeight<T>,
         ChargeAssetTxPayment,
     );
-    type Parameters = ();
+    type Parameters = DefaultExtraParams<T>;
 
     fn new(
         spec_version: u32,
         tx_version: u32,
         nonce: T::Index,
         genesis_hash: T::Hash,
-        _params: Self::Parameters,
+        params: Self::Parameters,
     ) -> Self {
+        let ExtensionParams {
+            spec_version,
+            tx_version,
+            nonce,
+            genesis_hash,
+            tip,
+            era,
+            era_checkpoint,
+        } = params.extension_params(spec_version, tx_version, nonce, genesis_hash);
         DefaultExtra {
             spec_version,
             tx_version,
             nonce,
             genesis_hash,
+            tip,
+            era,
+            era_checkpoint,
+            extensions: params.extensions,
         }
     }
 
+    fn explicit_nonce(params: &Self::Parameters) -> Option<T::Index> {
+        params.nonce
+    }
+
     fn extra(&self) -> Self::Extra {
         (
-            CheckSpecVersion(PhantomData, self.spec_version),
-            CheckTxVersion(PhantomData, self.tx_version),
-            CheckGenesis(PhantomData, self.genesis_hash),
-            CheckMortality((Era::Immortal, PhantomData), self.genesis_hash),
+            Toggle::new(
+                self.extensions.spec_version,
+                CheckSpecVersion(PhantomData, self.spec_version),
+            ),
+            Toggle::new(
+                self.extensions.tx_version,
+                CheckTxVersion(PhantomData, self.tx_version),
+            ),
+            Toggle::new(
+                self.extensions.genesis,
+                CheckGenesis(PhantomData, self.genesis_hash),
+            ),
+            Toggle::new(
+                self.extensions.mortality,
+                CheckMortality((self.era, PhantomData), self.era_checkpoint),
+            ),
             CheckNonce(self.nonce),
             CheckWeight(PhantomData),
             ChargeAssetTxPayment {
-                tip: u128::default(),
+                tip: self.tip.unique_saturated_into(),
                 asset_id: None,
             },
         )
@@ -394,3 +639,52 @@ impl<T: Config + Clone + Debug + Eq + Send + Sync> SignedExtension for DefaultEx
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockConfig;
+
+    #[test]
+    fn disabled_extensions_contribute_no_bytes() {
+        let check = CheckTxVersion::<MockConfig>(PhantomData, 7);
+
+        let enabled = Toggle::new(true, check.clone());
+        assert_eq!(enabled.encode(), check.encode());
+        assert_eq!(enabled.additional_signed().unwrap().encode(), 7u32.encode());
+
+        let disabled = Toggle::new(false, check);
+        assert!(disabled.encode().is_empty());
+        assert!(disabled.additional_signed().unwrap().encode().is_empty());
+    }
+
+    #[test]
+    fn toggles_decode_as_enabled() {
+        let enabled = Toggle::new(true, CheckNonce::<MockConfig>(300));
+        let encoded = enabled.encode();
+        assert_eq!(Toggle::decode(&mut &encoded[..]).unwrap(), enabled);
+
+        // The bytes following a disabled extension are decoded as the extension itself.
+        let disabled = Toggle::new(false, CheckNonce::<MockConfig>(300));
+        let encoded = (disabled, 7u8).encode();
+        assert_eq!(
+            Toggle::<CheckNonce<MockConfig>>::decode(&mut &encoded[..]).unwrap(),
+            Toggle::new(true, CheckNonce(7))
+        );
+    }
+
+    #[test]
+    fn explicit_nonce_is_read_from_the_params() {
+        let params = DefaultExtraParams::<MockConfig>::default().tip(5).nonce(42);
+        assert_eq!(DefaultExtra::explicit_nonce(&params), Some(42));
+
+        // The client resolves the nonce to sign with, so `new` takes it as given.
+        let extra = DefaultExtra::<MockConfig>::new(1, 1, 42, Default::default(), params);
+        assert_eq!(extra.nonce, 42);
+        assert_eq!(extra.tip, 5);
+        assert_eq!(
+            DefaultExtra::<MockConfig>::explicit_nonce(&Default::default()),
+            None
+        );
+    }
+}
@@ -16,10 +16,17 @@
 
 //! Create signed or unsigned extrinsics.
 
+mod decode;
 mod extra;
+mod registry;
 mod signer;
 
+pub(crate) use self::signer::PlaceholderSigner;
 pub use self::{
+    decode::{
+        decode_extrinsic,
+        DecodedExtrinsic,
+    },
     extra::{
         ChargeAssetTxPayment,
         CheckGenesis,
@@ -29,7 +36,17 @@ pub use self::{
         CheckTxVersion,
         CheckWeight,
         DefaultExtra,
+        DefaultExtraParams,
         SignedExtra,
+        Toggle,
+    },
+    registry::{
+        EncodedExtensions,
+        ExtensionEncoder,
+        ExtensionParams,
+        ExtensionRegistry,
+        MetadataExtra,
+        MetadataExtraParams,
     },
     signer::{
         PairSigner,
@@ -37,7 +54,10 @@ pub use self::{
     },
 };
 
-use sp_runtime::traits::SignedExtension;
+use sp_runtime::traits::{
+    Hash,
+    SignedExtension,
+};
 use sp_version::RuntimeVersion;
 
 use crate::{
@@ -45,6 +65,7 @@ use crate::{
     Encoded,
     Error,
     ExtrinsicExtraData,
+    Metadata,
 };
 
 /// UncheckedExtrinsic type.
@@ -61,19 +82,19 @@ pub type SignedPayload<T> = sp_runtime::generic::SignedPayload<
     <<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra,
 >;
 
-/// Creates a signed extrinsic
-pub async fn create_signed<T>(
+/// Creates the payload which is signed to create a signed extrinsic, see [`create_signed`].
+///
+/// The `metadata` is passed to [`SignedExtra::with_metadata`].
+pub fn create_signed_payload<T>(
     runtime_version: &RuntimeVersion,
     genesis_hash: T::Hash,
+    metadata: Option<&Metadata>,
     nonce: T::Index,
     call: Encoded,
-    signer: &(dyn Signer<T> + Send + Sync),
     additional_params: <T::Extra as SignedExtra<T>>::Parameters,
-) -> Result<UncheckedExtrinsic<T>, Error>
+) -> Result<SignedPayload<T>, Error>
 where
     T: Config + ExtrinsicExtraData<T>,
-    <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
-        Send + Sync,
 {
     let spec_version = runtime_version.spec_version;
     let tx_version = runtime_version.transaction_version;
@@ -83,8 +104,45 @@ where
         nonce,
         genesis_hash,
         additional_params,
-    );
-    let payload = SignedPayload::<T>::new(call, extra.extra())?;
+    )
+    .with_metadata(metadata)?;
+    Ok(SignedPayload::<T>::new(call, extra.extra())?)
+}
+
+/// Creates a signed extrinsic
+///
+/// The `metadata` is passed to [`SignedExtra::with_metadata`].
+pub async fn create_signed<T>(
+    runtime_version: &RuntimeVersion,
+    genesis_hash: T::Hash,
+    metadata: Option<&Metadata>,
+    nonce: T::Index,
+    call: Encoded,
+    signer: &(dyn Signer<T> + Send + Sync),
+    additional_params: <T::Extra as SignedExtra<T>>::Parameters,
+) -> Result<UncheckedExtrinsic<T>, Error>
+where
+    T: Config + ExtrinsicExtraData<T>,
+    <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned:
+        Send + Sync,
+{
+    let payload = create_signed_payload::<T>(
+        runtime_version,
+        genesis_hash,
+        metadata,
+        nonce,
+        call,
+        additional_params,
+    )?;
     let signed = signer.sign(payload).await?;
     Ok(signed)
 }
+
+/// Hash an encoded extrinsic with the hasher `H`, rather than with [`Config::Hashing`].
+///
+/// The hashes of extrinsics reported by the client and the node are always computed with
+/// [`Config::Hashing`]; this is for reconciling them with systems which hash extrinsics
+/// differently.
+pub fn hash_with<H: Hash>(encoded: &[u8]) -> H::Output {
+    H::hash(encoded)
+}
@@ -0,0 +1,554 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signed extensions built from the list declared in the metadata, rather than a fixed set.
+
+use codec::{
+    Compact,
+    Decode,
+    Encode,
+};
+use core::{
+    fmt::Debug,
+    marker::PhantomData,
+};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    generic::Era,
+    traits::{
+        DispatchInfoOf,
+        SignedExtension,
+        UniqueSaturatedInto,
+    },
+    transaction_validity::TransactionValidityError,
+};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+};
+
+use super::{
+    ChargeAssetTxPayment,
+    CheckGenesis,
+    CheckMortality,
+    CheckNonce,
+    CheckSpecVersion,
+    CheckTxVersion,
+    DefaultExtra,
+    DefaultExtraParams,
+    SignedExtra,
+};
+use crate::{
+    metadata::MetadataError,
+    Config,
+    Encoded,
+    Error,
+    Metadata,
+};
+
+/// The values signed extensions are built from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtensionParams<T: Config> {
+    /// The spec version of the runtime.
+    pub spec_version: u32,
+    /// The transaction version of the runtime.
+    pub tx_version: u32,
+    /// The nonce of the signer.
+    pub nonce: T::Index,
+    /// The hash of the genesis block.
+    pub genesis_hash: T::Hash,
+    /// The tip for the block author.
+    pub tip: T::Balance,
+    /// The mortality of the transaction.
+    pub era: Era,
+    /// The hash of the block the era is anchored to.
+    pub era_checkpoint: T::Hash,
+}
+
+/// Encodes a signed extension declared in the metadata.
+pub trait ExtensionEncoder<T: Config>: Send + Sync {
+    /// Append the data of the extension included in the extrinsic to `extra`, and the data only
+    /// included in the signed payload to `additional`.
+    fn encode(
+        &self,
+        params: &ExtensionParams<T>,
+        extra: &mut Vec<u8>,
+        additional: &mut Vec<u8>,
+    ) -> Result<(), Error>;
+}
+
+impl<T, F> ExtensionEncoder<T> for F
+where
+    T: Config,
+    F: Fn(&ExtensionParams<T>, &mut Vec<u8>, &mut Vec<u8>) -> Result<(), Error>
+        + Send
+        + Sync,
+{
+    fn encode(
+        &self,
+        params: &ExtensionParams<T>,
+        extra: &mut Vec<u8>,
+        additional: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        self(params, extra, additional)
+    }
+}
+
+/// Encodes one of the [`SignedExtension`] implementations of this crate.
+struct Typed<T: Config, E>(fn(&ExtensionParams<T>) -> E);
+
+impl<T: Config, E: SignedExtension> ExtensionEncoder<T> for Typed<T, E> {
+    fn encode(
+        &self,
+        params: &ExtensionParams<T>,
+        extra: &mut Vec<u8>,
+        additional: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let extension = (self.0)(params);
+        extension.encode_to(extra);
+        extension.additional_signed()?.encode_to(additional);
+        Ok(())
+    }
+}
+
+/// Encoders of signed extensions, keyed by the identifier the metadata declares them with.
+#[derive(Clone)]
+pub struct ExtensionRegistry<T: Config> {
+    encoders: HashMap<String, Arc<dyn ExtensionEncoder<T>>>,
+}
+
+impl<T: Config> ExtensionRegistry<T> {
+    /// Creates a registry without any extensions.
+    pub fn empty() -> Self {
+        Self {
+            encoders: HashMap::new(),
+        }
+    }
+
+    /// Register the encoder of the extension with the given identifier, replacing any existing
+    /// one.
+    pub fn register<E: ExtensionEncoder<T> + 'static>(
+        mut self,
+        identifier: &str,
+        encoder: E,
+    ) -> Self {
+        self.encoders
+            .insert(identifier.to_string(), Arc::new(encoder));
+        self
+    }
+
+    /// Encode the extensions with the given identifiers, in order.
+    ///
+    /// Fails with [`Error::UnsupportedSignedExtension`] if an extension is not registered.
+    pub fn encode<'a>(
+        &self,
+        identifiers: impl IntoIterator<Item = &'a str>,
+        params: &ExtensionParams<T>,
+    ) -> Result<EncodedExtensions, Error> {
+        let mut extra = Vec::new();
+        let mut additional = Vec::new();
+        for identifier in identifiers {
+            let encoder = self.encoders.get(identifier).ok_or_else(|| {
+                Error::UnsupportedSignedExtension(identifier.to_string())
+            })?;
+            encoder.encode(params, &mut extra, &mut additional)?;
+        }
+        Ok(EncodedExtensions { extra, additional })
+    }
+}
+
+impl<T: Config + Clone + Debug + Eq + Send + Sync> ExtensionRegistry<T> {
+    /// Creates a registry of the signed extensions of substrate's FRAME pallets.
+    pub fn standard() -> Self {
+        fn no_data<T: Config>(
+            _: &ExtensionParams<T>,
+            _: &mut Vec<u8>,
+            _: &mut Vec<u8>,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+        Self::empty()
+            .register("CheckNonZeroSender", no_data::<T>)
+            .register(
+                "CheckSpecVersion",
+                Typed(|params: &ExtensionParams<T>| {
+                    CheckSpecVersion(PhantomData, params.spec_version)
+                }),
+            )
+            .register(
+                "CheckTxVersion",
+                Typed(|params: &ExtensionParams<T>| {
+                    CheckTxVersion(PhantomData, params.tx_version)
+                }),
+            )
+            .register(
+                "CheckGenesis",
+                Typed(|params: &ExtensionParams<T>| {
+                    CheckGenesis(PhantomData, params.genesis_hash)
+                }),
+            )
+            .register(
+                "CheckMortality",
+                Typed(|params: &ExtensionParams<T>| {
+                    CheckMortality((params.era, PhantomData), params.era_checkpoint)
+                }),
+            )
+            .register(
+                "CheckNonce",
+                Typed(|params: &ExtensionParams<T>| CheckNonce(params.nonce)),
+            )
+            .register("CheckWeight", no_data::<T>)
+            .register(
+                "ChargeTransactionPayment",
+                |params: &ExtensionParams<T>, extra: &mut Vec<u8>, _: &mut Vec<u8>| {
+                    let tip: u128 = params.tip.unique_saturated_into();
+                    Compact(tip).encode_to(extra);
+                    Ok(())
+                },
+            )
+            .register(
+                "ChargeAssetTxPayment",
+                Typed(|params: &ExtensionParams<T>| {
+                    ChargeAssetTxPayment {
+                        tip: params.tip.unique_saturated_into(),
+                        asset_id: None,
+                    }
+                }),
+            )
+    }
+}
+
+impl<T: Config + Clone + Debug + Eq + Send + Sync> Default for ExtensionRegistry<T> {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// The encoded signed extensions of a [`MetadataExtra`].
+#[derive(Clone, Debug, Eq, PartialEq, TypeInfo)]
+pub struct EncodedExtensions {
+    extra: Vec<u8>,
+    additional: Vec<u8>,
+}
+
+impl Encode for EncodedExtensions {
+    fn encode_to<O: codec::Output + ?Sized>(&self, dest: &mut O) {
+        dest.write(&self.extra)
+    }
+}
+
+impl Decode for EncodedExtensions {
+    fn decode<I: codec::Input>(_input: &mut I) -> Result<Self, codec::Error> {
+        Err("Signed extensions built from metadata cannot be decoded".into())
+    }
+}
+
+impl SignedExtension for EncodedExtensions {
+    const IDENTIFIER: &'static str = "EncodedExtensions";
+    type AccountId = u64;
+    type Call = ();
+    type AdditionalSigned = Encoded;
+    type Pre = ();
+    fn additional_signed(
+        &self,
+    ) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        Ok(Encoded(self.additional.clone()))
+    }
+    fn pre_dispatch(
+        self,
+        _who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        Ok(())
+    }
+}
+
+/// Additional parameters for [`MetadataExtra`], which are provided by the caller rather than
+/// fetched from the chain.
+///
+/// These are the parameters of [`DefaultExtraParams`], apart from the choice of extensions,
+/// which are those declared in the metadata, plus the registry encoding them.
+#[derive(Clone)]
+pub struct MetadataExtraParams<T: Config> {
+    params: DefaultExtraParams<T>,
+    registry: Option<Arc<ExtensionRegistry<T>>>,
+}
+
+impl<T: Config> Default for MetadataExtraParams<T> {
+    fn default() -> Self {
+        Self {
+            params: Default::default(),
+            registry: None,
+        }
+    }
+}
+
+impl<T: Config> MetadataExtraParams<T> {
+    /// Include a tip for the block author, see [`DefaultExtraParams::tip`].
+    pub fn tip(mut self, tip: T::Balance) -> Self {
+        self.params = self.params.tip(tip);
+        self
+    }
+
+    /// Sign with `nonce`, see [`DefaultExtraParams::nonce`].
+    pub fn nonce(mut self, nonce: T::Index) -> Self {
+        self.params = self.params.nonce(nonce);
+        self
+    }
+
+    /// Set the mortality of the transaction, see [`DefaultExtraParams::era`].
+    pub fn era(mut self, era: Era, checkpoint: T::Hash) -> Self {
+        self.params = self.params.era(era, checkpoint);
+        self
+    }
+
+    /// Encode the extensions with `registry` instead of [`ExtensionRegistry::standard`], e.g.
+    /// to support the custom extensions of a chain.
+    pub fn registry(mut self, registry: ExtensionRegistry<T>) -> Self {
+        self.registry = Some(Arc::new(registry));
+        self
+    }
+}
+
+/// `SignedExtra` including exactly the extensions declared in the metadata, in the declared
+/// order. Use it as the [`crate::ExtrinsicExtraData::Extra`] of a runtime to sign for chains
+/// with differing extensions without custom code.
+///
+/// Signing fails with [`Error::UnsupportedSignedExtension`] if the chain declares an extension
+/// which is not in the [`ExtensionRegistry`].
+#[derive(Clone)]
+pub struct MetadataExtra<T: Config> {
+    params: ExtensionParams<T>,
+    registry: Option<Arc<ExtensionRegistry<T>>>,
+    encoded: EncodedExtensions,
+}
+
+impl<T: Config> Debug for MetadataExtra<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetadataExtra")
+            .field("encoded", &self.encoded)
+            .finish()
+    }
+}
+
+impl<T: Config> PartialEq for MetadataExtra<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.encoded == other.encoded
+    }
+}
+
+impl<T: Config> Eq for MetadataExtra<T> {}
+
+impl<T: Config> Encode for MetadataExtra<T> {
+    fn encode_to<O: codec::Output + ?Sized>(&self, dest: &mut O) {
+        self.encoded.encode_to(dest)
+    }
+}
+
+impl<T: Config> Decode for MetadataExtra<T> {
+    fn decode<I: codec::Input>(_input: &mut I) -> Result<Self, codec::Error> {
+        Err("Signed extensions built from metadata cannot be decoded".into())
+    }
+}
+
+impl<T: Config> TypeInfo for MetadataExtra<T> {
+    type Identity = Self;
+
+    fn type_info() -> scale_info::Type {
+        scale_info::Type::builder()
+            .path(scale_info::Path::new("MetadataExtra", module_path!()))
+            .composite(scale_info::build::Fields::unit())
+    }
+}
+
+impl<T: Config + Clone + Debug + Eq + Send + Sync> SignedExtra<T> for MetadataExtra<T> {
+    type Extra = EncodedExtensions;
+    type Parameters = MetadataExtraParams<T>;
+
+    fn new(
+        spec_version: u32,
+        tx_version: u32,
+        nonce: T::Index,
+        genesis_hash: T::Hash,
+        params: Self::Parameters,
+    ) -> Self {
+        MetadataExtra {
+            params: params.params.extension_params(
+                spec_version,
+                tx_version,
+                nonce,
+                genesis_hash,
+            ),
+            registry: params.registry,
+            encoded: EncodedExtensions {
+                extra: Vec::new(),
+                additional: Vec::new(),
+            },
+        }
+    }
+
+    fn explicit_nonce(params: &Self::Parameters) -> Option<T::Index> {
+        DefaultExtra::explicit_nonce(&params.params)
+    }
+
+    fn with_metadata(mut self, metadata: Option<&Metadata>) -> Result<Self, Error> {
+        let metadata = metadata.ok_or(MetadataError::Unavailable)?;
+        let identifiers = metadata
+            .runtime_metadata()
+            .extrinsic
+            .signed_extensions
+            .iter()
+            .map(|extension| extension.identifier.as_str());
+        self.encoded = match &self.registry {
+            Some(registry) => registry.encode(identifiers, &self.params)?,
+            None => ExtensionRegistry::standard().encode(identifiers, &self.params)?,
+        };
+        Ok(self)
+    }
+
+    fn extra(&self) -> Self::Extra {
+        self.encoded.clone()
+    }
+}
+
+impl<T: Config + Clone + Debug + Eq + Send + Sync> SignedExtension for MetadataExtra<T> {
+    const IDENTIFIER: &'static str = "MetadataExtra";
+    type AccountId = T::AccountId;
+    type Call = ();
+    type AdditionalSigned = Encoded;
+    type Pre = ();
+
+    fn additional_signed(
+        &self,
+    ) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        self.encoded.additional_signed()
+    }
+    fn pre_dispatch(
+        self,
+        _who: &Self::AccountId,
+        _call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        extrinsic::{
+            DefaultExtra,
+            DefaultExtraParams,
+        },
+        test_utils::{
+            MetadataBuilder,
+            MockConfig,
+        },
+    };
+    use frame_metadata::SignedExtensionMetadata;
+    use scale_info::meta_type;
+
+    /// Metadata of a chain declaring the given signed extensions.
+    fn metadata(identifiers: &[&'static str]) -> Metadata {
+        identifiers
+            .iter()
+            .fold(MetadataBuilder::new(), |builder, identifier| {
+                builder.with_signed_extension(SignedExtensionMetadata {
+                    identifier: *identifier,
+                    ty: meta_type::<()>(),
+                    additional_signed: meta_type::<()>(),
+                })
+            })
+            .build()
+    }
+
+    fn metadata_extra(
+        identifiers: &[&'static str],
+        params: MetadataExtraParams<MockConfig>,
+    ) -> Result<MetadataExtra<MockConfig>, Error> {
+        MetadataExtra::new(1, 2, 3, sp_core::H256::repeat_byte(4), params)
+            .with_metadata(Some(&metadata(identifiers)))
+    }
+
+    #[test]
+    fn follows_the_declared_extensions() {
+        let identifiers = [
+            "CheckSpecVersion",
+            "CheckTxVersion",
+            "CheckGenesis",
+            "CheckMortality",
+            "CheckNonce",
+            "CheckWeight",
+            "ChargeAssetTxPayment",
+        ];
+        let extra =
+            metadata_extra(&identifiers, MetadataExtraParams::default().tip(5)).unwrap();
+        let default = DefaultExtra::<MockConfig>::new(
+            1,
+            2,
+            3,
+            sp_core::H256::repeat_byte(4),
+            DefaultExtraParams::default().tip(5),
+        );
+        assert_eq!(extra.extra().encode(), default.extra().encode());
+        assert_eq!(
+            extra.additional_signed().unwrap().encode(),
+            default.additional_signed().unwrap().encode()
+        );
+
+        let reordered = metadata_extra(
+            &["CheckNonce", "CheckSpecVersion", "CheckNonZeroSender"],
+            Default::default(),
+        )
+        .unwrap();
+        assert_eq!(reordered.extra().encode(), Compact(3u32).encode());
+        assert_eq!(
+            reordered.additional_signed().unwrap().encode(),
+            1u32.encode()
+        );
+    }
+
+    #[test]
+    fn unknown_extensions_must_be_registered() {
+        let identifiers = ["CheckNonce", "CheckCustom"];
+        assert!(matches!(
+            metadata_extra(&identifiers, Default::default()),
+            Err(Error::UnsupportedSignedExtension(identifier)) if identifier == "CheckCustom"
+        ));
+
+        let registry = ExtensionRegistry::standard().register(
+            "CheckCustom",
+            |_: &ExtensionParams<MockConfig>,
+             extra: &mut Vec<u8>,
+             additional: &mut Vec<u8>| {
+                extra.push(7);
+                additional.push(8);
+                Ok(())
+            },
+        );
+        let extra = metadata_extra(
+            &identifiers,
+            MetadataExtraParams::default().registry(registry),
+        )
+        .unwrap();
+        assert_eq!(extra.extra().encode(), vec![3 << 2, 7]);
+        assert_eq!(extra.additional_signed().unwrap().encode(), vec![8]);
+    }
+}
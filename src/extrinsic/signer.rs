@@ -23,14 +23,19 @@ use super::{
 };
 use crate::{
     Config,
+    Error,
     ExtrinsicExtraData,
     SignedExtra,
 };
-use codec::Encode;
+use codec::{
+    Decode,
+    Encode,
+};
 use sp_core::Pair;
 use sp_runtime::traits::{
     IdentifyAccount,
     SignedExtension,
+    TrailingZeroInput,
     Verify,
 };
 
@@ -80,6 +85,33 @@ where
         }
     }
 
+    /// Creates a new `Signer` from a `Pair`, which signs for the given account instead of
+    /// the one derived from its public key.
+    ///
+    /// The account is used to look up the nonce and as the address of the extrinsics, e.g.
+    /// on chains whose accounts are not derived from the public key by default.
+    pub fn with_account(signer: P, account_id: T::AccountId) -> Self {
+        Self {
+            account_id,
+            nonce: None,
+            signer,
+        }
+    }
+
+    /// Creates a new `Signer` from a BIP-39 mnemonic phrase, with an optional password.
+    pub fn from_phrase(phrase: &str, password: Option<&str>) -> Result<Self, Error> {
+        let (signer, _seed) = P::from_phrase(phrase, password)?;
+        Ok(Self::new(signer))
+    }
+
+    /// Creates a new `Signer` from a secret URI such as `//Alice`, or a phrase or seed followed
+    /// by `/soft` and `//hard` derivation junctions and an optional `///password`.
+    ///
+    /// A `password` given here takes precedence over one in the URI.
+    pub fn from_uri(uri: &str, password: Option<&str>) -> Result<Self, Error> {
+        Ok(Self::new(P::from_string(uri, password)?))
+    }
+
     /// Sets the nonce to a new value.
     pub fn set_nonce(&mut self, nonce: T::Index) {
         self.nonce = Some(nonce);
@@ -100,7 +132,7 @@ where
 impl<T, P> Signer<T> for PairSigner<T, P>
 where
     T: Config + ExtrinsicExtraData<T>,
-    T::AccountId: Into<T::Address> + 'static,
+    T::AccountId: 'static,
     <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
     P: Pair + 'static,
     P::Signature: Into<T::Signature> + 'static,
@@ -128,3 +160,51 @@ where
         Ok(extrinsic)
     }
 }
+
+/// Signs extrinsics for an account with a placeholder signature, so that their fee can be
+/// estimated without the key of the account, see
+/// [`crate::SubmittableExtrinsic::estimate_fee`].
+///
+/// The placeholder is the all zero first variant of the signature type, which for a
+/// `MultiSignature` is an `Ed25519` signature.
+pub(crate) struct PlaceholderSigner<T: Config> {
+    account_id: T::AccountId,
+}
+
+impl<T: Config> PlaceholderSigner<T> {
+    pub(crate) fn new(account_id: T::AccountId) -> Self {
+        Self { account_id }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> Signer<T> for PlaceholderSigner<T>
+where
+    T: Config + ExtrinsicExtraData<T>,
+    T::Signature: Decode,
+    <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+{
+    fn account_id(&self) -> &T::AccountId {
+        &self.account_id
+    }
+
+    fn nonce(&self) -> Option<T::Index> {
+        None
+    }
+
+    async fn sign(
+        &self,
+        extrinsic: SignedPayload<T>,
+    ) -> Result<UncheckedExtrinsic<T>, String> {
+        // An all zero signature, e.g. the first variant of a `MultiSignature`.
+        let signature = T::Signature::decode(&mut TrailingZeroInput::new(&[]))
+            .map_err(|err| err.to_string())?;
+        let (call, extra, _) = extrinsic.deconstruct();
+        Ok(UncheckedExtrinsic::<T>::new_signed(
+            call,
+            self.account_id.clone().into(),
+            signature,
+            extra,
+        ))
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Referendums of the `Democracy` or `Referenda` pallet, decoded independently of the
+//! runtime, backing [`crate::Client::referendum_info`].
+
+use crate::{
+    value::{
+        Primitive,
+        Value,
+    },
+    Metadata,
+};
+
+/// The pallet a chain holds referendums with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GovernancePallet {
+    /// The `Democracy` pallet, storing referendums in `Democracy::ReferendumInfoOf`.
+    Democracy,
+    /// The `Referenda` pallet, storing referendums in `Referenda::ReferendumInfoFor`.
+    Referenda,
+}
+
+impl GovernancePallet {
+    /// Returns the governance pallet of the chain described by `metadata`, preferring
+    /// `Referenda` if the chain has both.
+    pub fn detect(metadata: &Metadata) -> Option<Self> {
+        [Self::Referenda, Self::Democracy]
+            .into_iter()
+            .find(|pallet| {
+                metadata
+                    .storage_entry(pallet.name(), pallet.storage())
+                    .is_ok()
+            })
+    }
+
+    /// Returns the name of the pallet.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Democracy => "Democracy",
+            Self::Referenda => "Referenda",
+        }
+    }
+
+    /// Returns the name of the storage map of referendums by index.
+    pub fn storage(&self) -> &'static str {
+        match self {
+            Self::Democracy => "ReferendumInfoOf",
+            Self::Referenda => "ReferendumInfoFor",
+        }
+    }
+}
+
+/// The votes of an ongoing referendum.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Tally {
+    /// The votes in favour, weighted by conviction.
+    pub ayes: u128,
+    /// The votes against, weighted by conviction.
+    pub nays: u128,
+    /// The turnout of `Democracy` referendums, or the support of `Referenda` referendums,
+    /// which is not weighted by conviction.
+    pub turnout: u128,
+}
+
+impl Tally {
+    /// Read a tally of `Democracy` or of the `ConvictionVoting` pallet used by `Referenda`.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let tally = match value {
+            Value::Composite(tally) => tally,
+            _ => return None,
+        };
+        let turnout = tally.field("turnout").or_else(|| tally.field("support"))?;
+        Some(Self {
+            ayes: tally.field("ayes")?.as_u128()?,
+            nays: tally.field("nays")?.as_u128()?,
+            turnout: turnout.as_u128()?,
+        })
+    }
+}
+
+/// The status of a referendum, see [`crate::Client::referendum_info`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReferendumInfo {
+    /// The referendum is being voted on.
+    Ongoing {
+        /// The block at which voting ends, which only `Democracy` referendums fix in advance.
+        end: Option<u64>,
+        /// The votes so far.
+        tally: Tally,
+    },
+    /// The referendum is over.
+    Finished {
+        /// Whether the referendum was approved. `Referenda` referendums which were rejected,
+        /// cancelled, timed out or killed were not.
+        approved: bool,
+        /// The block at which the referendum ended.
+        end: u64,
+    },
+}
+
+impl ReferendumInfo {
+    /// Read the `ReferendumInfo` of `Democracy` or of `Referenda`.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let (name, fields) = match value {
+            Value::Variant { name, fields } => (name.as_str(), fields),
+            _ => return None,
+        };
+        match name {
+            "Ongoing" => {
+                let status = match fields.values().first().copied()? {
+                    Value::Composite(status) => status,
+                    _ => return None,
+                };
+                Some(Self::Ongoing {
+                    end: status.field("end").and_then(uint).map(|end| end as u64),
+                    tally: Tally::from_value(status.field("tally")?)?,
+                })
+            }
+            // `Democracy` referendums.
+            "Finished" => {
+                Some(Self::Finished {
+                    approved: matches!(
+                        fields.field("approved")?,
+                        Value::Primitive(Primitive::Bool(true))
+                    ),
+                    end: u64::try_from(fields.field("end")?.as_u128()?).ok()?,
+                })
+            }
+            // `Referenda` referendums, whose outcomes start with the block they ended at.
+            outcome => {
+                Some(Self::Finished {
+                    approved: outcome == "Approved",
+                    end: u64::try_from(fields.values().first()?.as_u128()?).ok()?,
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Composite;
+
+    fn named(fields: Vec<(&str, Value)>) -> Composite {
+        Composite::Named(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        )
+    }
+
+    fn tally(turnout_field: &str) -> Value {
+        Value::Composite(named(vec![
+            ("ayes", Value::u128(3)),
+            ("nays", Value::u128(1)),
+            (turnout_field, Value::u128(4)),
+        ]))
+    }
+
+    #[test]
+    fn reads_democracy_referendums() {
+        let ongoing = Value::Variant {
+            name: "Ongoing".into(),
+            fields: Composite::Unnamed(vec![Value::Composite(named(vec![
+                ("end", Value::u128(100)),
+                ("tally", tally("turnout")),
+            ]))]),
+        };
+        assert_eq!(
+            ReferendumInfo::from_value(&ongoing),
+            Some(ReferendumInfo::Ongoing {
+                end: Some(100),
+                tally: Tally {
+                    ayes: 3,
+                    nays: 1,
+                    turnout: 4,
+                },
+            })
+        );
+
+        let finished = Value::Variant {
+            name: "Finished".into(),
+            fields: named(vec![
+                ("approved", Value::Primitive(Primitive::Bool(true))),
+                ("end", Value::u128(100)),
+            ]),
+        };
+        assert_eq!(
+            ReferendumInfo::from_value(&finished),
+            Some(ReferendumInfo::Finished {
+                approved: true,
+                end: 100,
+            })
+        );
+    }
+
+    #[test]
+    fn reads_referenda_referendums() {
+        let ongoing = Value::Variant {
+            name: "Ongoing".into(),
+            fields: Composite::Unnamed(vec![Value::Composite(named(vec![
+                ("submitted", Value::u128(10)),
+                ("tally", tally("support")),
+            ]))]),
+        };
+        assert!(matches!(
+            ReferendumInfo::from_value(&ongoing),
+            Some(ReferendumInfo::Ongoing { end: None, .. })
+        ));
+
+        let rejected = Value::Variant {
+            name: "Rejected".into(),
+            fields: Composite::Unnamed(vec![Value::u128(42)]),
+        };
+        assert_eq!(
+            ReferendumInfo::from_value(&rejected),
+            Some(ReferendumInfo::Finished {
+                approved: false,
+                end: 42,
+            })
+        );
+    }
+}
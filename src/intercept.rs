@@ -0,0 +1,67 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Observe, and optionally veto, every extrinsic submitted through a [`crate::Client`].
+
+use crate::value::Composite;
+
+/// Whether a [`SubmitInterceptor`] allows an extrinsic to be submitted.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    /// Submit the extrinsic.
+    Allow,
+    /// Do not submit the extrinsic, failing with [`crate::Error::SubmissionRejected`]
+    /// and the given reason.
+    Reject(String),
+}
+
+/// The call of an intercepted extrinsic, decoded using the metadata type registry.
+#[derive(Clone, Copy, Debug)]
+pub struct InterceptedCall<'a> {
+    /// The name of the pallet of the call.
+    pub pallet: &'a str,
+    /// The name of the call.
+    pub name: &'a str,
+    /// The call arguments.
+    pub args: &'a Composite,
+}
+
+/// An extrinsic about to be submitted.
+#[derive(Clone, Copy, Debug)]
+pub struct InterceptedExtrinsic<'a> {
+    /// The encoded extrinsic, as passed to `author_submitExtrinsic`.
+    pub encoded: &'a [u8],
+    /// The decoded call, or `None` if it could not be decoded with the metadata.
+    pub call: Option<InterceptedCall<'a>>,
+}
+
+/// Hook called right before an extrinsic is submitted by [`crate::SubmittableExtrinsic`] or
+/// [`crate::Client::submit_dynamic`], e.g. to log it or enforce a policy.
+///
+/// Extrinsics submitted directly through [`crate::rpc::Rpc`] are not intercepted.
+pub trait SubmitInterceptor: Send + Sync {
+    /// Decide whether to submit the extrinsic.
+    fn intercept(&self, extrinsic: &InterceptedExtrinsic) -> Verdict;
+}
+
+impl<F> SubmitInterceptor for F
+where
+    F: Fn(&InterceptedExtrinsic) -> Verdict + Send + Sync,
+{
+    fn intercept(&self, extrinsic: &InterceptedExtrinsic) -> Verdict {
+        self(extrinsic)
+    }
+}
@@ -58,21 +58,48 @@ use core::{
     marker::PhantomData,
 };
 
+pub mod balances;
+pub mod batch;
 mod client;
 mod config;
+pub mod contracts;
+pub mod dynamic;
 mod error;
-mod events;
+pub mod events;
 pub mod extrinsic;
+pub mod governance;
+pub mod intercept;
 mod metadata;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock;
+pub mod mortality;
+#[doc(hidden)]
+pub mod outer_enum;
+pub mod proof;
 pub mod rpc;
+pub mod scheduler;
+pub mod session;
+pub mod ss58;
+pub mod staking;
 pub mod storage;
 mod subscription;
+pub mod sudo;
+pub mod system;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod timestamp;
+pub mod tip;
 mod transaction;
+pub mod value;
+#[cfg(any(target_arch = "wasm32", test))]
+pub mod wasm;
+pub mod weight;
 
 pub use crate::{
     client::{
         Client,
         ClientBuilder,
+        ReadAt,
         SubmittableExtrinsic,
     },
     config::{
@@ -81,17 +108,30 @@ pub use crate::{
         ExtrinsicExtraData,
     },
     error::{
+        DecodeError,
         Error,
         PalletError,
         RuntimeError,
         TransactionError,
+        WatchedStatus,
     },
     events::{
+        BlockEvents,
+        DecodingMode,
+        DynamicEvent,
+        EventRecord,
+        EventsClient,
         EventsDecoder,
         RawEvent,
+        ScanOptions,
+        WellKnownEvent,
     },
     extrinsic::{
         DefaultExtra,
+        DefaultExtraParams,
+        ExtensionRegistry,
+        MetadataExtra,
+        MetadataExtraParams,
         PairSigner,
         SignedExtra,
         Signer,
@@ -99,32 +139,53 @@ pub use crate::{
     },
     metadata::{
         Metadata,
+        MetadataCache,
         MetadataError,
+        MetadataProvider,
         PalletMetadata,
+        StorageEntryInfo,
+        TypeRef,
     },
     rpc::{
         BlockNumber,
+        DispatchClass,
+        Health,
+        RawSubscription,
         ReadProof,
+        RetryPolicy,
         RpcClient,
+        RpcClientT,
+        RpcLog,
+        RuntimeDispatchInfo,
+        Subscription,
+        SyncState,
         SystemProperties,
     },
     storage::{
         KeyIter,
         StorageEntry,
         StorageEntryKey,
+        StorageKeyPager,
         StorageMapKey,
+        WellKnownKey,
     },
     subscription::{
+        BestBlock,
+        BestBlockSubscription,
+        CallSubscription,
         EventStorageSubscription,
         EventSubscription,
         FinalizedEventStorageSubscription,
+        WatchedCall,
     },
     transaction::{
+        BatchWatcher,
         TransactionEvents,
         TransactionInBlock,
         TransactionProgress,
         TransactionStatus,
     },
+    weight::Weight,
 };
 
 /// Call trait.
@@ -155,7 +216,7 @@ pub trait Event: Decode {
 
 /// Wraps an already encoded byte vector, prevents being encoded as a raw byte vector as part of
 /// the transaction payload
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, scale_info::TypeInfo)]
 pub struct Encoded(pub Vec<u8>);
 
 impl codec::Encode for Encoded {
@@ -15,11 +15,25 @@
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::{
-    collections::HashMap,
-    convert::TryFrom,
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+    convert::{
+        TryFrom,
+        TryInto,
+    },
+    sync::{
+        Arc,
+        Mutex,
+        MutexGuard,
+    },
 };
 
-use codec::Error as CodecError;
+use codec::{
+    Decode,
+    Error as CodecError,
+};
 
 use frame_metadata::{
     PalletConstantMetadata,
@@ -27,12 +41,19 @@ use frame_metadata::{
     RuntimeMetadataLastVersion,
     RuntimeMetadataPrefixed,
     StorageEntryMetadata,
+    StorageEntryModifier,
+    StorageEntryType,
+    StorageHasher,
     META_RESERVED,
 };
 
 use crate::{
+    rpc::Rpc,
+    value::Value,
     Call,
+    Config,
     Encoded,
+    Error,
 };
 use scale_info::{
     form::PortableForm,
@@ -52,6 +73,22 @@ pub enum MetadataError {
     /// Call is not in metadata.
     #[error("Call {0} not found")]
     CallNotFound(&'static str),
+    /// Call is not in the metadata of its pallet.
+    #[error("Call {pallet}::{call} not found")]
+    PalletCallNotFound {
+        /// Name of the pallet.
+        pallet: String,
+        /// Name of the call.
+        call: String,
+    },
+    /// Call index is not in the metadata of its pallet.
+    #[error("Pallet index {pallet}, call index {call} not found")]
+    CallIndexNotFound {
+        /// Index of the pallet.
+        pallet: u8,
+        /// Index of the call within the pallet.
+        call: u8,
+    },
     /// Event is not in metadata.
     #[error("Pallet {0}, Event {0} not found")]
     EventNotFound(u8, u8),
@@ -60,10 +97,13 @@ pub enum MetadataError {
     ErrorNotFound(u8, u8),
     /// Storage is not in metadata.
     #[error("Storage {0} not found")]
-    StorageNotFound(&'static str),
+    StorageNotFound(String),
     /// Storage type does not match requested type.
     #[error("Storage type error")]
     StorageTypeError,
+    /// Storage map keys cannot be recovered from the hashed storage key.
+    #[error("Storage {0} keys cannot be decoded: {1}")]
+    StorageKeyNotDecodable(&'static str, &'static str),
     /// Default error.
     #[error("Failed to decode default: {0}")]
     DefaultError(CodecError),
@@ -76,6 +116,17 @@ pub enum MetadataError {
     /// Type is not in metadata.
     #[error("Type {0} missing from type registry")]
     TypeNotFound(u32),
+    /// No type with the given path or name is in metadata.
+    #[error("Type {0} not found")]
+    TypePathNotFound(String),
+    /// Several types have the given path or name, e.g. instances of a generic type.
+    #[error("Type {0} is ambiguous, refer to it by id instead")]
+    AmbiguousTypePath(String),
+    /// The client was built without metadata.
+    #[error(
+        "Metadata unavailable: only raw RPC operations are supported by this client"
+    )]
+    Unavailable,
 }
 
 /// Runtime metadata.
@@ -88,13 +139,41 @@ pub struct Metadata {
 }
 
 impl Metadata {
+    /// Decode the metadata returned by the `state_getMetadata` RPC.
+    ///
+    /// Only V14 metadata is supported. Other versions fail with
+    /// [`Error::UnsupportedMetadataVersion`] before the rest of the bytes are decoded.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        // The magic number is followed by the index of the version in `RuntimeMetadata`.
+        if let Some((magic, [version, ..])) = bytes.get(..4).zip(bytes.get(4..)) {
+            if magic == &META_RESERVED.to_le_bytes()[..] && *version != 14 {
+                return Err(Error::UnsupportedMetadataVersion(*version))
+            }
+        }
+        let metadata = RuntimeMetadataPrefixed::decode(&mut &bytes[..])?;
+        Ok(metadata.try_into()?)
+    }
+
     /// Returns a reference to [`PalletMetadata`].
-    pub fn pallet(&self, name: &'static str) -> Result<&PalletMetadata, MetadataError> {
+    pub fn pallet(&self, name: &str) -> Result<&PalletMetadata, MetadataError> {
         self.pallets
             .get(name)
             .ok_or_else(|| MetadataError::PalletNotFound(name.to_string()))
     }
 
+    /// Returns true if the runtime has a pallet with the given name.
+    pub fn has_pallet(&self, name: &str) -> bool {
+        self.pallets.contains_key(name)
+    }
+
+    /// Returns a reference to the [`PalletMetadata`] of the pallet with the given index.
+    pub fn pallet_by_index(&self, index: u8) -> Result<&PalletMetadata, MetadataError> {
+        self.pallets
+            .values()
+            .find(|pallet| pallet.index == index)
+            .ok_or(MetadataError::PalletIndexNotFound(index))
+    }
+
     /// Returns the metadata for the event at the given pallet and event indices.
     pub fn event(
         &self,
@@ -126,10 +205,174 @@ impl Metadata {
         self.metadata.types.resolve(id)
     }
 
+    /// Returns the id of the type with the given path, e.g.
+    /// `pallet_contracts::storage::RawContractInfo`, or with the given name if no type has
+    /// that path.
+    pub fn type_id_by_path(&self, path: &str) -> Result<u32, MetadataError> {
+        let types = self.metadata.types.types();
+        let by_path: Vec<u32> = types
+            .iter()
+            .filter(|ty| ty.ty().path().segments().join("::") == path)
+            .map(|ty| ty.id())
+            .collect();
+        let ids = if by_path.is_empty() {
+            types
+                .iter()
+                .filter(|ty| {
+                    ty.ty().path().segments().last().map(String::as_str) == Some(path)
+                })
+                .map(|ty| ty.id())
+                .collect()
+        } else {
+            by_path
+        };
+        match ids[..] {
+            [id] => Ok(id),
+            [] => Err(MetadataError::TypePathNotFound(path.into())),
+            _ => Err(MetadataError::AmbiguousTypePath(path.into())),
+        }
+    }
+
+    /// Decode `bytes` as a value of the type `ty` of the type registry, given by its id or by
+    /// its path, see [`Metadata::type_id_by_path`].
+    ///
+    /// All of `bytes` must be consumed by the value.
+    pub fn decode_value<'a>(
+        &self,
+        ty: impl Into<TypeRef<'a>>,
+        bytes: &[u8],
+    ) -> Result<Value, Error> {
+        let type_id = match ty.into() {
+            TypeRef::Id(id) => id,
+            TypeRef::Path(path) => self.type_id_by_path(path)?,
+        };
+        let input = &mut &bytes[..];
+        let value = Value::decode(self, type_id, input)?;
+        if !input.is_empty() {
+            return Err(Error::Other(format!(
+                "{} bytes left over after decoding type {}",
+                input.len(),
+                type_id
+            )))
+        }
+        Ok(value)
+    }
+
     /// Return the runtime metadata.
     pub fn runtime_metadata(&self) -> &RuntimeMetadataLastVersion {
         &self.metadata
     }
+
+    /// Describe all storage entries of a pallet, in the order they are declared.
+    ///
+    /// Together with [`StorageClient::fetch_dynamic`](crate::storage::StorageClient::fetch_dynamic),
+    /// this allows reading storage without generated code.
+    pub fn storage_entries(
+        &self,
+        pallet: &str,
+    ) -> Result<Vec<StorageEntryInfo>, MetadataError> {
+        let pallet = self
+            .metadata
+            .pallets
+            .iter()
+            .find(|p| p.name == pallet)
+            .ok_or_else(|| MetadataError::PalletNotFound(pallet.to_string()))?;
+        pallet
+            .storage
+            .iter()
+            .flat_map(|storage| &storage.entries)
+            .map(|entry| StorageEntryInfo::new(self, entry))
+            .collect()
+    }
+
+    /// Describe the storage entry with the given name.
+    pub fn storage_entry(
+        &self,
+        pallet: &str,
+        entry: &str,
+    ) -> Result<StorageEntryInfo, MetadataError> {
+        StorageEntryInfo::new(self, self.pallet(pallet)?.storage(entry)?)
+    }
+}
+
+/// The shape of a storage entry, as described by the metadata.
+#[derive(Clone, Debug)]
+pub struct StorageEntryInfo {
+    /// The name of the entry.
+    pub name: String,
+    /// Whether a missing value reads as the default or as `None`.
+    pub modifier: StorageEntryModifier,
+    /// The hashers of the keys of a map, one per key. Empty for a plain entry.
+    pub hashers: Vec<StorageHasher>,
+    /// The type ids of the keys of a map, one per hasher. Empty for a plain entry.
+    pub key_types: Vec<u32>,
+    /// The type id of the value.
+    pub value_type: u32,
+    /// The SCALE encoded default value.
+    pub default: Vec<u8>,
+    /// The documentation of the entry.
+    pub docs: Vec<String>,
+}
+
+impl StorageEntryInfo {
+    fn new(
+        metadata: &Metadata,
+        entry: &StorageEntryMetadata<PortableForm>,
+    ) -> Result<Self, MetadataError> {
+        let (hashers, key_types, value_type) = match &entry.ty {
+            StorageEntryType::Plain(value) => (Vec::new(), Vec::new(), value.id()),
+            StorageEntryType::Map {
+                hashers,
+                key,
+                value,
+            } => {
+                // The key type of a map with multiple hashers is a tuple of the key types.
+                let key_types = if hashers.len() == 1 {
+                    vec![key.id()]
+                } else {
+                    match metadata.resolve_type(key.id()).map(|ty| ty.type_def()) {
+                        Some(scale_info::TypeDef::Tuple(tuple))
+                            if tuple.fields().len() == hashers.len() =>
+                        {
+                            tuple.fields().iter().map(|ty| ty.id()).collect()
+                        }
+                        _ => return Err(MetadataError::StorageTypeError),
+                    }
+                };
+                (hashers.clone(), key_types, value.id())
+            }
+        };
+        Ok(Self {
+            name: entry.name.clone(),
+            modifier: entry.modifier.clone(),
+            hashers,
+            key_types,
+            value_type,
+            default: entry.default.clone(),
+            docs: entry.docs.clone(),
+        })
+    }
+}
+
+/// A type of the type registry of the metadata, see [`Metadata::decode_value`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TypeRef<'a> {
+    /// The id of the type.
+    Id(u32),
+    /// The path or name of the type.
+    Path(&'a str),
+}
+
+impl From<u32> for TypeRef<'_> {
+    fn from(id: u32) -> Self {
+        TypeRef::Id(id)
+    }
+}
+
+impl<'a> From<&'a str> for TypeRef<'a> {
+    fn from(path: &'a str) -> Self {
+        TypeRef::Path(path)
+    }
 }
 
 /// Metadata for a specific pallet.
@@ -137,7 +380,7 @@ impl Metadata {
 pub struct PalletMetadata {
     index: u8,
     name: String,
-    calls: HashMap<String, u8>,
+    calls: HashMap<String, Variant<PortableForm>>,
     storage: HashMap<String, StorageEntryMetadata<PortableForm>>,
     constants: HashMap<String, PalletConstantMetadata<PortableForm>>,
 }
@@ -148,6 +391,11 @@ impl PalletMetadata {
         &self.name
     }
 
+    /// Get the index of the pallet.
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
     /// Encode a call based on this pallet metadata.
     pub fn encode_call<C>(&self, call: &C) -> Result<Encoded, MetadataError>
     where
@@ -156,20 +404,31 @@ impl PalletMetadata {
         let fn_index = self
             .calls
             .get(C::FUNCTION)
-            .ok_or(MetadataError::CallNotFound(C::FUNCTION))?;
-        let mut bytes = vec![self.index, *fn_index];
+            .ok_or(MetadataError::CallNotFound(C::FUNCTION))?
+            .index();
+        let mut bytes = vec![self.index, fn_index];
         bytes.extend(call.encode());
         Ok(Encoded(bytes))
     }
 
+    /// Return the type def variant for a call, describing its index and arguments.
+    pub fn call(&self, name: &str) -> Option<&Variant<PortableForm>> {
+        self.calls.get(name)
+    }
+
+    /// Return the type def variant for the call with the given index.
+    pub fn call_by_index(&self, index: u8) -> Option<&Variant<PortableForm>> {
+        self.calls.values().find(|call| call.index() == index)
+    }
+
     /// Return [`StorageEntryMetadata`] given some storage key.
     pub fn storage(
         &self,
-        key: &'static str,
+        key: &str,
     ) -> Result<&StorageEntryMetadata<PortableForm>, MetadataError> {
         self.storage
             .get(key)
-            .ok_or(MetadataError::StorageNotFound(key))
+            .ok_or_else(|| MetadataError::StorageNotFound(key.to_string()))
     }
 
     /// Get a constant's metadata by name.
@@ -231,6 +490,208 @@ impl ErrorMetadata {
     }
 }
 
+/// An in-memory cache of parsed metadata keyed by genesis hash and spec version, shared
+/// between clients by cloning it and passing it to [`crate::ClientBuilder::set_metadata_cache`].
+///
+/// A client connecting to a runtime which is already cached reuses its metadata instead of
+/// fetching and decoding it again. When a client sees a newer spec version of a chain, the
+/// metadata of the older runtimes of that chain is evicted.
+#[derive(Clone, Debug, Default)]
+pub struct MetadataCache {
+    entries: Arc<Mutex<HashMap<(Vec<u8>, u32), Arc<Metadata>>>>,
+}
+
+impl MetadataCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached metadata of a runtime, given the encoded genesis hash of its chain.
+    pub fn get(&self, genesis_hash: &[u8], spec_version: u32) -> Option<Arc<Metadata>> {
+        self.entries()
+            .get(&(genesis_hash.to_vec(), spec_version))
+            .cloned()
+    }
+
+    /// Caches the metadata of a runtime, evicting the metadata of the older runtimes of the
+    /// same chain.
+    pub fn insert(
+        &self,
+        genesis_hash: &[u8],
+        spec_version: u32,
+        metadata: Arc<Metadata>,
+    ) {
+        let mut entries = self.entries();
+        entries.retain(|(genesis, version), _| {
+            genesis != genesis_hash || *version > spec_version
+        });
+        entries.insert((genesis_hash.to_vec(), spec_version), metadata);
+    }
+
+    /// Evicts the metadata of a runtime.
+    pub fn invalidate(&self, genesis_hash: &[u8], spec_version: u32) {
+        self.entries()
+            .remove(&(genesis_hash.to_vec(), spec_version));
+    }
+
+    /// Evicts all metadata.
+    pub fn clear(&self) {
+        self.entries().clear();
+    }
+
+    /// Returns the number of cached runtimes.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    /// Returns `true` if no metadata is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<(Vec<u8>, u32), Arc<Metadata>>> {
+        self.entries.lock().expect("lock is never poisoned; qed")
+    }
+}
+
+/// Provides the metadata of the runtime at any block, fetching it once per spec version.
+///
+/// Unlike a [`MetadataCache`], the metadata of older runtimes is kept, so that blocks from
+/// either side of a runtime upgrade can be decoded. Clones share the cached metadata.
+#[derive(Clone)]
+pub struct MetadataProvider<T: Config> {
+    rpc: Rpc<T>,
+    runtimes: Arc<Mutex<HashMap<u32, Arc<Metadata>>>>,
+    spec_versions: Arc<Mutex<SpecVersions<T::Hash>>>,
+}
+
+impl<T: Config> MetadataProvider<T> {
+    /// Creates a provider fetching metadata using `rpc`, with nothing cached.
+    pub fn new(rpc: Rpc<T>) -> Self {
+        Self {
+            rpc,
+            runtimes: Default::default(),
+            spec_versions: Default::default(),
+        }
+    }
+
+    /// Returns the metadata of the runtime of the block with the given hash.
+    ///
+    /// The spec version of the runtime of the recently seen blocks is remembered, so that
+    /// their metadata is returned without a request to the node.
+    pub async fn at(&self, hash: T::Hash) -> Result<Arc<Metadata>, Error> {
+        let cached = self.spec_versions().get(&hash);
+        let spec_version = match cached {
+            Some(spec_version) => spec_version,
+            None => {
+                let spec_version =
+                    self.rpc.runtime_version(Some(hash)).await?.spec_version;
+                self.spec_versions().insert(hash, spec_version);
+                spec_version
+            }
+        };
+        self.for_spec_version(spec_version, hash).await
+    }
+
+    /// Returns the metadata of the runtime with the given spec version, fetching it at `hash`
+    /// if it is not cached yet. The runtime at `hash` must have that spec version.
+    pub async fn for_spec_version(
+        &self,
+        spec_version: u32,
+        hash: T::Hash,
+    ) -> Result<Arc<Metadata>, Error> {
+        if let Some(metadata) = self.get(spec_version) {
+            return Ok(metadata)
+        }
+        let metadata = Arc::new(self.rpc.metadata(Some(hash)).await?);
+        Ok(self
+            .runtimes()
+            .entry(spec_version)
+            .or_insert(metadata)
+            .clone())
+    }
+
+    /// Returns the cached metadata of the runtime with the given spec version.
+    pub fn get(&self, spec_version: u32) -> Option<Arc<Metadata>> {
+        self.runtimes().get(&spec_version).cloned()
+    }
+
+    /// Caches the metadata of the runtime with the given spec version.
+    pub fn insert(&self, spec_version: u32, metadata: Arc<Metadata>) {
+        self.runtimes().insert(spec_version, metadata);
+    }
+
+    /// Returns the number of cached runtimes.
+    pub fn len(&self) -> usize {
+        self.runtimes().len()
+    }
+
+    /// Returns `true` if no metadata is cached.
+    pub fn is_empty(&self) -> bool {
+        self.runtimes().is_empty()
+    }
+
+    fn runtimes(&self) -> MutexGuard<'_, HashMap<u32, Arc<Metadata>>> {
+        self.runtimes.lock().expect("lock is never poisoned; qed")
+    }
+
+    fn spec_versions(&self) -> MutexGuard<'_, SpecVersions<T::Hash>> {
+        self.spec_versions
+            .lock()
+            .expect("lock is never poisoned; qed")
+    }
+}
+
+/// The maximum number of blocks whose spec version a [`MetadataProvider`] remembers.
+const MAX_SPEC_VERSIONS: usize = 1024;
+
+/// The spec versions of the runtimes of the most recently seen blocks, by block hash.
+///
+/// The runtime of a block never changes, so only the number of entries needs bounding.
+struct SpecVersions<Hash> {
+    versions: HashMap<Hash, u32>,
+    /// The hashes in `versions`, oldest first.
+    order: VecDeque<Hash>,
+}
+
+impl<Hash> Default for SpecVersions<Hash> {
+    fn default() -> Self {
+        Self {
+            versions: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+}
+
+impl<Hash: Copy + Eq + std::hash::Hash> SpecVersions<Hash> {
+    fn get(&self, hash: &Hash) -> Option<u32> {
+        self.versions.get(hash).copied()
+    }
+
+    fn insert(&mut self, hash: Hash, spec_version: u32) {
+        if self.versions.insert(hash, spec_version).is_some() {
+            return
+        }
+        self.order.push_back(hash);
+        if self.order.len() > MAX_SPEC_VERSIONS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.versions.remove(&oldest);
+            }
+        }
+    }
+}
+
+impl<T: Config> std::fmt::Debug for MetadataProvider<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut spec_versions: Vec<_> = self.runtimes().keys().copied().collect();
+        spec_versions.sort_unstable();
+        f.debug_struct("MetadataProvider")
+            .field("spec_versions", &spec_versions)
+            .finish()
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum InvalidMetadataError {
     #[error("Invalid prefix")]
@@ -275,7 +736,7 @@ impl TryFrom<RuntimeMetadataPrefixed> for Metadata {
                     let calls = type_def_variant
                         .variants()
                         .iter()
-                        .map(|v| (v.name().clone(), v.index()))
+                        .map(|v| (v.name().clone(), v.clone()))
                         .collect();
                     Ok(calls)
                 })?;
@@ -0,0 +1,400 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! An in-memory [`RpcClientT`] for testing code which uses subxt without a running node.
+//!
+//! ```ignore
+//! let mock = MockClient::new();
+//! mock.set_metadata(metadata_bytes);
+//! mock.set_storage(key, value);
+//! let client: Client<DefaultConfig> = ClientBuilder::new()
+//!     .set_client(RpcClient::new(mock.clone()))
+//!     .build()
+//!     .await?;
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    time::Duration,
+};
+
+use futures::StreamExt;
+use jsonrpsee::types::{
+    to_json_value,
+    Error as RpcError,
+    JsonValue,
+};
+use serde::Serialize;
+use sp_core::{
+    storage::{
+        StorageData,
+        StorageKey,
+    },
+    Bytes,
+};
+use sp_version::RuntimeVersion;
+
+use crate::{
+    rpc::{
+        RawSubscription,
+        RpcClientT,
+    },
+    subscription::SystemEvents,
+};
+
+#[derive(Default)]
+struct MockState {
+    responses: HashMap<String, JsonValue>,
+    errors: HashMap<String, MockError>,
+    delay: Option<Duration>,
+    storage: HashMap<StorageKey, StorageData>,
+    headers: HashMap<String, JsonValue>,
+    latest_header: Option<JsonValue>,
+    notifications: HashMap<String, Vec<JsonValue>>,
+    active_subscriptions: usize,
+    unsubscribed: Vec<String>,
+}
+
+/// An error returned by requests to a method, instead of its response.
+struct MockError {
+    /// How many more requests fail, or `None` if they all do.
+    remaining: Option<u32>,
+    error: fn() -> RpcError,
+}
+
+impl MockState {
+    /// Returns the error the next request to `method` fails with, if any.
+    fn next_error(&mut self, method: &str) -> Option<RpcError> {
+        let mock_error = self.errors.get_mut(method)?;
+        match &mut mock_error.remaining {
+            Some(0) => return None,
+            Some(remaining) => *remaining -= 1,
+            None => (),
+        }
+        Some((mock_error.error)())
+    }
+}
+
+/// Counts a subscription as active until the stream holding it is dropped, which unsubscribes
+/// with `unsubscribe_method`.
+struct SubscriptionGuard {
+    state: Arc<Mutex<MockState>>,
+    unsubscribe_method: String,
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        let mut state = self
+            .state
+            .lock()
+            .expect("mock state is never poisoned; qed");
+        state.active_subscriptions -= 1;
+        state
+            .unsubscribed
+            .push(std::mem::take(&mut self.unsubscribe_method));
+    }
+}
+
+/// An rpc transport serving registered responses, for deterministic offline tests.
+///
+/// Clones share the same state, so responses can still be registered after the mock has been
+/// handed to a [`crate::ClientBuilder`].
+#[derive(Clone, Default)]
+pub struct MockClient {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockClient {
+    /// Creates a new mock with the responses required to build a [`crate::Client`], apart
+    /// from the metadata which must be registered with [`MockClient::set_metadata`].
+    pub fn new() -> Self {
+        let mock = Self::default();
+        mock.set_response("chain_getBlockHash", sp_core::H256::zero())
+            .set_response("state_getRuntimeVersion", RuntimeVersion::default())
+            .set_response("system_properties", serde_json::Map::new());
+        mock
+    }
+
+    /// Registers the response returned for every request to `method`.
+    pub fn set_response<R: Serialize>(&self, method: &str, response: R) -> &Self {
+        let response = to_json_value(response).expect("mock responses serialize to json");
+        self.state().responses.insert(method.to_string(), response);
+        self
+    }
+
+    /// Registers the error returned by every request to `method`, instead of its response.
+    pub fn set_error(&self, method: &str, error: fn() -> RpcError) -> &Self {
+        let error = MockError {
+            remaining: None,
+            error,
+        };
+        self.state().errors.insert(method.to_string(), error);
+        self
+    }
+
+    /// Registers the error returned by the next `failures` requests to `method`, after which
+    /// its response is returned again, e.g. to test retries of transient errors.
+    pub fn set_failures(
+        &self,
+        method: &str,
+        failures: u32,
+        error: fn() -> RpcError,
+    ) -> &Self {
+        let error = MockError {
+            remaining: Some(failures),
+            error,
+        };
+        self.state().errors.insert(method.to_string(), error);
+        self
+    }
+
+    /// Delays the response to every request by `delay`, e.g. to test request timeouts.
+    pub fn set_delay(&self, delay: Duration) -> &Self {
+        self.state().delay = Some(delay);
+        self
+    }
+
+    /// Registers the SCALE encoded metadata returned by `state_getMetadata`.
+    pub fn set_metadata(&self, metadata: Vec<u8>) -> &Self {
+        self.set_response("state_getMetadata", Bytes(metadata))
+    }
+
+    /// Registers a value returned by `state_getStorage` for the given key, at every block.
+    pub fn set_storage(&self, key: StorageKey, value: Vec<u8>) -> &Self {
+        self.state().storage.insert(key, StorageData(value));
+        self
+    }
+
+    /// Registers the SCALE encoded `System::Events` storage value.
+    pub fn set_events(&self, events: Vec<u8>) -> &Self {
+        self.set_storage(SystemEvents::new().into(), events)
+    }
+
+    /// Registers a header returned by `chain_getHeader` for the given hash.
+    ///
+    /// The most recently registered header is returned when no hash is requested.
+    pub fn set_header<H: Serialize, Header: Serialize>(
+        &self,
+        hash: H,
+        header: Header,
+    ) -> &Self {
+        let hash = to_json_value(hash).expect("mock hashes serialize to json");
+        let header = to_json_value(header).expect("mock headers serialize to json");
+        let mut state = self.state();
+        state.headers.insert(hash.to_string(), header.clone());
+        state.latest_header = Some(header);
+        drop(state);
+        self
+    }
+
+    /// Registers the notifications yielded by subscriptions to `subscribe_method`.
+    ///
+    /// The subscription ends once all the notifications have been yielded.
+    pub fn set_notifications<N: Serialize>(
+        &self,
+        subscribe_method: &str,
+        notifications: Vec<N>,
+    ) -> &Self {
+        let notifications = notifications
+            .into_iter()
+            .map(|n| to_json_value(n).expect("mock notifications serialize to json"))
+            .collect();
+        self.state()
+            .notifications
+            .insert(subscribe_method.to_string(), notifications);
+        self
+    }
+
+    /// Returns the number of subscriptions which have not been unsubscribed from, i.e. whose
+    /// stream has not been dropped yet.
+    pub fn active_subscriptions(&self) -> usize {
+        self.state().active_subscriptions
+    }
+
+    /// Returns the unsubscribe methods called so far, in the order the subscriptions were
+    /// dropped.
+    pub fn unsubscribed(&self) -> Vec<String> {
+        self.state().unsubscribed.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .expect("mock state is never poisoned; qed")
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcClientT for MockClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        let delay = self.state().delay;
+        if let Some(delay) = delay {
+            futures_timer::Delay::new(delay).await;
+        }
+        let mut state = self.state();
+        if let Some(error) = state.next_error(method) {
+            return Err(error)
+        }
+        let first_param = params.into_iter().next().unwrap_or(JsonValue::Null);
+        let response = match method {
+            "state_getStorage" => {
+                let key: StorageKey =
+                    serde_json::from_value(first_param).map_err(RpcError::ParseError)?;
+                return to_json_value(state.storage.get(&key))
+                    .map_err(RpcError::ParseError)
+            }
+            "chain_getHeader" if !first_param.is_null() => {
+                return Ok(state
+                    .headers
+                    .get(&first_param.to_string())
+                    .cloned()
+                    .unwrap_or(JsonValue::Null))
+            }
+            "chain_getHeader" => state.latest_header.clone(),
+            _ => state.responses.get(method).cloned(),
+        };
+        response.ok_or_else(|| {
+            RpcError::Custom(format!("MockClient: no response registered for {}", method))
+        })
+    }
+
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        _params: Vec<JsonValue>,
+        unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        let mut state = self.state();
+        let notifications = state
+            .notifications
+            .get(subscribe_method)
+            .cloned()
+            .ok_or_else(|| {
+                RpcError::Custom(format!(
+                    "MockClient: no notifications registered for {}",
+                    subscribe_method
+                ))
+            })?;
+        state.active_subscriptions += 1;
+        let guard = SubscriptionGuard {
+            state: self.state.clone(),
+            unsubscribe_method: unsubscribe_method.to_string(),
+        };
+        Ok(Box::pin(futures::stream::iter(notifications).map(
+            move |notification| {
+                let _ = &guard;
+                Ok(notification)
+            },
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rpc::{
+            Rpc,
+            RpcClient,
+        },
+        test_utils::{
+            empty_metadata,
+            MockConfig,
+        },
+        transaction::TransactionProgress,
+        ClientBuilder,
+    };
+
+    #[async_std::test]
+    async fn serves_registered_storage_and_headers() {
+        let mock = MockClient::new();
+        let key = StorageKey(vec![1, 2, 3]);
+        mock.set_storage(key.clone(), vec![4, 5])
+            .set_header(sp_core::H256::repeat_byte(1), "first")
+            .set_header(sp_core::H256::repeat_byte(2), "second");
+        let client = RpcClient::new(mock);
+
+        let params = [to_json_value(&key).unwrap(), JsonValue::Null];
+        let value: Option<StorageData> =
+            client.request("state_getStorage", &params).await.unwrap();
+        assert_eq!(value, Some(StorageData(vec![4, 5])));
+
+        let params = [to_json_value(StorageKey(vec![9])).unwrap()];
+        let value: Option<StorageData> =
+            client.request("state_getStorage", &params).await.unwrap();
+        assert_eq!(value, None);
+
+        let params = [to_json_value(sp_core::H256::repeat_byte(1)).unwrap()];
+        let header: String = client.request("chain_getHeader", &params).await.unwrap();
+        assert_eq!(header, "first");
+        let header: String = client.request("chain_getHeader", &[]).await.unwrap();
+        assert_eq!(header, "second");
+
+        assert!(client
+            .request::<JsonValue>("author_rotateKeys", &[])
+            .await
+            .is_err());
+    }
+
+    #[async_std::test]
+    async fn dropping_a_subscription_unsubscribes() {
+        let mock = MockClient::new();
+        mock.set_notifications("author_submitAndWatchExtrinsic", vec!["ready"]);
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(mock.clone()));
+
+        let mut watch = rpc.watch_extrinsic(vec![0u8]).await.unwrap();
+        let other = rpc.watch_extrinsic(vec![1u8]).await.unwrap();
+        assert_eq!(mock.active_subscriptions(), 2);
+
+        assert!(watch.next().await.unwrap().is_some());
+        drop(watch);
+        assert_eq!(mock.active_subscriptions(), 1);
+        drop(other);
+        assert_eq!(mock.active_subscriptions(), 0);
+    }
+
+    #[async_std::test]
+    async fn dropping_or_cancelling_transaction_progress_unwatches() {
+        let mock = MockClient::new();
+        mock.set_notifications("author_submitAndWatchExtrinsic", vec!["ready"]);
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(mock.clone()));
+        let client = ClientBuilder::new().build_offline::<MockConfig>(
+            empty_metadata(),
+            Default::default(),
+            RuntimeVersion::default(),
+        );
+
+        let sub = rpc.watch_extrinsic(vec![0u8]).await.unwrap();
+        drop(TransactionProgress::new(sub, &client, Default::default()));
+        assert_eq!(mock.unsubscribed(), ["author_unwatchExtrinsic"]);
+
+        let sub = rpc.watch_extrinsic(vec![1u8]).await.unwrap();
+        TransactionProgress::new(sub, &client, Default::default()).cancel();
+        assert_eq!(
+            mock.unsubscribed(),
+            ["author_unwatchExtrinsic", "author_unwatchExtrinsic"]
+        );
+        assert_eq!(mock.active_subscriptions(), 0);
+    }
+}
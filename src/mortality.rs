@@ -0,0 +1,85 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction mortality, for computing eras independently of a node, e.g. when signing
+//! offline.
+//!
+//! [`Era`] is the era type of Substrate itself, so it encodes exactly as the runtime expects.
+//! [`Era::mortal`] quantizes a period and the current block number into an era,
+//! [`Era::immortal`] never expires, and [`Era::birth`] and [`Era::death`] return the first
+//! block the transaction is valid in and the first block it is no longer valid in.
+
+pub use sp_runtime::generic::Era;
+
+/// Returns a mortal era valid for about `period` blocks from `current_block`, and the number of
+/// the block it is anchored to.
+///
+/// The hash of that block must be signed as the era checkpoint, see
+/// [`DefaultExtraParams::era`](crate::DefaultExtraParams::era).
+pub fn mortal(period: u64, current_block: u64) -> (Era, u64) {
+    let era = Era::mortal(period, current_block);
+    (era, era.birth(current_block))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::{
+        Decode,
+        Encode,
+    };
+
+    #[test]
+    fn immortal_era_encodes_as_zero() {
+        assert_eq!(Era::immortal().encode(), vec![0]);
+        assert_eq!(Era::immortal().birth(100), 0);
+        assert_eq!(Era::immortal().death(100), u64::MAX);
+    }
+
+    #[test]
+    fn mortal_eras_match_known_encodings() {
+        let era = Era::mortal(64, 42);
+        assert_eq!(era, Era::Mortal(64, 42));
+        assert_eq!(era.encode(), vec![5 + 42 % 16 * 16, 42 / 16]);
+        assert_eq!(Era::decode(&mut &era.encode()[..]).unwrap(), era);
+
+        // Phases of periods above 4096 are quantized.
+        let era = Era::mortal(32768, 20000);
+        assert_eq!(era, Era::Mortal(32768, 20000));
+        assert_eq!(era.encode(), vec![14 + 2500 % 16 * 16, 2500 / 16]);
+        assert_eq!(Era::decode(&mut &era.encode()[..]).unwrap(), era);
+    }
+
+    #[test]
+    fn mortal_periods_are_rounded_to_powers_of_two() {
+        assert_eq!(Era::mortal(0, 0), Era::Mortal(4, 0));
+        assert_eq!(Era::mortal(200, 513), Era::Mortal(256, 1));
+        assert_eq!(Era::mortal(2, 1), Era::Mortal(4, 1));
+        assert_eq!(Era::mortal(4, 5), Era::Mortal(4, 1));
+    }
+
+    #[test]
+    fn birth_and_death_bound_the_validity() {
+        let (era, birth) = mortal(64, 100);
+        assert_eq!(era, Era::Mortal(64, 36));
+        assert_eq!(birth, 100);
+        assert_eq!(era.death(100), 164);
+
+        // Later blocks within the period share the same birth block.
+        assert_eq!(era.birth(163), 100);
+        assert_eq!(era.death(163), 164);
+    }
+}
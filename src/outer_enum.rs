@@ -0,0 +1,109 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding support for the outer `Call` and `Event` enums generated by the [`crate::subxt`]
+//! macro.
+//!
+//! The generated enums have a variant per pallet, wrapping the pallet's own `Call` or `Event`
+//! enum, plus an `Other(pallet_index, variant_index, fields)` catch-all. Calls and events of a
+//! pallet or variant which did not exist when the code was generated decode into `Other`
+//! instead of failing, so that a runtime upgrade does not break decoding until the code is
+//! regenerated.
+//!
+//! The length of the fields of an unknown variant is not known, so `Other` takes the rest of
+//! the input. Decode the outer enums from exactly the bytes of one call or event, e.g.
+//! [`crate::RawEvent::bytes`].
+
+use codec::{
+    Decode,
+    Error as CodecError,
+    Input,
+};
+
+/// Decode a pallet `Call` or `Event` enum whose variant index was already read from `input`.
+pub fn decode_variant<T: Decode, I: Input>(
+    variant_index: u8,
+    input: &mut I,
+) -> Result<T, CodecError> {
+    T::decode(&mut VariantPrefixed {
+        variant_index: Some(variant_index),
+        input,
+    })
+}
+
+/// Read the encoded fields of an unknown variant, which are the rest of `input`.
+pub fn decode_unknown<I: Input>(input: &mut I) -> Result<Vec<u8>, CodecError> {
+    let len = input.remaining_len()?.ok_or_else(|| {
+        CodecError::from("Cannot decode an unknown variant from input of unknown length")
+    })?;
+    let mut fields = vec![0; len];
+    input.read(&mut fields)?;
+    Ok(fields)
+}
+
+/// Puts a variant index read ahead back in front of the input.
+struct VariantPrefixed<'a, I> {
+    variant_index: Option<u8>,
+    input: &'a mut I,
+}
+
+impl<'a, I: Input> Input for VariantPrefixed<'a, I> {
+    fn remaining_len(&mut self) -> Result<Option<usize>, CodecError> {
+        let prefix_len = self.variant_index.map_or(0, |_| 1);
+        Ok(self.input.remaining_len()?.map(|len| len + prefix_len))
+    }
+
+    fn read(&mut self, into: &mut [u8]) -> Result<(), CodecError> {
+        match (self.variant_index, into.split_first_mut()) {
+            (Some(variant_index), Some((first, rest))) => {
+                *first = variant_index;
+                self.variant_index = None;
+                self.input.read(rest)
+            }
+            _ => self.input.read(into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::Encode;
+
+    #[derive(Debug, Decode, Encode, PartialEq)]
+    enum PalletEvent {
+        #[codec(index = 2)]
+        Deposit(u32, u64),
+    }
+
+    #[test]
+    fn decode_variant_puts_the_variant_index_back() {
+        let encoded = PalletEvent::Deposit(1, 2).encode();
+        let (variant_index, mut fields) = (encoded[0], &encoded[1..]);
+        assert_eq!(
+            decode_variant::<PalletEvent, _>(variant_index, &mut fields).unwrap(),
+            PalletEvent::Deposit(1, 2)
+        );
+        assert!(fields.is_empty());
+    }
+
+    #[test]
+    fn decode_unknown_takes_the_rest_of_the_input() {
+        let mut input = &[1u8, 2, 3][..];
+        assert_eq!(decode_unknown(&mut input).unwrap(), vec![1, 2, 3]);
+        assert!(input.is_empty());
+    }
+}
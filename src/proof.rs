@@ -0,0 +1,183 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prove that an extrinsic was included in a block, e.g. to relay it to another chain.
+//!
+//! The extrinsics of a block are stored in a trie ordered by their index, whose root is the
+//! `extrinsics_root` of the block header. See [`crate::Client::extrinsic_inclusion_proof`].
+
+use codec::{
+    Compact,
+    Encode,
+};
+use sp_trie::{
+    Layout,
+    MemoryDB,
+    TrieDBMut,
+    TrieMut,
+};
+
+use crate::{
+    Config,
+    Error,
+};
+
+/// A proof that an extrinsic is included in the block with the given extrinsics root.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtrinsicInclusionProof<T: Config> {
+    /// The hash of the block including the extrinsic.
+    pub block_hash: T::Hash,
+    /// The `extrinsics_root` of the header of the block.
+    pub extrinsics_root: T::Hash,
+    /// The index of the extrinsic in the block.
+    pub index: u32,
+    /// The encoded extrinsic.
+    pub extrinsic: Vec<u8>,
+    /// The trie nodes proving the extrinsic under its index.
+    pub proof: Vec<Vec<u8>>,
+}
+
+impl<T: Config> ExtrinsicInclusionProof<T> {
+    /// Verify the proof against its extrinsics root, which should be checked to match a header
+    /// which is already trusted, e.g. one which has been finalized.
+    pub fn verify(&self) -> Result<(), Error> {
+        verify_extrinsic_proof::<T>(
+            &self.extrinsics_root,
+            self.index,
+            &self.extrinsic,
+            &self.proof,
+        )
+    }
+}
+
+/// Build the proof that the extrinsic at `index` of `extrinsics` is included in the block
+/// whose header has the `extrinsics_root` given.
+///
+/// Fails if `extrinsics` do not hash to `extrinsics_root`, or there is no extrinsic at `index`.
+pub fn prove_extrinsic<T: Config>(
+    block_hash: T::Hash,
+    extrinsics_root: T::Hash,
+    extrinsics: &[T::Extrinsic],
+    index: u32,
+) -> Result<ExtrinsicInclusionProof<T>, Error> {
+    let extrinsic = extrinsics
+        .get(index as usize)
+        .ok_or_else(|| {
+            Error::InvalidProof(format!("The block has no extrinsic at index {}", index))
+        })?
+        .encode();
+
+    let mut db = MemoryDB::<T::Hashing>::default();
+    let mut root = T::Hash::default();
+    {
+        let mut trie = TrieDBMut::<Layout<T::Hashing>>::new(&mut db, &mut root);
+        for (i, extrinsic) in extrinsics.iter().enumerate() {
+            trie.insert(&Compact(i as u32).encode(), &extrinsic.encode())
+                .map_err(|e| Error::InvalidProof(format!("{:?}", e)))?;
+        }
+    }
+    if root != extrinsics_root {
+        return Err(Error::InvalidProof(format!(
+            "The extrinsics hash to the root {:?} rather than {:?}",
+            root, extrinsics_root
+        )))
+    }
+
+    let key = Compact(index).encode();
+    let proof =
+        sp_trie::generate_trie_proof::<Layout<T::Hashing>, _, _, _>(&db, root, &[key])
+            .map_err(|e| Error::InvalidProof(format!("{:?}", e)))?;
+    Ok(ExtrinsicInclusionProof {
+        block_hash,
+        extrinsics_root,
+        index,
+        extrinsic,
+        proof,
+    })
+}
+
+/// Verify that `proof` shows the encoded `extrinsic` at `index` of the block whose header has
+/// the `extrinsics_root` given.
+pub fn verify_extrinsic_proof<T: Config>(
+    extrinsics_root: &T::Hash,
+    index: u32,
+    extrinsic: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<(), Error> {
+    let items = [(Compact(index).encode(), Some(extrinsic))];
+    sp_trie::verify_trie_proof::<Layout<T::Hashing>, _, _, _>(
+        extrinsics_root,
+        proof,
+        &items,
+    )
+    .map_err(|e| Error::InvalidProof(format!("{:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockConfig;
+    use sp_runtime::{
+        traits::Hash as _,
+        OpaqueExtrinsic,
+    };
+
+    fn extrinsics() -> Vec<OpaqueExtrinsic> {
+        (0..3u8)
+            .map(|i| OpaqueExtrinsic::from_bytes(&vec![i; 4].encode()).unwrap())
+            .collect()
+    }
+
+    fn extrinsics_root(extrinsics: &[OpaqueExtrinsic]) -> sp_core::H256 {
+        sp_runtime::traits::BlakeTwo256::ordered_trie_root(
+            extrinsics.iter().map(Encode::encode).collect(),
+        )
+    }
+
+    #[test]
+    fn proves_extrinsic_inclusion() {
+        let extrinsics = extrinsics();
+        let root = extrinsics_root(&extrinsics);
+        let proof =
+            prove_extrinsic::<MockConfig>(Default::default(), root, &extrinsics, 1)
+                .unwrap();
+        assert_eq!(proof.extrinsic, extrinsics[1].encode());
+        proof.verify().unwrap();
+
+        // The proof does not hold for another extrinsic.
+        let other = extrinsics[2].encode();
+        assert!(
+            verify_extrinsic_proof::<MockConfig>(&root, 1, &other, &proof.proof).is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_extrinsics_not_matching_the_root() {
+        let extrinsics = extrinsics();
+        let root = extrinsics_root(&extrinsics[..2]);
+        assert!(
+            prove_extrinsic::<MockConfig>(Default::default(), root, &extrinsics, 0)
+                .is_err()
+        );
+        assert!(prove_extrinsic::<MockConfig>(
+            Default::default(),
+            extrinsics_root(&extrinsics),
+            &extrinsics,
+            3
+        )
+        .is_err());
+    }
+}
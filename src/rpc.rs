@@ -21,32 +21,42 @@
 // Related: https://github.com/paritytech/subxt/issues/66
 #![allow(irrefutable_let_patterns)]
 
-use std::sync::Arc;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{
+        BuildHasher,
+        Hasher,
+    },
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
 
 use codec::{
     Decode,
     Encode,
 };
-use core::{
-    convert::TryInto,
-    marker::PhantomData,
+use core::marker::PhantomData;
+use futures::{
+    future::Either,
+    Stream,
+    StreamExt,
+};
+use jsonrpsee::types::{
+    to_json_value,
+    DeserializeOwned,
+    Error as RpcError,
+    JsonValue,
 };
-use frame_metadata::RuntimeMetadataPrefixed;
+#[cfg(not(target_arch = "wasm32"))]
 use jsonrpsee::{
     http_client::{
         HttpClient,
         HttpClientBuilder,
     },
-    types::{
-        to_json_value,
-        traits::{
-            Client,
-            SubscriptionClient,
-        },
-        DeserializeOwned,
-        Error as RpcError,
-        JsonValue,
-        Subscription,
+    types::traits::{
+        Client,
+        SubscriptionClient,
     },
     ws_client::{
         WsClient,
@@ -71,6 +81,8 @@ use sp_runtime::generic::{
     SignedBlock,
 };
 use sp_version::RuntimeVersion;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::RwLock;
 
 use crate::{
     error::Error,
@@ -80,6 +92,7 @@ use crate::{
         FinalizedEventStorageSubscription,
         SystemEvents,
     },
+    weight::Weight,
     Config,
     Metadata,
 };
@@ -131,8 +144,44 @@ impl From<u32> for BlockNumber {
     }
 }
 
-/// Arbitrary properties defined in the chain spec as a JSON object.
-pub type SystemProperties = serde_json::Map<String, serde_json::Value>;
+impl From<u64> for BlockNumber {
+    fn from(x: u64) -> Self {
+        NumberOrHex::Number(x).into()
+    }
+}
+
+/// Properties defined in the chain spec.
+///
+/// The well known properties are parsed into typed fields, normalising chains which provide a
+/// single token symbol or decimals value rather than a list. Any other properties remain
+/// available as JSON in [`SystemProperties::other`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemProperties {
+    /// The SS58 address format of the chain, if defined.
+    #[serde(default)]
+    pub ss58_format: Option<u16>,
+    /// The symbols of the chain's tokens, the first being the native token.
+    #[serde(default, deserialize_with = "list_or_value")]
+    pub token_symbol: Vec<String>,
+    /// The decimals of the chain's tokens, the first being the native token.
+    #[serde(default, deserialize_with = "list_or_value")]
+    pub token_decimals: Vec<u32>,
+    /// Any other properties defined in the chain spec.
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+fn list_or_value<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(match ListOrValue::<T>::deserialize(deserializer)? {
+        ListOrValue::List(list) => list,
+        ListOrValue::Value(value) => vec![value],
+    })
+}
 
 /// Possible transaction status events.
 ///
@@ -167,15 +216,477 @@ pub enum SubstrateTransactionStatus<Hash, BlockHash> {
     Invalid,
 }
 
-/// Rpc client wrapper.
-/// This is workaround because adding generic types causes the macros to fail.
+/// A stream of raw JSON notifications returned by [`RpcClientT::subscribe_raw`].
+pub type RawSubscription =
+    Pin<Box<dyn Stream<Item = Result<JsonValue, RpcError>> + Send + 'static>>;
+
+/// A JSON-RPC transport used to talk to a substrate node.
+///
+/// Implemented for the jsonrpsee `WsClient` and `HttpClient`, or the browser based `WasmClient`
+/// when compiled to wasm. Implement this to plug an externally constructed client (e.g. with
+/// custom TLS, proxies or middleware) into [`RpcClient::new`], or to serve canned responses in
+/// tests.
+#[async_trait::async_trait]
+pub trait RpcClientT: Send + Sync + 'static {
+    /// Make a JSON-RPC request, returning the raw JSON result.
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError>;
+
+    /// Start a JSON-RPC subscription, returning a stream of raw JSON notifications.
+    ///
+    /// Dropping the returned stream is expected to unsubscribe using `unsubscribe_method`.
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        params: Vec<JsonValue>,
+        unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError>;
+
+    /// Close the connection, ending any active subscriptions.
+    ///
+    /// Requests made after disconnecting should fail. Does nothing by default.
+    async fn disconnect(&self) -> Result<(), RpcError> {
+        Ok(())
+    }
+}
+
+/// A subscription to a JSON-RPC method, yielding notifications of type `T`.
+pub struct Subscription<T> {
+    inner: RawSubscription,
+    idle_timeout: Option<(Duration, futures_timer::Delay)>,
+    stalled: bool,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> std::fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field(
+                "idle_timeout",
+                &self.idle_timeout.as_ref().map(|(timeout, _)| timeout),
+            )
+            .field("stalled", &self.stalled)
+            .finish()
+    }
+}
+
+impl<T: DeserializeOwned> Subscription<T> {
+    /// Create a new [`Subscription`] decoding notifications from the given raw stream.
+    pub fn new(inner: RawSubscription) -> Self {
+        Self {
+            inner,
+            idle_timeout: None,
+            stalled: false,
+            marker: PhantomData,
+        }
+    }
+
+    /// Give up on the subscription if no notification arrives within `timeout` of the previous
+    /// one, or of calling this method for the first notification.
+    ///
+    /// [`Subscription::next`] then returns [`Error::SubscriptionStalled`] once, and `None`
+    /// afterwards, so that a node which silently stopped sending notifications is detected.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some((timeout, futures_timer::Delay::new(timeout)));
+        self
+    }
+
+    /// Returns the next notification, or `None` if the subscription was closed.
+    pub async fn next(&mut self) -> Result<Option<T>, Error> {
+        if self.stalled {
+            return Ok(None)
+        }
+        let next = match &mut self.idle_timeout {
+            Some((timeout, delay)) => {
+                match futures::future::select(self.inner.next(), delay).await {
+                    Either::Left((next, delay)) => {
+                        delay.reset(*timeout);
+                        next
+                    }
+                    Either::Right(_) => {
+                        self.stalled = true;
+                        return Err(Error::SubscriptionStalled(*timeout))
+                    }
+                }
+            }
+            None => self.inner.next().await,
+        };
+        match next {
+            Some(Ok(value)) => {
+                serde_json::from_value(value)
+                    .map(Some)
+                    .map_err(|err| RpcError::ParseError(err).into())
+            }
+            Some(Err(err)) => Err(err.into()),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait::async_trait]
+impl RpcClientT for WsClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        Client::request(self, method, Some(params.into())).await
+    }
+
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        params: Vec<JsonValue>,
+        unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        let sub = SubscriptionClient::subscribe::<JsonValue>(
+            self,
+            subscribe_method,
+            Some(params.into()),
+            unsubscribe_method,
+        )
+        .await?;
+        let stream = futures::stream::unfold(sub, |mut sub| {
+            async move {
+                match sub.next().await {
+                    Ok(Some(value)) => Some((Ok(value), sub)),
+                    Ok(None) => None,
+                    Err(err) => Some((Err(err), sub)),
+                }
+            }
+        });
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait::async_trait]
+impl RpcClientT for HttpClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        Client::request(self, method, Some(params.into())).await
+    }
+
+    async fn subscribe_raw(
+        &self,
+        _subscribe_method: &str,
+        _params: Vec<JsonValue>,
+        _unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        Err(RpcError::Custom(
+            "Subscriptions not supported on HTTP transport".to_owned(),
+        ))
+    }
+}
+
+/// How often [`RpcClientT::disconnect`] checks whether the requests in flight have completed.
+#[cfg(not(target_arch = "wasm32"))]
+const DISCONNECT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[cfg(not(target_arch = "wasm32"))]
+/// A [`WsClient`] owned by subxt, which can therefore be explicitly disconnected.
+struct OwnedWsClient(RwLock<Option<Arc<WsClient>>>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl OwnedWsClient {
+    fn new(client: WsClient) -> Self {
+        Self(RwLock::new(Some(Arc::new(client))))
+    }
+
+    fn client(&self) -> Result<Arc<WsClient>, RpcError> {
+        self.0
+            .read()
+            .expect("lock is never poisoned; qed")
+            .clone()
+            .ok_or_else(|| RpcError::Custom("Client has been disconnected".into()))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[async_trait::async_trait]
+impl RpcClientT for OwnedWsClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        let client = self.client()?;
+        client.request_raw(method, params).await
+    }
+
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        params: Vec<JsonValue>,
+        unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        let client = self.client()?;
+        client
+            .subscribe_raw(subscribe_method, params, unsubscribe_method)
+            .await
+    }
+
+    async fn disconnect(&self) -> Result<(), RpcError> {
+        let mut client = match self.0.write().expect("lock is never poisoned; qed").take()
+        {
+            Some(client) => client,
+            None => return Ok(()),
+        };
+        // Dropping the last reference terminates the background task of the client, which
+        // closes the connection and ends its subscriptions. Requests still in flight hold
+        // references of their own, so wait for them to complete first.
+        loop {
+            match Arc::try_unwrap(client) {
+                Ok(client) => {
+                    drop(client);
+                    return Ok(())
+                }
+                Err(shared) => {
+                    client = shared;
+                    futures_timer::Delay::new(DISCONNECT_POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
+
+/// Policy for retrying RPC requests which failed with a transient error.
+///
+/// Each retry waits for `base_delay` doubled by the number of previous retries, up to
+/// `max_delay`. With jitter enabled, a random delay between half and all of that is used
+/// instead, so that many clients do not retry in lockstep.
+///
+/// Subscriptions are not retried, and neither are responses which fail to decode.
 #[derive(Clone)]
-pub enum RpcClient {
-    /// JSONRPC client WebSocket transport.
-    WebSocket(Arc<WsClient>),
-    /// JSONRPC client HTTP transport.
-    // NOTE: Arc because `HttpClient` is not clone.
-    Http(Arc<HttpClient>),
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    is_retryable: fn(&RpcError) -> bool,
+}
+
+impl std::fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy making at most `max_attempts` attempts at each request, including the
+    /// first one, with a base delay of 100ms and a maximum delay of 10s.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            is_retryable: Self::is_transient,
+        }
+    }
+
+    /// Set the delay before the first retry.
+    pub fn set_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Set the maximum delay between retries.
+    pub fn set_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Set whether the delays are randomized.
+    pub fn set_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set which errors are retried. Defaults to [`RetryPolicy::is_transient`].
+    pub fn set_retryable(mut self, is_retryable: fn(&RpcError) -> bool) -> Self {
+        self.is_retryable = is_retryable;
+        self
+    }
+
+    /// Returns `true` for transport errors and timeouts.
+    pub fn is_transient(err: &RpcError) -> bool {
+        matches!(err, RpcError::Transport(_) | RpcError::RequestTimeout)
+    }
+
+    /// The delay before the given retry, counting from zero.
+    fn delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(retry))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        if self.jitter {
+            let random = RandomState::new().build_hasher().finish();
+            delay / 2 + delay.mul_f64((random % 1000) as f64 / 2000.0)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Applies a [`RetryPolicy`] to the requests of a transport.
+struct RetryClient {
+    inner: Arc<dyn RpcClientT>,
+    policy: RetryPolicy,
+}
+
+#[async_trait::async_trait]
+impl RpcClientT for RetryClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        let mut retry = 0;
+        loop {
+            match self.inner.request_raw(method, params.clone()).await {
+                Err(err)
+                    if retry + 1 < self.policy.max_attempts
+                        && (self.policy.is_retryable)(&err) =>
+                {
+                    let delay = self.policy.delay(retry);
+                    log::debug!(
+                        "retrying {} in {:?} after error: {}",
+                        method,
+                        delay,
+                        err
+                    );
+                    futures_timer::Delay::new(delay).await;
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        params: Vec<JsonValue>,
+        unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        self.inner
+            .subscribe_raw(subscribe_method, params, unsubscribe_method)
+            .await
+    }
+
+    async fn disconnect(&self) -> Result<(), RpcError> {
+        self.inner.disconnect().await
+    }
+}
+
+/// A request made through an [`RpcClient`] with a logger set by
+/// [`RpcClient::with_logger`], and the response of the node.
+#[derive(Debug)]
+pub struct RpcLog<'a> {
+    /// The method called.
+    pub method: &'a str,
+    /// The parameters of the call.
+    pub params: &'a [JsonValue],
+    /// The raw result returned by the node, or the error the request failed with.
+    pub result: Result<&'a JsonValue, &'a RpcError>,
+}
+
+/// Calls a logger with the requests of a transport and their responses.
+struct LoggingClient {
+    inner: Arc<dyn RpcClientT>,
+    logger: Arc<dyn Fn(&RpcLog) + Send + Sync>,
+}
+
+#[async_trait::async_trait]
+impl RpcClientT for LoggingClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        let result = self.inner.request_raw(method, params.clone()).await;
+        (self.logger)(&RpcLog {
+            method,
+            params: &params,
+            result: result.as_ref(),
+        });
+        result
+    }
+
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        params: Vec<JsonValue>,
+        unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        self.inner
+            .subscribe_raw(subscribe_method, params, unsubscribe_method)
+            .await
+    }
+
+    async fn disconnect(&self) -> Result<(), RpcError> {
+        self.inner.disconnect().await
+    }
+}
+
+/// The transport of clients built with [`crate::ClientBuilder::build_offline`], which fails
+/// every request.
+pub(crate) struct OfflineClient;
+
+#[async_trait::async_trait]
+impl RpcClientT for OfflineClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        _params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        Err(RpcError::Custom(format!(
+            "Cannot call {} on an offline client",
+            method
+        )))
+    }
+
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        _params: Vec<JsonValue>,
+        _unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        Err(RpcError::Custom(format!(
+            "Cannot subscribe to {} on an offline client",
+            subscribe_method
+        )))
+    }
+}
+
+/// The request timeout of the transports built by [`RpcClient::try_from_url`], which is the
+/// default of `jsonrpsee`.
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The timeout the transports built by [`RpcClient::try_from_url`] enforce themselves, which
+/// bounds the timeouts set with [`RpcClient::with_request_timeout`].
+#[cfg(not(target_arch = "wasm32"))]
+const TRANSPORT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Rpc client wrapper, dispatching to a type erased [`RpcClientT`] transport.
+#[derive(Clone)]
+pub struct RpcClient {
+    transport: Arc<dyn RpcClientT>,
+    request_timeout: Option<Duration>,
 }
 
 impl RpcClient {
@@ -184,34 +695,93 @@ impl RpcClient {
     /// Infers the protocol from the URL, supports:
     ///     - Websockets (`ws://`, `wss://`)
     ///     - Http (`http://`, `https://`)
+    ///
+    /// When compiled to `wasm32`, only websockets are supported, through the browser
+    /// `WebSocket` API.
+    ///
+    /// Requests time out after 60 seconds, unless overridden with
+    /// [`RpcClient::with_request_timeout`] or per request with
+    /// [`RpcClient::request_with_timeout`].
+    #[cfg(not(target_arch = "wasm32"))]
     pub async fn try_from_url(url: &str) -> Result<Self, Error> {
-        if url.starts_with("ws://") || url.starts_with("wss://") {
+        let client = if url.starts_with("ws://") || url.starts_with("wss://") {
             let client = WsClientBuilder::default()
                 .max_notifs_per_subscription(4096)
+                .request_timeout(TRANSPORT_REQUEST_TIMEOUT)
                 .build(url)
                 .await?;
-            Ok(RpcClient::WebSocket(Arc::new(client)))
+            RpcClient::new(OwnedWsClient::new(client))
+        } else {
+            let client = HttpClientBuilder::default()
+                .request_timeout(TRANSPORT_REQUEST_TIMEOUT)
+                .build(&url)?;
+            RpcClient::new(client)
+        };
+        Ok(client.with_request_timeout(Some(DEFAULT_REQUEST_TIMEOUT)))
+    }
+
+    /// Create a new [`RpcClient`] from the given websocket URL (`ws://`, `wss://`).
+    #[cfg(target_arch = "wasm32")]
+    pub async fn try_from_url(url: &str) -> Result<Self, Error> {
+        if url.starts_with("ws://") || url.starts_with("wss://") {
+            let client = crate::wasm::WasmClient::connect(url).await?;
+            Ok(RpcClient::new(client))
         } else {
-            let client = HttpClientBuilder::default().build(&url)?;
-            Ok(RpcClient::Http(Arc::new(client)))
+            Err(RpcError::Custom(format!(
+                "Unsupported URL {}: only websockets are available on wasm32",
+                url
+            ))
+            .into())
         }
     }
 
+    /// Create a new [`RpcClient`] from any [`RpcClientT`] transport.
+    pub fn new<C: RpcClientT>(client: C) -> Self {
+        RpcClient::from(Arc::new(client) as Arc<dyn RpcClientT>)
+    }
+
+    /// Fail requests which get no response within `timeout` with
+    /// [`RpcError::RequestTimeout`], or leave timeouts to the transport if `None`.
+    ///
+    /// The timeout covers all attempts of a request retried with
+    /// [`RpcClient::with_retry_policy`]. The transport may still enforce its own, shorter
+    /// timeout.
+    pub fn with_request_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
     /// Start a JSON-RPC request.
     pub async fn request<'a, T: DeserializeOwned + std::fmt::Debug>(
         &self,
         method: &str,
         params: &[JsonValue],
     ) -> Result<T, Error> {
-        let params = Some(params.into());
+        self.request_with_timeout(method, params, self.request_timeout)
+            .await
+    }
+
+    /// Start a JSON-RPC request which fails with [`RpcError::RequestTimeout`] if it gets no
+    /// response within `timeout`, instead of the timeout of the client.
+    pub async fn request_with_timeout<'a, T: DeserializeOwned + std::fmt::Debug>(
+        &self,
+        method: &str,
+        params: &[JsonValue],
+        timeout: Option<Duration>,
+    ) -> Result<T, Error> {
         log::debug!("request {}: {:?}", method, params);
-        let data = match self {
-            Self::WebSocket(inner) => {
-                inner.request(method, params).await.map_err(Into::into)
+        let request = self.transport.request_raw(method, params.to_vec());
+        let value = match timeout {
+            Some(timeout) => {
+                let delay = futures_timer::Delay::new(timeout);
+                match futures::future::select(request, delay).await {
+                    Either::Left((value, _)) => value?,
+                    Either::Right(_) => return Err(RpcError::RequestTimeout.into()),
+                }
             }
-            Self::Http(inner) => inner.request(method, params).await.map_err(Into::into),
+            None => request.await?,
         };
-        data
+        Ok(serde_json::from_value(value)?)
     }
 
     /// Start a JSON-RPC Subscription.
@@ -221,48 +791,184 @@ impl RpcClient {
         params: &[JsonValue],
         unsubscribe_method: &str,
     ) -> Result<Subscription<T>, Error> {
-        let params = Some(params.into());
-        match self {
-            Self::WebSocket(inner) => {
-                inner
-                    .subscribe(subscribe_method, params, unsubscribe_method)
-                    .await
-                    .map_err(Into::into)
-            }
-            Self::Http(_) => {
-                Err(RpcError::Custom(
-                    "Subscriptions not supported on HTTP transport".to_owned(),
-                )
-                .into())
-            }
-        }
+        let stream = self
+            .transport
+            .subscribe_raw(subscribe_method, params.to_vec(), unsubscribe_method)
+            .await?;
+        Ok(Subscription::new(stream))
+    }
+
+    /// Retry failed requests made through the client according to the given policy.
+    pub fn with_retry_policy(self, policy: RetryPolicy) -> Self {
+        let request_timeout = self.request_timeout;
+        RpcClient::new(RetryClient {
+            inner: self.transport,
+            policy,
+        })
+        .with_request_timeout(request_timeout)
+    }
+
+    /// Call `logger` with every request made through the client and the response of the
+    /// node, e.g. to debug what is sent over the wire.
+    ///
+    /// Each attempt of a request retried with [`RpcClient::with_retry_policy`] is logged if
+    /// the logger is set first. Subscriptions are not logged.
+    pub fn with_logger<F>(self, logger: F) -> Self
+    where
+        F: Fn(&RpcLog) + Send + Sync + 'static,
+    {
+        let request_timeout = self.request_timeout;
+        RpcClient::new(LoggingClient {
+            inner: self.transport,
+            logger: Arc::new(logger),
+        })
+        .with_request_timeout(request_timeout)
+    }
+
+    /// Close the connection, ending any active subscriptions.
+    ///
+    /// Waits for the requests in flight to complete before closing the connection, and fails
+    /// any requests made afterwards. Only connections created by subxt, or from an owned
+    /// [`WsClient`], are closed; a shared `Arc<WsClient>` stays connected until its last
+    /// reference is dropped.
+    pub async fn disconnect(&self) -> Result<(), Error> {
+        Ok(self.transport.disconnect().await?)
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<WsClient> for RpcClient {
     fn from(client: WsClient) -> Self {
-        RpcClient::WebSocket(Arc::new(client))
+        RpcClient::new(OwnedWsClient::new(client))
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<Arc<WsClient>> for RpcClient {
     fn from(client: Arc<WsClient>) -> Self {
-        RpcClient::WebSocket(client)
+        RpcClient::from(client as Arc<dyn RpcClientT>)
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<HttpClient> for RpcClient {
     fn from(client: HttpClient) -> Self {
-        RpcClient::Http(Arc::new(client))
+        RpcClient::new(client)
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 impl From<Arc<HttpClient>> for RpcClient {
     fn from(client: Arc<HttpClient>) -> Self {
-        RpcClient::Http(client)
+        RpcClient::from(client as Arc<dyn RpcClientT>)
     }
 }
 
+impl From<Arc<dyn RpcClientT>> for RpcClient {
+    fn from(client: Arc<dyn RpcClientT>) -> Self {
+        RpcClient {
+            transport: client,
+            request_timeout: None,
+        }
+    }
+}
+
+/// A generalized group of dispatch types.
+///
+/// # Note
+///
+/// This is copied from `frame-support` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DispatchClass {
+    /// A normal dispatch.
+    Normal,
+    /// An operational dispatch.
+    Operational,
+    /// A mandatory dispatch.
+    Mandatory,
+}
+
+/// Information related to a dispatchable's class, weight, and fee, returned by
+/// `payment_queryInfo`.
+///
+/// # Note
+///
+/// This is copied from `pallet-transaction-payment-rpc-runtime-api` to avoid a dependency on
+/// that crate. Therefore it must be kept compatible with that type from the target substrate
+/// version.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeDispatchInfo {
+    /// Weight of this dispatch.
+    pub weight: Weight,
+    /// Class of this dispatch.
+    pub class: DispatchClass,
+    /// The inclusion fee of this dispatch, not including the tip.
+    #[serde(deserialize_with = "number_or_string")]
+    pub partial_fee: u128,
+    /// The length in bytes of the encoded extrinsic the fee was computed for.
+    ///
+    /// This is not part of the response of `payment_queryInfo`, but filled in by
+    /// [`Rpc::payment_query_info`].
+    #[serde(default)]
+    pub len: u32,
+}
+
+fn number_or_string<'de, D>(deserializer: D) -> Result<u128, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(u128),
+        String(String),
+    }
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n),
+        NumberOrString::String(s) => s.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+/// Health struct returned by the RPC
+///
+/// # Note
+///
+/// This is copied from `sc-rpc-api` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Health {
+    /// Number of connected peers
+    pub peers: usize,
+    /// Is the node syncing
+    pub is_syncing: bool,
+    /// Should this node have any peers
+    ///
+    /// Might be false for local chains or when running without discovery.
+    pub should_have_peers: bool,
+}
+
+/// Sync state struct returned by the RPC
+///
+/// # Note
+///
+/// This is copied from `sc-rpc-api` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncState {
+    /// Height of the block at which syncing started.
+    pub starting_block: u64,
+    /// Height of the current best block of the node.
+    pub current_block: u64,
+    /// Height of the highest block learned from the network. Missing if no block is known yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highest_block: Option<u64>,
+}
+
 /// ReadProof struct returned by the RPC
 ///
 /// # Note
@@ -282,6 +988,7 @@ pub struct ReadProof<Hash> {
 pub struct Rpc<T: Config> {
     /// Rpc client for sending requests.
     pub client: RpcClient,
+    subscription_idle_timeout: Option<Duration>,
     marker: PhantomData<T>,
 }
 
@@ -289,6 +996,7 @@ impl<T: Config> Clone for Rpc<T> {
     fn clone(&self) -> Self {
         Self {
             client: self.client.clone(),
+            subscription_idle_timeout: self.subscription_idle_timeout,
             marker: PhantomData,
         }
     }
@@ -299,10 +1007,60 @@ impl<T: Config> Rpc<T> {
     pub fn new(client: RpcClient) -> Self {
         Self {
             client,
+            subscription_idle_timeout: None,
             marker: PhantomData,
         }
     }
 
+    /// Apply [`Subscription::with_idle_timeout`] to every subscription started by this [`Rpc`].
+    pub fn with_subscription_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.subscription_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Override the request timeout of the client for every request made by this [`Rpc`], see
+    /// [`RpcClient::with_request_timeout`].
+    pub fn with_request_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.client = self.client.with_request_timeout(timeout);
+        self
+    }
+
+    fn watch<S: DeserializeOwned>(
+        &self,
+        subscription: Subscription<S>,
+    ) -> Subscription<S> {
+        match self.subscription_idle_timeout {
+            Some(timeout) => subscription.with_idle_timeout(timeout),
+            None => subscription,
+        }
+    }
+
+    /// Make a request reading the state of the block with hash `at`, failing with
+    /// [`Error::StatePruned`] if the node has pruned it.
+    async fn state_request<R: DeserializeOwned + std::fmt::Debug>(
+        &self,
+        method: &str,
+        params: &[JsonValue],
+        at: Option<T::Hash>,
+    ) -> Result<R, Error> {
+        self.client.request(method, params).await.map_err(|err| {
+            match err {
+                // Substrate reports reads of pruned state as a client error.
+                Error::Rpc(RpcError::Request(message))
+                    if message.contains("State already discarded") =>
+                {
+                    Error::StatePruned {
+                        block: at.map_or_else(
+                            || "best block".into(),
+                            |hash| format!("{:?}", hash),
+                        ),
+                    }
+                }
+                err => err,
+            }
+        })
+    }
+
     /// Fetch a storage key
     pub async fn storage(
         &self,
@@ -310,7 +1068,7 @@ impl<T: Config> Rpc<T> {
         hash: Option<T::Hash>,
     ) -> Result<Option<StorageData>, Error> {
         let params = &[to_json_value(key)?, to_json_value(hash)?];
-        let data = self.client.request("state_getStorage", params).await?;
+        let data = self.state_request("state_getStorage", params, hash).await?;
         Ok(data)
     }
 
@@ -331,7 +1089,9 @@ impl<T: Config> Rpc<T> {
             to_json_value(start_key)?,
             to_json_value(hash)?,
         ];
-        let data = self.client.request("state_getKeysPaged", params).await?;
+        let data = self
+            .state_request("state_getKeysPaged", params, hash)
+            .await?;
         Ok(data)
     }
 
@@ -347,10 +1107,8 @@ impl<T: Config> Rpc<T> {
             to_json_value(from)?,
             to_json_value(to)?,
         ];
-        self.client
-            .request("state_queryStorage", params)
+        self.state_request("state_queryStorage", params, Some(from))
             .await
-            .map_err(Into::into)
     }
 
     /// Query historical storage entries
@@ -360,10 +1118,7 @@ impl<T: Config> Rpc<T> {
         at: Option<T::Hash>,
     ) -> Result<Vec<StorageChangeSet<T::Hash>>, Error> {
         let params = &[to_json_value(keys)?, to_json_value(at)?];
-        self.client
-            .request("state_queryStorageAt", params)
-            .await
-            .map_err(Into::into)
+        self.state_request("state_queryStorageAt", params, at).await
     }
 
     /// Fetch the genesis hash
@@ -380,12 +1135,11 @@ impl<T: Config> Rpc<T> {
         }
     }
 
-    /// Fetch the metadata
-    pub async fn metadata(&self) -> Result<Metadata, Error> {
-        let bytes: Bytes = self.client.request("state_getMetadata", &[]).await?;
-        let meta: RuntimeMetadataPrefixed = Decode::decode(&mut &bytes[..])?;
-        let metadata: Metadata = meta.try_into()?;
-        Ok(metadata)
+    /// Fetch the metadata, at the latest block by default
+    pub async fn metadata(&self, at: Option<T::Hash>) -> Result<Metadata, Error> {
+        let params = &[to_json_value(at)?];
+        let bytes: Bytes = self.state_request("state_getMetadata", params, at).await?;
+        Metadata::decode(&bytes)
     }
 
     /// Fetch system properties
@@ -393,6 +1147,16 @@ impl<T: Config> Rpc<T> {
         Ok(self.client.request("system_properties", &[]).await?)
     }
 
+    /// Fetch the node health
+    pub async fn system_health(&self) -> Result<Health, Error> {
+        Ok(self.client.request("system_health", &[]).await?)
+    }
+
+    /// Fetch the node sync state
+    pub async fn system_sync_state(&self) -> Result<SyncState, Error> {
+        Ok(self.client.request("system_syncState", &[]).await?)
+    }
+
     /// Get a header
     pub async fn header(
         &self,
@@ -440,7 +1204,9 @@ impl<T: Config> Rpc<T> {
         hash: Option<T::Hash>,
     ) -> Result<ReadProof<T::Hash>, Error> {
         let params = &[to_json_value(keys)?, to_json_value(hash)?];
-        let proof = self.client.request("state_getReadProof", params).await?;
+        let proof = self
+            .state_request("state_getReadProof", params, hash)
+            .await?;
         Ok(proof)
     }
 
@@ -451,8 +1217,7 @@ impl<T: Config> Rpc<T> {
     ) -> Result<RuntimeVersion, Error> {
         let params = &[to_json_value(at)?];
         let version = self
-            .client
-            .request("state_getRuntimeVersion", params)
+            .state_request("state_getRuntimeVersion", params, at)
             .await?;
         Ok(version)
     }
@@ -469,7 +1234,20 @@ impl<T: Config> Rpc<T> {
             .client
             .subscribe("state_subscribeStorage", params, "state_unsubscribeStorage")
             .await?;
-        Ok(EventStorageSubscription::Imported(subscription))
+        Ok(EventStorageSubscription::Imported(self.watch(subscription)))
+    }
+
+    /// Subscribe to changes of the values under `keys`, starting with their current values.
+    pub async fn subscribe_storage(
+        &self,
+        keys: Vec<StorageKey>,
+    ) -> Result<Subscription<StorageChangeSet<T::Hash>>, Error> {
+        let params = &[to_json_value(keys)?];
+        let subscription = self
+            .client
+            .subscribe("state_subscribeStorage", params, "state_unsubscribeStorage")
+            .await?;
+        Ok(self.watch(subscription))
     }
 
     /// Subscribe to finalized events.
@@ -491,7 +1269,7 @@ impl<T: Config> Rpc<T> {
             .subscribe("chain_subscribeNewHeads", &[], "chain_unsubscribeNewHeads")
             .await?;
 
-        Ok(subscription)
+        Ok(self.watch(subscription))
     }
 
     /// Subscribe to finalized blocks.
@@ -506,7 +1284,7 @@ impl<T: Config> Rpc<T> {
                 "chain_unsubscribeFinalizedHeads",
             )
             .await?;
-        Ok(subscription)
+        Ok(self.watch(subscription))
     }
 
     /// Create and submit an extrinsic and return corresponding Hash if successful
@@ -538,7 +1316,38 @@ impl<T: Config> Rpc<T> {
                 "author_unwatchExtrinsic",
             )
             .await?;
-        Ok(subscription)
+        Ok(self.watch(subscription))
+    }
+
+    /// Execute a runtime API call, e.g. `TransactionPaymentApi_query_info`, with the SCALE
+    /// encoded arguments, returning the SCALE encoded result.
+    pub async fn state_call(
+        &self,
+        function: &str,
+        call_parameters: Bytes,
+        at: Option<T::Hash>,
+    ) -> Result<Bytes, Error> {
+        let params = &[
+            to_json_value(function)?,
+            to_json_value(call_parameters)?,
+            to_json_value(at)?,
+        ];
+        let result = self.state_request("state_call", params, at).await?;
+        Ok(result)
+    }
+
+    /// Fetch the weight, dispatch class, inclusion fee and length of an extrinsic.
+    pub async fn payment_query_info<E: Encode>(
+        &self,
+        extrinsic: E,
+        at: Option<T::Hash>,
+    ) -> Result<RuntimeDispatchInfo, Error> {
+        let bytes: Bytes = extrinsic.encode().into();
+        let len = bytes.len() as u32;
+        let params = &[to_json_value(bytes)?, to_json_value(at)?];
+        let info: RuntimeDispatchInfo =
+            self.client.request("payment_queryInfo", params).await?;
+        Ok(RuntimeDispatchInfo { len, ..info })
     }
 
     /// Insert a key into the keystore.
@@ -584,3 +1393,189 @@ impl<T: Config> Rpc<T> {
         Ok(self.client.request("author_hasKey", params).await?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::MockClient,
+        test_utils::MockConfig,
+    };
+    use sp_runtime::traits::Header as _;
+
+    /// Fails the first `failures` requests to `test` with the given error.
+    fn flaky(failures: u32, error: fn() -> RpcError) -> RpcClient {
+        let mock = MockClient::new();
+        mock.set_response("test", true)
+            .set_failures("test", failures, error);
+        let policy = RetryPolicy::new(3).set_base_delay(Duration::from_millis(1));
+        RpcClient::new(mock).with_retry_policy(policy)
+    }
+
+    #[async_std::test]
+    async fn retry_policy_retries_transient_errors() {
+        let client = flaky(2, || RpcError::RequestTimeout);
+        assert!(client.request::<bool>("test", &[]).await.unwrap());
+
+        let client = flaky(3, || RpcError::RequestTimeout);
+        assert!(client.request::<bool>("test", &[]).await.is_err());
+
+        let client = flaky(1, || RpcError::Custom("bad request".into()));
+        assert!(client.request::<bool>("test", &[]).await.is_err());
+    }
+
+    #[async_std::test]
+    async fn logger_sees_every_attempt() {
+        let logs = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = logs.clone();
+        let policy = RetryPolicy::new(3).set_base_delay(Duration::from_millis(1));
+        let mock = MockClient::new();
+        mock.set_response("test", true)
+            .set_failures("test", 1, || RpcError::RequestTimeout);
+        let client = RpcClient::new(mock)
+            .with_logger(move |log: &RpcLog| {
+                sink.lock().unwrap().push((
+                    log.method.to_string(),
+                    log.params.to_vec(),
+                    log.result.map(Clone::clone).map_err(ToString::to_string),
+                ))
+            })
+            .with_retry_policy(policy);
+
+        assert!(client
+            .request::<bool>("test", &[JsonValue::from(1)])
+            .await
+            .unwrap());
+        let logs = logs.lock().unwrap();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].0, "test");
+        assert_eq!(logs[0].1, vec![JsonValue::from(1)]);
+        assert!(logs[0].2.is_err());
+        assert_eq!(logs[1].2, Ok(JsonValue::Bool(true)));
+    }
+
+    #[async_std::test]
+    async fn request_timeouts_can_be_overridden_per_request() {
+        let mock = MockClient::new();
+        mock.set_response("test", true)
+            .set_delay(Duration::from_millis(50));
+        let client =
+            RpcClient::new(mock).with_request_timeout(Some(Duration::from_millis(10)));
+        assert!(matches!(
+            client.request::<bool>("test", &[]).await,
+            Err(Error::Rpc(RpcError::RequestTimeout))
+        ));
+
+        let slow = Some(Duration::from_secs(5));
+        assert!(client
+            .request_with_timeout::<bool>("test", &[], slow)
+            .await
+            .unwrap());
+        assert!(client
+            .with_request_timeout(None)
+            .request::<bool>("test", &[])
+            .await
+            .unwrap());
+    }
+
+    #[async_std::test]
+    async fn metadata_reports_unsupported_versions() {
+        let mut v13 = b"meta".to_vec();
+        v13.extend([13, 0, 0, 0]);
+        let mock = MockClient::new();
+        mock.set_metadata(v13);
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(mock));
+
+        assert!(matches!(
+            rpc.metadata(None).await,
+            Err(Error::UnsupportedMetadataVersion(13))
+        ));
+    }
+
+    #[async_std::test]
+    async fn reads_of_pruned_state_are_reported() {
+        let mock = MockClient::new();
+        mock.set_error("state_getStorage", || {
+            RpcError::Request(
+                "Client error: UnknownBlock: State already discarded for BlockId::Hash(..)"
+                    .into(),
+            )
+        });
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(mock));
+        let hash = sp_core::H256::from([1; 32]);
+        match rpc.storage(&StorageKey(vec![]), Some(hash)).await {
+            Err(Error::StatePruned { block }) => assert_eq!(block, format!("{:?}", hash)),
+            other => panic!("Unexpected result {:?}", other),
+        }
+        // Other requests are not about state.
+        assert!(matches!(rpc.finalized_head().await, Err(Error::Rpc(_))));
+    }
+
+    #[async_std::test]
+    async fn rpc_requests_and_subscriptions_go_through_the_transport() {
+        let hash = sp_core::H256::from([1; 32]);
+        let header = <MockConfig as Config>::Header::new(
+            1,
+            Default::default(),
+            Default::default(),
+            hash,
+            Default::default(),
+        );
+        let mock = MockClient::new();
+        mock.set_response("chain_getFinalizedHead", hash)
+            .set_notifications("chain_subscribeFinalizedHeads", vec![&header]);
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(mock));
+
+        assert_eq!(rpc.finalized_head().await.unwrap(), hash);
+        assert!(rpc.rotate_keys().await.is_err());
+
+        let mut sub = rpc.subscribe_finalized_blocks().await.unwrap();
+        assert_eq!(sub.next().await.unwrap(), Some(header));
+        assert_eq!(sub.next().await.unwrap(), None);
+    }
+
+    #[async_std::test]
+    async fn subscriptions_end_when_idle_for_too_long() {
+        let notifications = futures::stream::iter(vec![Ok(JsonValue::Bool(true))])
+            .chain(futures::stream::pending());
+        let mut sub = Subscription::<bool>::new(Box::pin(notifications))
+            .with_idle_timeout(Duration::from_millis(10));
+
+        assert_eq!(sub.next().await.unwrap(), Some(true));
+        assert!(matches!(
+            sub.next().await,
+            Err(Error::SubscriptionStalled(timeout)) if timeout == Duration::from_millis(10)
+        ));
+        assert_eq!(sub.next().await.unwrap(), None);
+    }
+
+    #[test]
+    fn system_properties_normalise_scalar_and_list_values() {
+        let scalar: SystemProperties = serde_json::from_value(serde_json::json!({
+            "ss58Format": 0,
+            "tokenDecimals": 10,
+            "tokenSymbol": "DOT",
+        }))
+        .unwrap();
+        assert_eq!(scalar.ss58_format, Some(0));
+        assert_eq!(scalar.token_decimals, vec![10]);
+        assert_eq!(scalar.token_symbol, vec!["DOT".to_string()]);
+
+        let list: SystemProperties = serde_json::from_value(serde_json::json!({
+            "tokenDecimals": [12, 12],
+            "tokenSymbol": ["KAR", "KUSD"],
+            "custom": true,
+        }))
+        .unwrap();
+        assert_eq!(list.ss58_format, None);
+        assert_eq!(list.token_decimals, vec![12, 12]);
+        assert_eq!(
+            list.token_symbol,
+            vec!["KAR".to_string(), "KUSD".to_string()]
+        );
+        assert_eq!(
+            list.other.get("custom"),
+            Some(&serde_json::Value::Bool(true))
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls and events of the `Scheduler` pallet which are independent of the runtime, used to
+//! dispatch a call at a later block.
+//!
+//! See [`crate::SubmittableExtrinsic::schedule`] and
+//! [`crate::SubmittableExtrinsic::schedule_after`].
+
+use codec::{
+    Decode,
+    Encode,
+};
+use scale_info::TypeDef;
+
+use crate::{
+    Call,
+    Config,
+    Encoded,
+    Error,
+    Event,
+    Metadata,
+    MetadataError,
+};
+
+/// The address of a scheduled task: the block it is scheduled at and its index in the agenda
+/// of that block.
+pub type TaskAddress<BlockNumber> = (BlockNumber, u32);
+
+/// The `Scheduler::schedule` call, dispatching an already encoded call at block `when`.
+#[derive(Encode)]
+pub struct Schedule<T: Config> {
+    /// The block to dispatch the call at.
+    pub when: T::BlockNumber,
+    /// The period between repetitions in blocks and the number of repetitions, if any.
+    pub maybe_periodic: Option<(T::BlockNumber, u32)>,
+    /// The priority of the call among the calls scheduled at the same block, where lower
+    /// values come first.
+    pub priority: u8,
+    /// The call, encoded for the runtime, see [`encode_scheduled_call`].
+    pub call: Encoded,
+}
+
+impl<T: Config> Call for Schedule<T> {
+    const PALLET: &'static str = "Scheduler";
+    const FUNCTION: &'static str = "schedule";
+}
+
+/// The `Scheduler::schedule_after` call, dispatching an already encoded call `after` blocks
+/// from the block it is included in.
+#[derive(Encode)]
+pub struct ScheduleAfter<T: Config> {
+    /// The number of blocks to wait for before dispatching the call.
+    pub after: T::BlockNumber,
+    /// The period between repetitions in blocks and the number of repetitions, if any.
+    pub maybe_periodic: Option<(T::BlockNumber, u32)>,
+    /// The priority of the call among the calls scheduled at the same block, where lower
+    /// values come first.
+    pub priority: u8,
+    /// The call, encoded for the runtime, see [`encode_scheduled_call`].
+    pub call: Encoded,
+}
+
+impl<T: Config> Call for ScheduleAfter<T> {
+    const PALLET: &'static str = "Scheduler";
+    const FUNCTION: &'static str = "schedule_after";
+}
+
+/// The `Scheduler::Scheduled` event, emitted with the address of a scheduled task.
+#[derive(Debug, Eq, PartialEq, Decode)]
+pub struct Scheduled<T: Config> {
+    /// The block the task is scheduled at.
+    pub when: T::BlockNumber,
+    /// The index of the task in the agenda of the block.
+    pub index: u32,
+}
+
+impl<T: Config> Scheduled<T> {
+    /// Returns the address of the scheduled task.
+    pub fn task_address(&self) -> TaskAddress<T::BlockNumber> {
+        (self.when, self.index)
+    }
+}
+
+impl<T: Config> Event for Scheduled<T> {
+    const PALLET: &'static str = "Scheduler";
+    const EVENT: &'static str = "Scheduled";
+}
+
+/// Encode `call`, including its pallet and call indices, as the `call` argument of the
+/// scheduler call `function`.
+///
+/// Older runtimes take the boxed call itself, which encodes like the call, whereas newer
+/// runtimes take a `MaybeHashed` of the call, whose `Value` variant is used.
+pub fn encode_scheduled_call(
+    metadata: &Metadata,
+    function: &'static str,
+    call: Encoded,
+) -> Result<Encoded, Error> {
+    let variant = metadata
+        .pallet("Scheduler")?
+        .call(function)
+        .ok_or(MetadataError::CallNotFound(function))?;
+    let field = variant
+        .fields()
+        .iter()
+        .find(|field| field.name().map(String::as_str) == Some("call"))
+        .ok_or_else(|| {
+            Error::Other(format!("Scheduler::{} has no call argument", function))
+        })?;
+    let ty = metadata
+        .resolve_type(field.ty().id())
+        .ok_or(MetadataError::TypeNotFound(field.ty().id()))?;
+    let maybe_hashed = match ty.type_def() {
+        TypeDef::Variant(variant)
+            if ty.path().segments().last().map(String::as_str) == Some("MaybeHashed") =>
+        {
+            variant
+                .variants()
+                .iter()
+                .find(|variant| variant.name() == "Value")
+        }
+        _ => None,
+    };
+    Ok(match maybe_hashed {
+        Some(value) => {
+            let mut bytes = vec![value.index()];
+            bytes.extend(call.0);
+            Encoded(bytes)
+        }
+        None => call,
+    })
+}
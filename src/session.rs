@@ -0,0 +1,59 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls and storage of the `Session` pallet which are independent of the runtime, backing
+//! [`crate::Client::current_session`] and [`crate::Client::set_session_keys`].
+
+use codec::Encode;
+
+use crate::{
+    Call,
+    Encoded,
+    StorageEntry,
+    StorageEntryKey,
+};
+
+/// The index of a session.
+pub type SessionIndex = u32;
+
+/// The `Session::CurrentIndex` storage entry.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct CurrentIndex;
+
+impl StorageEntry for CurrentIndex {
+    const PALLET: &'static str = "Session";
+    const STORAGE: &'static str = "CurrentIndex";
+    type Value = SessionIndex;
+
+    fn key(&self) -> StorageEntryKey {
+        StorageEntryKey::Plain
+    }
+}
+
+/// The `Session::set_keys` call, setting the session keys of the validator of the signer.
+#[derive(Clone, Debug, Eq, PartialEq, Encode)]
+pub struct SetKeys {
+    /// The SCALE encoded session keys of the runtime, e.g. as returned by
+    /// [`crate::rpc::Rpc::rotate_keys`].
+    pub keys: Encoded,
+    /// The proof of ownership of the keys, which most runtimes do not check.
+    pub proof: Vec<u8>,
+}
+
+impl Call for SetKeys {
+    const PALLET: &'static str = "Session";
+    const FUNCTION: &'static str = "set_keys";
+}
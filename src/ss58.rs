@@ -0,0 +1,89 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Formatting and parsing of SS58 encoded account addresses.
+//!
+//! See [`crate::Client::ss58_prefix`] for the prefix used by the connected chain.
+
+use sp_core::crypto::{
+    Ss58AddressFormat,
+    Ss58Codec,
+};
+
+use crate::Error;
+
+/// Format an account as an SS58 address with the given prefix.
+pub fn format_account<A: Ss58Codec>(account: &A, ss58_prefix: u16) -> String {
+    account.to_ss58check_with_version(Ss58AddressFormat::custom(ss58_prefix))
+}
+
+/// Parse an SS58 address into an account, validating its checksum and that it was encoded
+/// with the given prefix.
+pub fn parse_account<A: Ss58Codec>(address: &str, ss58_prefix: u16) -> Result<A, Error> {
+    let (account, format) = A::from_ss58check_with_version(address)
+        .map_err(|e| Error::InvalidAddress(format!("{:?}", e)))?;
+    let prefix = u16::from(format);
+    if prefix != ss58_prefix {
+        return Err(Error::InvalidAddress(format!(
+            "expected SS58 prefix {}, found {}",
+            ss58_prefix, prefix
+        )))
+    }
+    Ok(account)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_runtime::AccountId32;
+
+    const ALICE: &str =
+        "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+
+    fn alice() -> AccountId32 {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&hex::decode(ALICE).unwrap());
+        AccountId32::from(bytes)
+    }
+
+    #[test]
+    fn format_and_parse_roundtrip() {
+        let substrate = format_account(&alice(), 42);
+        assert_eq!(
+            substrate,
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        );
+        let polkadot = format_account(&alice(), 0);
+        assert_eq!(polkadot, "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5");
+
+        assert_eq!(
+            parse_account::<AccountId32>(&substrate, 42).unwrap(),
+            alice()
+        );
+        assert_eq!(parse_account::<AccountId32>(&polkadot, 0).unwrap(), alice());
+    }
+
+    #[test]
+    fn parse_rejects_wrong_prefix_and_checksum() {
+        let polkadot = format_account(&alice(), 0);
+        assert!(parse_account::<AccountId32>(&polkadot, 2).is_err());
+
+        let mut corrupted = polkadot;
+        corrupted.pop();
+        corrupted.push('6');
+        assert!(parse_account::<AccountId32>(&corrupted, 0).is_err());
+    }
+}
@@ -0,0 +1,52 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage of the `Staking` pallet which is independent of the runtime, backing
+//! [`crate::Client::active_era`].
+
+use codec::Decode;
+
+use crate::{
+    StorageEntry,
+    StorageEntryKey,
+};
+
+/// The index of an era.
+pub type EraIndex = u32;
+
+/// The `Staking::ActiveEra` storage entry.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ActiveEra;
+
+impl StorageEntry for ActiveEra {
+    const PALLET: &'static str = "Staking";
+    const STORAGE: &'static str = "ActiveEra";
+    type Value = ActiveEraInfo;
+
+    fn key(&self) -> StorageEntryKey {
+        StorageEntryKey::Plain
+    }
+}
+
+/// The active era.
+#[derive(Clone, Debug, Eq, PartialEq, Decode)]
+pub struct ActiveEraInfo {
+    /// The index of the era.
+    pub index: EraIndex,
+    /// The moment the era started, in milliseconds since the Unix epoch, or `None` until its
+    /// first block.
+    pub start: Option<u64>,
+}
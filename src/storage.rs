@@ -20,21 +20,42 @@ use codec::{
     Decode,
     Encode,
 };
+use frame_metadata::{
+    StorageEntryModifier,
+    StorageEntryType,
+};
+use futures::Stream;
 use sp_core::storage::{
+    well_known_keys,
     StorageChangeSet,
     StorageData,
     StorageKey,
 };
 pub use sp_runtime::traits::SignedExtension;
 pub use sp_version::RuntimeVersion;
-use std::marker::PhantomData;
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    sync::Mutex,
+    time::Duration,
+};
 
 use crate::{
+    client::{
+        FinalizedHead,
+        ReadAt,
+    },
+    error::DecodeError,
     metadata::{
         Metadata,
         MetadataError,
+        MetadataProvider,
+    },
+    rpc::{
+        ReadProof,
+        Rpc,
     },
-    rpc::Rpc,
+    value::Value,
     Config,
     Error,
     StorageHasher,
@@ -131,32 +152,178 @@ impl StorageMapKey {
     }
 }
 
+/// A storage key defined by substrate itself rather than by a pallet, and so not described by
+/// the metadata.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WellKnownKey {
+    /// `:code`, the Wasm code of the runtime.
+    Code,
+    /// `:heappages`, the number of Wasm heap pages of the runtime.
+    HeapPages,
+    /// `:extrinsic_index`, the index of the extrinsic being applied.
+    ExtrinsicIndex,
+}
+
+impl WellKnownKey {
+    /// Convert to a [`StorageKey`].
+    pub fn to_storage_key(self) -> StorageKey {
+        let key = match self {
+            Self::Code => well_known_keys::CODE,
+            Self::HeapPages => well_known_keys::HEAP_PAGES,
+            Self::ExtrinsicIndex => well_known_keys::EXTRINSIC_INDEX,
+        };
+        StorageKey(key.to_vec())
+    }
+}
+
+/// Build the key of a storage entry using `metadata`, see [`StorageClient::dynamic_key`].
+fn dynamic_key(
+    metadata: &Metadata,
+    pallet: &str,
+    entry: &str,
+    keys: &[Value],
+) -> Result<StorageKey, Error> {
+    let info = metadata.storage_entry(pallet, entry)?;
+    if keys.len() != info.hashers.len() {
+        return Err(MetadataError::StorageTypeError.into())
+    }
+
+    let mut bytes = sp_core::twox_128(pallet.as_bytes()).to_vec();
+    bytes.extend(&sp_core::twox_128(entry.as_bytes())[..]);
+    let prefix = StorageKeyPrefix(bytes);
+
+    let map_keys = keys
+        .iter()
+        .zip(&info.key_types)
+        .zip(&info.hashers)
+        .map(|((key, ty), hasher)| {
+            let mut value = Vec::new();
+            key.encode_as(metadata, *ty, &mut value)?;
+            Ok(StorageMapKey {
+                value,
+                hasher: hasher.clone(),
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+    Ok(StorageEntryKey::Map(map_keys).final_key(prefix))
+}
+
+/// Decode the raw value of a storage entry using `metadata`, see
+/// [`StorageClient::decode_dynamic`].
+fn decode_dynamic(
+    metadata: &Metadata,
+    pallet: &str,
+    entry: &str,
+    data: Option<StorageData>,
+) -> Result<Option<Value>, Error> {
+    let info = metadata.storage_entry(pallet, entry)?;
+    let data = match data {
+        Some(data) => data.0,
+        None => {
+            match info.modifier {
+                StorageEntryModifier::Default => info.default,
+                StorageEntryModifier::Optional => return Ok(None),
+            }
+        }
+    };
+    Ok(Some(Value::decode(
+        metadata,
+        info.value_type,
+        &mut &data[..],
+    )?))
+}
+
+/// Verify a storage read proof against a state root, returning the value stored under `key`.
+///
+/// The state root should be taken from a header which is already trusted, e.g. one which has
+/// been finalized. Returns `Ok(None)` if the proof shows that there is no value under `key`, and
+/// an error if the proof is incomplete or does not match the root.
+pub fn verify_proof<T: Config>(
+    root: &T::Hash,
+    proof: &ReadProof<T::Hash>,
+    key: &StorageKey,
+) -> Result<Option<StorageData>, Error> {
+    let nodes = proof.proof.iter().map(|node| node.0.clone()).collect();
+    let db = sp_trie::StorageProof::new(nodes).into_memory_db::<T::Hashing>();
+    let value =
+        sp_trie::read_trie_value::<sp_trie::Layout<T::Hashing>, _>(&db, root, &key.0)
+            .map_err(|e| Error::InvalidProof(format!("{:?}", e)))?;
+    Ok(value.map(StorageData))
+}
+
 /// Client for querying runtime storage.
 #[derive(Clone)]
 pub struct StorageClient<'a, T: Config> {
-    rpc: &'a Rpc<T>,
-    metadata: &'a Metadata,
+    rpc: Cow<'a, Rpc<T>>,
+    metadata: Option<&'a Metadata>,
+    metadata_provider: Option<&'a MetadataProvider<T>>,
     iter_page_size: u32,
+    read_at: ReadAt<T::Hash>,
+    finalized_head: Option<&'a Mutex<FinalizedHead<T>>>,
 }
 
 impl<'a, T: Config> StorageClient<'a, T> {
     /// Create a new [`StorageClient`]
-    pub fn new(rpc: &'a Rpc<T>, metadata: &'a Metadata, iter_page_size: u32) -> Self {
+    ///
+    /// Without `metadata` only raw storage access is supported.
+    pub fn new(
+        rpc: &'a Rpc<T>,
+        metadata: Option<&'a Metadata>,
+        iter_page_size: u32,
+    ) -> Self {
         Self {
-            rpc,
+            rpc: Cow::Borrowed(rpc),
             metadata,
+            metadata_provider: None,
             iter_page_size,
+            read_at: ReadAt::Best,
+            finalized_head: None,
         }
     }
 
+    /// Fail requests which get no response within `timeout`, instead of within the request
+    /// timeout of the client.
+    ///
+    /// This allows heavy queries, such as iterating a large map or querying the history of
+    /// storage over many blocks, to take longer than other requests.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        let rpc = self.rpc.into_owned().with_request_timeout(Some(timeout));
+        self.rpc = Cow::Owned(rpc);
+        self
+    }
+
+    /// Read at the block of `read_at` when no block hash is given, rather than at the best
+    /// block.
+    pub fn with_read_at(mut self, read_at: ReadAt<T::Hash>) -> Self {
+        self.read_at = read_at;
+        self
+    }
+
+    /// Decode dynamic reads at a block with the metadata of the runtime at that block, given
+    /// by `provider`, rather than with the metadata of the client.
+    pub fn with_metadata_provider(mut self, provider: &'a MetadataProvider<T>) -> Self {
+        self.metadata_provider = Some(provider);
+        self
+    }
+
+    /// Take the latest finalized block of [`ReadAt::Finalized`] from the finalized head
+    /// tracked by the client.
+    pub(crate) fn with_finalized_head(
+        mut self,
+        finalized_head: Option<&'a Mutex<FinalizedHead<T>>>,
+    ) -> Self {
+        self.finalized_head = finalized_head;
+        self
+    }
+
     /// Fetch the value under an unhashed storage key
     pub async fn fetch_unhashed<V: Decode>(
         &self,
         key: StorageKey,
         hash: Option<T::Hash>,
     ) -> Result<Option<V>, Error> {
-        if let Some(data) = self.rpc.storage(&key, hash).await? {
-            Ok(Some(Decode::decode(&mut &data.0[..])?))
+        if let Some(data) = self.fetch_raw(key, hash).await? {
+            Ok(Some(decode_value(&data.0)?))
         } else {
             Ok(None)
         }
@@ -168,9 +335,22 @@ impl<'a, T: Config> StorageClient<'a, T> {
         key: StorageKey,
         hash: Option<T::Hash>,
     ) -> Result<Option<StorageData>, Error> {
+        let hash = self
+            .read_at
+            .resolve(&*self.rpc, self.finalized_head, hash)
+            .await?;
         self.rpc.storage(&key, hash).await
     }
 
+    /// Fetch the raw value under a [`WellKnownKey`] with an optional block hash.
+    pub async fn fetch_well_known(
+        &self,
+        key: WellKnownKey,
+        hash: Option<T::Hash>,
+    ) -> Result<Option<StorageData>, Error> {
+        self.fetch_raw(key.to_storage_key(), hash).await
+    }
+
     /// Fetch a StorageKey with an optional block hash.
     pub async fn fetch<F: StorageEntry>(
         &self,
@@ -191,7 +371,8 @@ impl<'a, T: Config> StorageClient<'a, T> {
         if let Some(data) = self.fetch(store, hash).await? {
             Ok(data)
         } else {
-            let pallet_metadata = self.metadata.pallet(F::PALLET)?;
+            let metadata = self.metadata.ok_or(MetadataError::Unavailable)?;
+            let pallet_metadata = metadata.pallet(F::PALLET)?;
             let storage_metadata = pallet_metadata.storage(F::STORAGE)?;
             let default = Decode::decode(&mut &storage_metadata.default[..])
                 .map_err(MetadataError::DefaultError)?;
@@ -199,6 +380,63 @@ impl<'a, T: Config> StorageClient<'a, T> {
         }
     }
 
+    /// Fetch a storage entry by the names of its pallet and entry, building the key from
+    /// `keys` and decoding the value using the metadata type registry.
+    ///
+    /// `keys` must hold one value per hasher of a map, and be empty for a plain entry.
+    /// Returns `None` if there is no value and the entry has no default.
+    ///
+    /// When reading at a block with a metadata provider set, see
+    /// [`StorageClient::with_metadata_provider`], the metadata of the runtime at that block is
+    /// used.
+    pub async fn fetch_dynamic(
+        &self,
+        pallet: &str,
+        entry: &str,
+        keys: Vec<Value>,
+        hash: Option<T::Hash>,
+    ) -> Result<Option<Value>, Error> {
+        let hash = self
+            .read_at
+            .resolve(&*self.rpc, self.finalized_head, hash)
+            .await?;
+        let historic = match (self.metadata_provider, hash) {
+            (Some(provider), Some(hash)) => Some(provider.at(hash).await?),
+            _ => None,
+        };
+        let metadata = match &historic {
+            Some(metadata) => &**metadata,
+            None => self.metadata.ok_or(MetadataError::Unavailable)?,
+        };
+        let key = dynamic_key(metadata, pallet, entry, &keys)?;
+        let data = self.rpc.storage(&key, hash).await?;
+        decode_dynamic(metadata, pallet, entry, data)
+    }
+
+    /// Build the key of a storage entry by the names of its pallet and entry, encoding `keys`
+    /// as the map keys described by the metadata, see [`StorageClient::fetch_dynamic`].
+    pub fn dynamic_key(
+        &self,
+        pallet: &str,
+        entry: &str,
+        keys: &[Value],
+    ) -> Result<StorageKey, Error> {
+        let metadata = self.metadata.ok_or(MetadataError::Unavailable)?;
+        dynamic_key(metadata, pallet, entry, keys)
+    }
+
+    /// Decode the raw value of a storage entry by the names of its pallet and entry using the
+    /// metadata type registry, falling back to the default of the entry if there is no value.
+    pub fn decode_dynamic(
+        &self,
+        pallet: &str,
+        entry: &str,
+        data: Option<StorageData>,
+    ) -> Result<Option<Value>, Error> {
+        let metadata = self.metadata.ok_or(MetadataError::Unavailable)?;
+        decode_dynamic(metadata, pallet, entry, data)
+    }
+
     /// Query historical storage entries
     pub async fn query_storage(
         &self,
@@ -219,6 +457,10 @@ impl<'a, T: Config> StorageClient<'a, T> {
         hash: Option<T::Hash>,
     ) -> Result<Vec<StorageKey>, Error> {
         let prefix = StorageKeyPrefix::new::<F>();
+        let hash = self
+            .read_at
+            .resolve(&*self.rpc, self.finalized_head, hash)
+            .await?;
         let keys = self
             .rpc
             .storage_keys_paged(Some(prefix), count, start_key, hash)
@@ -226,12 +468,67 @@ impl<'a, T: Config> StorageClient<'a, T> {
         Ok(keys)
     }
 
+    /// Fetch up to `count` keys for a storage map, like [`StorageClient::fetch_keys`], decoding
+    /// the map key from each of them.
+    ///
+    /// Only maps with a single key hashed with a concat hasher (`Blake2_128Concat`,
+    /// `Twox64Concat` or `Identity`) can be decoded, since other hashers do not retain the key.
+    pub async fn fetch_keys_decoded<F: StorageEntry, K: Decode>(
+        &self,
+        count: u32,
+        start_key: Option<StorageKey>,
+        hash: Option<T::Hash>,
+    ) -> Result<Vec<K>, Error> {
+        let metadata = self.metadata.ok_or(MetadataError::Unavailable)?;
+        let storage_metadata = metadata.pallet(F::PALLET)?.storage(F::STORAGE)?;
+        let hash_len = match &storage_metadata.ty {
+            StorageEntryType::Map { hashers, .. } if hashers.len() == 1 => {
+                match hashers[0] {
+                    StorageHasher::Blake2_128Concat => 16,
+                    StorageHasher::Twox64Concat => 8,
+                    StorageHasher::Identity => 0,
+                    _ => {
+                        return Err(MetadataError::StorageKeyNotDecodable(
+                            F::STORAGE,
+                            "the hasher does not retain the key",
+                        )
+                        .into())
+                    }
+                }
+            }
+            StorageEntryType::Map { .. } => {
+                return Err(MetadataError::StorageKeyNotDecodable(
+                    F::STORAGE,
+                    "only maps with a single key are supported",
+                )
+                .into())
+            }
+            StorageEntryType::Plain(_) => {
+                return Err(MetadataError::StorageTypeError.into())
+            }
+        };
+        // The pallet and storage name prefixes are each hashed with twox_128.
+        let skip = 32 + hash_len;
+        self.fetch_keys::<F>(count, start_key, hash)
+            .await?
+            .into_iter()
+            .map(|key| {
+                let mut encoded_key = key.0.get(skip..).unwrap_or_default();
+                Ok(K::decode(&mut encoded_key)?)
+            })
+            .collect()
+    }
+
     /// Returns an iterator of key value pairs.
     pub async fn iter<F: StorageEntry>(
         &self,
         hash: Option<T::Hash>,
     ) -> Result<KeyIter<'a, T, F>, Error> {
-        let hash = if let Some(hash) = hash {
+        let hash = if let Some(hash) = self
+            .read_at
+            .resolve(&*self.rpc, self.finalized_head, hash)
+            .await?
+        {
             hash
         } else {
             self.rpc
@@ -242,15 +539,110 @@ impl<'a, T: Config> StorageClient<'a, T> {
         Ok(KeyIter {
             client: self.clone(),
             hash,
-            count: self.iter_page_size,
+            count: self.iter_page_size.max(1),
             start_key: None,
             buffer: Default::default(),
             _marker: PhantomData,
         })
     }
+
+    /// Returns a pager over the keys of a storage map, fetching `page_size` keys per page.
+    ///
+    /// All pages are read at the same block, the block with hash `hash` or the block reads
+    /// default to. A page size of `0` is treated as `1`, since an empty page would never
+    /// be short and so never end the scan.
+    pub async fn key_pager<F: StorageEntry>(
+        &self,
+        page_size: u32,
+        hash: Option<T::Hash>,
+    ) -> Result<StorageKeyPager<'a, T, F>, Error> {
+        let hash = if let Some(hash) = self
+            .read_at
+            .resolve(&*self.rpc, self.finalized_head, hash)
+            .await?
+        {
+            hash
+        } else {
+            self.rpc
+                .block_hash(None)
+                .await?
+                .expect("didn't pass a block number; qed")
+        };
+        Ok(StorageKeyPager {
+            client: self.clone(),
+            page_size: page_size.max(1),
+            hash,
+            cursor: None,
+            exhausted: false,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// Pages through the keys of a storage map, see [`StorageClient::key_pager`].
+///
+/// Unlike [`KeyIter`] only the keys are fetched, and the caller controls when the next page
+/// is fetched. The cursor can be saved and restored with [`StorageKeyPager::with_cursor`] to
+/// resume a long scan from a checkpoint.
+pub struct StorageKeyPager<'a, T: Config, F: StorageEntry> {
+    client: StorageClient<'a, T>,
+    page_size: u32,
+    hash: T::Hash,
+    cursor: Option<StorageKey>,
+    exhausted: bool,
+    _marker: PhantomData<F>,
+}
+
+impl<'a, T: Config, F: StorageEntry> StorageKeyPager<'a, T, F> {
+    /// Continue after `cursor`, the last key of a page fetched earlier, or from the start
+    /// given `None`.
+    pub fn with_cursor(mut self, cursor: Option<StorageKey>) -> Self {
+        self.cursor = cursor;
+        self.exhausted = false;
+        self
+    }
+
+    /// Fetch the next page of keys, advancing the cursor past them.
+    ///
+    /// Returns an empty page once all keys have been fetched.
+    pub async fn next_page(&mut self) -> Result<Vec<StorageKey>, Error> {
+        if self.exhausted {
+            return Ok(Vec::new())
+        }
+        let keys = self
+            .client
+            .fetch_keys::<F>(self.page_size, self.cursor.clone(), Some(self.hash))
+            .await?;
+        // A short page is the last one; otherwise the next page may still turn out empty.
+        self.exhausted = keys.len() < self.page_size as usize;
+        if let Some(last) = keys.last() {
+            self.cursor = Some(last.clone());
+        }
+        Ok(keys)
+    }
+
+    /// Returns `true` once all keys have been fetched.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Returns the last key fetched, from which the next page continues.
+    pub fn cursor(&self) -> Option<&StorageKey> {
+        self.cursor.as_ref()
+    }
+
+    /// Returns the hash of the block the keys are read at.
+    pub fn block_hash(&self) -> T::Hash {
+        self.hash
+    }
 }
 
 /// Iterates over key value pairs in a map.
+///
+/// Pages of keys are fetched only once the previous page has been consumed, along with the
+/// raw values of its keys. Each value is decoded when it is returned and its raw data is
+/// dropped, so memory is bounded by a single page regardless of the size of the map. Set the
+/// number of keys per page with [`KeyIter::with_page_size`].
 pub struct KeyIter<'a, T: Config, F: StorageEntry> {
     client: StorageClient<'a, T>,
     _marker: PhantomData<F>,
@@ -261,11 +653,43 @@ pub struct KeyIter<'a, T: Config, F: StorageEntry> {
 }
 
 impl<'a, T: Config, F: StorageEntry> KeyIter<'a, T, F> {
+    /// Fetch `page_size` keys and their values per page, bounding how many raw values are
+    /// buffered at once. Defaults to the page size of the client.
+    ///
+    /// A page size of `0` is treated as `1`, since empty pages would end the iteration.
+    pub fn with_page_size(mut self, page_size: u32) -> Self {
+        self.count = page_size.max(1);
+        self
+    }
+
+    /// Turns the iterator into a stream of key value pairs, which ends after the first
+    /// error.
+    ///
+    /// The stream fetches the next page only when it is polled with an empty buffer, so a
+    /// slow consumer holds back fetching rather than letting pages pile up in memory.
+    pub fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<(StorageKey, F::Value), Error>> + 'a
+    where
+        F: 'a,
+    {
+        futures::stream::unfold(Some(self), |iter| {
+            async move {
+                let mut iter = iter?;
+                match iter.next().await {
+                    Ok(Some(item)) => Some((Ok(item), Some(iter))),
+                    Ok(None) => None,
+                    Err(err) => Some((Err(err), None)),
+                }
+            }
+        })
+    }
+
     /// Returns the next key value pair from a map.
     pub async fn next(&mut self) -> Result<Option<(StorageKey, F::Value)>, Error> {
         loop {
             if let Some((k, v)) = self.buffer.pop() {
-                return Ok(Some((k, Decode::decode(&mut &v.0[..])?)))
+                return Ok(Some((k, decode_value(&v.0)?)))
             } else {
                 let keys = self
                     .client
@@ -295,3 +719,43 @@ impl<'a, T: Config, F: StorageEntry> KeyIter<'a, T, F> {
         }
     }
 }
+
+/// Decode a storage value, reporting where decoding failed with [`Error::Decode`].
+fn decode_value<V: Decode>(data: &[u8]) -> Result<V, Error> {
+    let mut input = data;
+    V::decode(&mut input).map_err(|err| {
+        let offset = data.len() - input.len();
+        DecodeError::within(err.into(), std::any::type_name::<V>(), offset)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::MockClient,
+        rpc::RpcClient,
+        test_utils::{
+            MockAccountData,
+            MockConfig,
+        },
+    };
+
+    #[async_std::test]
+    async fn key_pager_treats_a_page_size_of_zero_as_one() {
+        let mock = MockClient::new();
+        mock.set_response("state_getKeysPaged", vec![StorageKey(vec![1])]);
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(mock.clone()));
+        let storage = StorageClient::new(&rpc, None, 10);
+        let mut pager = storage
+            .key_pager::<MockAccountData>(0, Some(Default::default()))
+            .await
+            .unwrap();
+
+        assert_eq!(pager.next_page().await.unwrap(), [StorageKey(vec![1])]);
+        assert!(!pager.is_exhausted());
+        mock.set_response("state_getKeysPaged", Vec::<StorageKey>::new());
+        assert!(pager.next_page().await.unwrap().is_empty());
+        assert!(pager.is_exhausted());
+    }
+}
@@ -14,10 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
-use jsonrpsee::types::{
-    DeserializeOwned,
-    Subscription,
-};
+use codec::Encode;
+use jsonrpsee::types::DeserializeOwned;
 use sp_core::{
     storage::{
         StorageChangeSet,
@@ -26,17 +24,31 @@ use sp_core::{
     twox_128,
 };
 use sp_runtime::traits::Header;
-use std::collections::VecDeque;
+use std::collections::{
+    HashMap,
+    VecDeque,
+};
 
 use crate::{
-    error::Error,
+    error::{
+        Error,
+        TransactionError,
+    },
     events::{
+        BlockEvents,
         EventsDecoder,
         RawEvent,
     },
-    rpc::Rpc,
+    extrinsic::decode_extrinsic,
+    rpc::{
+        Rpc,
+        Subscription,
+    },
+    value::Composite,
+    Client,
     Config,
     Event,
+    Metadata,
     Phase,
 };
 
@@ -62,13 +74,18 @@ enum BlockReader<'a, T: Config> {
 }
 
 impl<'a, T: Config> BlockReader<'a, T> {
-    async fn next(&mut self) -> Option<(T::Hash, Result<Vec<(Phase, RawEvent)>, Error>)> {
+    async fn next(
+        &mut self,
+    ) -> Option<Result<(T::Hash, Result<Vec<(Phase, RawEvent)>, Error>), Error>> {
         match self {
             BlockReader::Decoder {
                 subscription,
                 decoder,
             } => {
-                let change_set = subscription.next().await?;
+                let change_set = match subscription.next().await? {
+                    Ok(change_set) => change_set,
+                    Err(err) => return Some(Err(err)),
+                };
                 let events: Result<Vec<_>, _> = change_set
                     .changes
                     .into_iter()
@@ -78,10 +95,10 @@ impl<'a, T: Config> BlockReader<'a, T> {
                     .collect();
 
                 let flattened_events = events.map(|x| x.into_iter().flatten().collect());
-                Some((change_set.block, flattened_events))
+                Some(Ok((change_set.block, flattened_events)))
             }
             #[cfg(test)]
-            BlockReader::Mock(it) => it.next(),
+            BlockReader::Mock(it) => it.next().map(Ok),
         }
     }
 }
@@ -131,7 +148,13 @@ impl<'a, T: Config> EventSubscription<'a, T> {
                 return None
             }
             // always return None if subscription has closed
-            let (received_hash, events) = self.block_reader.next().await?;
+            let (received_hash, events) = match self.block_reader.next().await? {
+                Ok(block) => block,
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err))
+                }
+            };
             if let Some(hash) = self.block.as_ref() {
                 if &received_hash == hash {
                     self.finished = true;
@@ -200,14 +223,22 @@ impl<T: Config> FinalizedEventStorageSubscription<T> {
     }
 
     /// Gets the next change_set.
-    pub async fn next(&mut self) -> Option<StorageChangeSet<T::Hash>> {
+    ///
+    /// Returns [`Error::SubscriptionStalled`] if the underlying subscription stalled.
+    pub async fn next(&mut self) -> Option<Result<StorageChangeSet<T::Hash>, Error>> {
         loop {
             if let Some(storage_change) = self.storage_changes.pop_front() {
-                return Some(storage_change)
+                return Some(Ok(storage_change))
             }
-            let header: T::Header =
-                read_subscription_response("HeaderSubscription", &mut self.subscription)
-                    .await?;
+            let header: T::Header = match read_subscription_response(
+                "HeaderSubscription",
+                &mut self.subscription,
+            )
+            .await?
+            {
+                Ok(header) => header,
+                Err(err) => return Some(Err(err)),
+            };
             self.storage_changes.extend(
                 self.rpc
                     .query_storage_at(&[self.storage_key.clone()], Some(header.hash()))
@@ -228,7 +259,9 @@ pub enum EventStorageSubscription<T: Config> {
 
 impl<T: Config> EventStorageSubscription<T> {
     /// Gets the next change_set from the subscription.
-    pub async fn next(&mut self) -> Option<StorageChangeSet<T::Hash>> {
+    ///
+    /// Returns [`Error::SubscriptionStalled`] if the underlying subscription stalled.
+    pub async fn next(&mut self) -> Option<Result<StorageChangeSet<T::Hash>, Error>> {
         match self {
             Self::Imported(event_sub) => {
                 read_subscription_response("StorageChangeSetSubscription", event_sub)
@@ -239,16 +272,327 @@ impl<T: Config> EventStorageSubscription<T> {
     }
 }
 
+/// A call included in a finalized block, matched by a [`CallSubscription`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WatchedCall<T: Config> {
+    /// The hash of the block the call was included in.
+    pub block_hash: T::Hash,
+    /// The index of the extrinsic in the block.
+    pub extrinsic_index: usize,
+    /// The address of the signer, or `None` if the extrinsic was unsigned.
+    pub signer: Option<T::Address>,
+    /// The decoded call arguments.
+    pub args: Composite,
+}
+
+/// Subscription to the extrinsics of finalized blocks which make a specific call,
+/// whoever submitted them.
+///
+/// Only top level calls are matched, not calls nested in e.g. `Utility::batch`.
+pub struct CallSubscription<'a, T: Config> {
+    rpc: Rpc<T>,
+    metadata: &'a Metadata,
+    subscription: Subscription<T::Header>,
+    pallet: String,
+    call: String,
+    /// The hash and number of the last finalized block whose calls were matched.
+    last_finalized: Option<(T::Hash, T::BlockNumber)>,
+    calls: VecDeque<Result<WatchedCall<T>, Error>>,
+}
+
+impl<'a, T: Config> CallSubscription<'a, T> {
+    /// Creates a new call subscription, matching the extrinsics of the blocks yielded by
+    /// `subscription`, and of the blocks finalized along with them after the first.
+    pub fn new(
+        rpc: Rpc<T>,
+        metadata: &'a Metadata,
+        subscription: Subscription<T::Header>,
+        pallet: &str,
+        call: &str,
+    ) -> Self {
+        Self {
+            rpc,
+            metadata,
+            subscription,
+            pallet: pallet.to_string(),
+            call: call.to_string(),
+            last_finalized: None,
+            calls: Default::default(),
+        }
+    }
+
+    /// Gets the next matching call. Extrinsics which cannot be decoded are returned as errors.
+    pub async fn next(&mut self) -> Option<Result<WatchedCall<T>, Error>> {
+        loop {
+            if let Some(call) = self.calls.pop_front() {
+                return Some(call)
+            }
+            let header: T::Header = match read_subscription_response(
+                "HeaderSubscription",
+                &mut self.subscription,
+            )
+            .await?
+            {
+                Ok(header) => header,
+                Err(err) => return Some(Err(err)),
+            };
+            let hashes = match self.last_finalized {
+                Some(last) => newly_finalized(&self.rpc, last, &header).await,
+                None => Ok(vec![header.hash()]),
+            };
+            let hashes = match hashes {
+                Ok(hashes) => hashes,
+                Err(err) => return Some(Err(err)),
+            };
+            for block_hash in hashes {
+                if let Err(err) = self.match_calls(block_hash).await {
+                    return Some(Err(err))
+                }
+            }
+            if self
+                .last_finalized
+                .map_or(true, |(_, number)| *header.number() > number)
+            {
+                self.last_finalized = Some((header.hash(), *header.number()));
+            }
+        }
+    }
+
+    /// Queue the matching calls of the block with hash `block_hash`.
+    async fn match_calls(&mut self, block_hash: T::Hash) -> Result<(), Error> {
+        let block = match self.rpc.block(Some(block_hash)).await? {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+        for (extrinsic_index, extrinsic) in block.block.extrinsics.iter().enumerate() {
+            match decode_extrinsic::<T>(self.metadata, &extrinsic.encode()) {
+                Ok(decoded)
+                    if decoded.pallet == self.pallet && decoded.call == self.call =>
+                {
+                    self.calls.push_back(Ok(WatchedCall {
+                        block_hash,
+                        extrinsic_index,
+                        signer: decoded.signer,
+                        args: decoded.args,
+                    }))
+                }
+                Ok(_) => (),
+                Err(err) => self.calls.push_back(Err(err)),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the hashes of the blocks finalized after the block `last` up to `header`, oldest
+/// first, or none if `header` is not above `last`.
+///
+/// Blocks can be finalized several at a time, and only the last one is notified, so the
+/// blocks in between are found by walking the parents of `header` back to `last`.
+pub(crate) async fn newly_finalized<T: Config>(
+    rpc: &Rpc<T>,
+    (last_hash, last_number): (T::Hash, T::BlockNumber),
+    header: &T::Header,
+) -> Result<Vec<T::Hash>, Error> {
+    if *header.number() <= last_number {
+        return Ok(Vec::new())
+    }
+    let mut hashes = vec![header.hash()];
+    let mut parent = *header.parent_hash();
+    while parent != last_hash {
+        let ancestor = rpc
+            .header(Some(parent))
+            .await?
+            .ok_or(Error::Transaction(TransactionError::BlockHashNotFound))?;
+        if *ancestor.number() <= last_number {
+            break
+        }
+        hashes.push(parent);
+        parent = *ancestor.parent_hash();
+    }
+    hashes.reverse();
+    Ok(hashes)
+}
+
+/// A notification of a [`BestBlockSubscription`].
+#[derive(Debug)]
+pub enum BestBlock<T: Config> {
+    /// A block joined the best chain, with the events it emitted.
+    NewBlock(BlockEvents<T>),
+    /// A block previously returned as [`BestBlock::NewBlock`] left the best chain, so its events
+    /// should be rolled back.
+    Retracted(T::Hash),
+}
+
+/// The deepest reorg a [`BestBlockSubscription`] can follow.
+const MAX_REORG_DEPTH: usize = 256;
+
+/// Subscription to the events of the best chain which also reports the blocks a reorg removes
+/// from it.
+///
+/// When a reorg happens, the retracted blocks are returned newest first, followed by the new
+/// blocks of the best chain oldest first. Blocks skipped by the underlying new heads
+/// subscription are filled in, so every block of the best chain is returned exactly once.
+pub struct BestBlockSubscription<'a, T: Config> {
+    client: &'a Client<T>,
+    subscription: Subscription<T::Header>,
+    tracker: ChainTracker<T::Hash>,
+    notifications: VecDeque<Result<BestBlock<T>, Error>>,
+}
+
+impl<'a, T: Config> BestBlockSubscription<'a, T> {
+    /// Creates a new best block subscription, following the headers yielded by `subscription`.
+    pub fn new(client: &'a Client<T>, subscription: Subscription<T::Header>) -> Self {
+        Self {
+            client,
+            subscription,
+            tracker: ChainTracker::new(MAX_REORG_DEPTH),
+            notifications: Default::default(),
+        }
+    }
+
+    /// Gets the next notification.
+    pub async fn next(&mut self) -> Option<Result<BestBlock<T>, Error>> {
+        loop {
+            if let Some(notification) = self.notifications.pop_front() {
+                return Some(notification)
+            }
+            let header: T::Header = match read_subscription_response(
+                "HeaderSubscription",
+                &mut self.subscription,
+            )
+            .await?
+            {
+                Ok(header) => header,
+                Err(err) => return Some(Err(err)),
+            };
+            self.tracker.insert(header.hash(), *header.parent_hash());
+            let (retracted, enacted) = loop {
+                match self.tracker.advance(header.hash()) {
+                    Ok(Step::Done { retracted, enacted }) => break (retracted, enacted),
+                    Ok(Step::NeedHeader(hash)) => {
+                        match self.client.rpc().header(Some(hash)).await {
+                            Ok(Some(header)) => {
+                                self.tracker.insert(hash, *header.parent_hash())
+                            }
+                            Ok(None) => {
+                                return Some(Err(Error::Other(format!(
+                                    "Header of block {:?} not found",
+                                    hash
+                                ))))
+                            }
+                            Err(err) => return Some(Err(err)),
+                        }
+                    }
+                    Err(err) => return Some(Err(err)),
+                }
+            };
+            self.notifications.extend(
+                retracted
+                    .into_iter()
+                    .map(|hash| Ok(BestBlock::Retracted(hash))),
+            );
+            for hash in enacted {
+                let events = self.client.events().at(hash).await;
+                self.notifications
+                    .push_back(events.map(BestBlock::NewBlock));
+            }
+        }
+    }
+}
+
+/// Progress of [`ChainTracker::advance`].
+#[derive(Debug, Eq, PartialEq)]
+enum Step<H> {
+    /// The best chain was updated: `retracted` blocks, newest first, were replaced by
+    /// `enacted` blocks, oldest first.
+    Done { retracted: Vec<H>, enacted: Vec<H> },
+    /// The parent of this block is needed, see [`ChainTracker::insert`].
+    NeedHeader(H),
+}
+
+/// Tracks the recent blocks of the best chain by following parent hashes.
+struct ChainTracker<H> {
+    max_depth: usize,
+    /// The best chain, oldest first.
+    best_chain: VecDeque<H>,
+    parents: HashMap<H, H>,
+}
+
+impl<H: Copy + Eq + std::hash::Hash + std::fmt::Debug> ChainTracker<H> {
+    fn new(max_depth: usize) -> Self {
+        Self {
+            max_depth,
+            best_chain: Default::default(),
+            parents: Default::default(),
+        }
+    }
+
+    /// Record the parent of a block.
+    fn insert(&mut self, hash: H, parent: H) {
+        self.parents.insert(hash, parent);
+    }
+
+    /// Make `best` the head of the best chain, walking back to a block of the current best chain.
+    fn advance(&mut self, best: H) -> Result<Step<H>, Error> {
+        if self.best_chain.is_empty() {
+            self.best_chain.push_back(best);
+            return Ok(Step::Done {
+                retracted: Vec::new(),
+                enacted: vec![best],
+            })
+        }
+
+        let mut path = Vec::new();
+        let mut current = best;
+        let ancestor = loop {
+            if let Some(index) = self.best_chain.iter().rposition(|hash| *hash == current)
+            {
+                break index
+            }
+            if path.len() >= self.max_depth {
+                return Err(Error::Other(format!(
+                    "Block {:?} does not descend from the last {} blocks of the best chain",
+                    best, self.max_depth
+                )))
+            }
+            path.push(current);
+            current = match self.parents.get(&current) {
+                Some(parent) => *parent,
+                None => return Ok(Step::NeedHeader(current)),
+            };
+        };
+
+        let retracted = self.best_chain.drain(ancestor + 1..).rev().collect();
+        path.reverse();
+        self.best_chain.extend(path.iter().copied());
+        while self.best_chain.len() > self.max_depth {
+            self.best_chain.pop_front();
+        }
+        if self.parents.len() > 2 * self.max_depth {
+            let best_chain = &self.best_chain;
+            self.parents.retain(|hash, _| best_chain.contains(hash));
+        }
+        Ok(Step::Done {
+            retracted,
+            enacted: path,
+        })
+    }
+}
+
+/// Read the next notification of a subscription, ending it on errors other than
+/// [`Error::SubscriptionStalled`], which is returned.
 async fn read_subscription_response<T>(
     sub_name: &str,
     sub: &mut Subscription<T>,
-) -> Option<T>
+) -> Option<Result<T, Error>>
 where
     T: DeserializeOwned,
 {
     match sub.next().await {
-        Ok(Some(next)) => Some(next),
+        Ok(Some(next)) => Some(Ok(next)),
         Ok(None) => None,
+        Err(err @ Error::SubscriptionStalled(_)) => Some(Err(err)),
         Err(e) => {
             log::error!("Subscription {} failed: {:?} dropping", sub_name, e);
             None
@@ -259,23 +603,146 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        mock::MockClient,
+        rpc::RpcClient,
+        test_utils::{
+            pallet,
+            MetadataBuilder,
+            MockConfig,
+        },
+    };
+    use frame_metadata::v14::{
+        PalletCallMetadata,
+        PalletMetadata,
+    };
     use sp_core::H256;
-    #[derive(Clone)]
-    struct MockConfig;
-
-    impl Config for MockConfig {
-        type Index = u32;
-        type BlockNumber = u32;
-        type Hash = sp_core::H256;
-        type Hashing = sp_runtime::traits::BlakeTwo256;
-        type AccountId = sp_runtime::AccountId32;
-        type Address = sp_runtime::MultiAddress<Self::AccountId, u32>;
-        type Header = sp_runtime::generic::Header<
-            Self::BlockNumber,
-            sp_runtime::traits::BlakeTwo256,
-        >;
-        type Signature = sp_runtime::MultiSignature;
-        type Extrinsic = sp_runtime::OpaqueExtrinsic;
+    use sp_runtime::{
+        generic::{
+            Block,
+            SignedBlock,
+        },
+        OpaqueExtrinsic,
+    };
+
+    /// Advance `tracker` to `best`, given the parents of all blocks.
+    fn advance(
+        tracker: &mut ChainTracker<u32>,
+        parents: &[(u32, u32)],
+        best: u32,
+    ) -> (Vec<u32>, Vec<u32>) {
+        loop {
+            match tracker.advance(best).unwrap() {
+                Step::Done { retracted, enacted } => return (retracted, enacted),
+                Step::NeedHeader(hash) => {
+                    let (_, parent) = parents.iter().find(|(h, _)| *h == hash).unwrap();
+                    tracker.insert(hash, *parent);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn chain_tracker_follows_forks() {
+        // 1 - 2 - 3 - 4
+        //      \
+        //       12 - 13 - 14 - 15
+        let parents = [
+            (2, 1),
+            (3, 2),
+            (4, 3),
+            (12, 2),
+            (13, 12),
+            (14, 13),
+            (15, 14),
+        ];
+        let mut tracker = ChainTracker::new(256);
+
+        assert_eq!(advance(&mut tracker, &parents, 1), (vec![], vec![1]));
+        assert_eq!(advance(&mut tracker, &parents, 2), (vec![], vec![2]));
+        // Skipped blocks are filled in.
+        assert_eq!(advance(&mut tracker, &parents, 4), (vec![], vec![3, 4]));
+        // A repeated head changes nothing.
+        assert_eq!(advance(&mut tracker, &parents, 4), (vec![], vec![]));
+        // The fork overtakes the best chain.
+        assert_eq!(
+            advance(&mut tracker, &parents, 14),
+            (vec![4, 3], vec![12, 13, 14])
+        );
+        assert_eq!(advance(&mut tracker, &parents, 15), (vec![], vec![15]));
+        // And back onto a descendant of the original chain.
+        let parents = [(5, 4), (6, 5), (7, 6), (8, 7)];
+        assert_eq!(
+            advance(&mut tracker, &parents, 8),
+            (vec![15, 14, 13, 12], vec![3, 4, 5, 6, 7, 8])
+        );
+    }
+
+    #[test]
+    fn chain_tracker_rejects_deep_reorgs() {
+        let mut tracker = ChainTracker::new(2);
+        tracker.advance(1).unwrap();
+        for (hash, parent) in [(10, 0), (11, 10), (12, 11)] {
+            tracker.insert(hash, parent);
+        }
+        assert!(tracker.advance(12).is_err());
+    }
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(scale_info::TypeInfo)]
+    enum SystemCall {
+        remark { remark: Vec<u8> },
+    }
+
+    #[async_std::test]
+    async fn call_subscription_matches_calls_of_skipped_blocks() {
+        let header = |number, parent_hash| {
+            <MockConfig as Config>::Header::new(
+                number,
+                Default::default(),
+                Default::default(),
+                parent_hash,
+                Default::default(),
+            )
+        };
+        let first = header(1, H256::zero());
+        let second = header(2, first.hash());
+        let third = header(3, second.hash());
+        // `System::remark(vec![1, 2, 3])`, unsigned.
+        let remark = OpaqueExtrinsic::from_bytes(&[
+            0x1c, 0x04, 0x00, 0x00, 0x0c, 0x01, 0x02, 0x03,
+        ])
+        .unwrap();
+        let block = SignedBlock {
+            block: Block {
+                header: first.clone(),
+                extrinsics: vec![remark],
+            },
+            justifications: None,
+        };
+        let mock = MockClient::new();
+        // The finalization of the second block is not notified.
+        mock.set_header(second.hash(), &second)
+            .set_response("chain_getBlock", block)
+            .set_notifications("chain_subscribeFinalizedHeads", vec![&first, &third]);
+        let metadata = MetadataBuilder::new()
+            .with_pallet(PalletMetadata {
+                calls: Some(PalletCallMetadata {
+                    ty: scale_info::meta_type::<SystemCall>(),
+                }),
+                ..pallet("System", 0)
+            })
+            .build();
+        let rpc = Rpc::<MockConfig>::new(RpcClient::new(mock));
+        let subscription = rpc.subscribe_finalized_blocks().await.unwrap();
+        let mut calls =
+            CallSubscription::new(rpc, &metadata, subscription, "System", "remark");
+
+        let mut block_hashes = Vec::new();
+        while let Some(call) = calls.next().await {
+            block_hashes.push(call.unwrap().block_hash);
+        }
+        assert_eq!(block_hashes, [first.hash(), second.hash(), third.hash()]);
     }
 
     fn named_event(event_name: &str) -> RawEvent {
@@ -285,6 +752,7 @@ mod tests {
             variant: event_name.to_string(),
             pallet_index: 0,
             variant_index: 0,
+            bytes: sp_core::Bytes::from(vec![0, 0]),
         }
     }
 
@@ -0,0 +1,57 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls and events of the `Sudo` pallet which are independent of the runtime, used to
+//! dispatch any call with `Root` origin on dev and test chains.
+//!
+//! See [`crate::SubmittableExtrinsic::as_sudo`] and
+//! [`crate::TransactionInBlock::wait_for_sudo_success`].
+
+use codec::{
+    Decode,
+    Encode,
+};
+use sp_runtime::DispatchError;
+
+use crate::{
+    Call,
+    Encoded,
+    Event,
+};
+
+/// The `Sudo::sudo` call, dispatching an already encoded call with `Root` origin.
+#[derive(Clone, Debug, Eq, PartialEq, Encode)]
+pub struct Sudo {
+    /// The encoded call, including its pallet and call indices.
+    pub call: Encoded,
+}
+
+impl Call for Sudo {
+    const PALLET: &'static str = "Sudo";
+    const FUNCTION: &'static str = "sudo";
+}
+
+/// The `Sudo::Sudid` event, emitted with the result of a call dispatched by `Sudo::sudo`.
+#[derive(Clone, Debug, Eq, PartialEq, Decode)]
+pub struct Sudid {
+    /// The result of the dispatched call.
+    pub sudo_result: Result<(), DispatchError>,
+}
+
+impl Event for Sudid {
+    const PALLET: &'static str = "Sudo";
+    const EVENT: &'static str = "Sudid";
+}
@@ -0,0 +1,205 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Calls and events of the `System` pallet which are independent of the runtime, backing
+//! [`crate::Client::remark_with_event`] and the well known events of
+//! [`crate::events::prelude`].
+
+use codec::{
+    Compact,
+    Decode,
+    Encode,
+    Error as CodecError,
+    Input,
+};
+use sp_runtime::DispatchError;
+
+use crate::{
+    rpc::DispatchClass,
+    weight::WeightShape,
+    Call,
+    Config,
+    Event,
+    Weight,
+};
+
+/// The `System::remark_with_event` call.
+#[derive(Encode)]
+pub struct RemarkWithEvent {
+    /// The arbitrary bytes to put on chain.
+    pub remark: Vec<u8>,
+}
+
+impl Call for RemarkWithEvent {
+    const PALLET: &'static str = "System";
+    const FUNCTION: &'static str = "remark_with_event";
+}
+
+/// The `System::Remarked` event.
+#[derive(Debug, Eq, PartialEq, Decode)]
+pub struct Remarked<T: Config> {
+    /// The account which made the remark.
+    pub sender: T::AccountId,
+    /// The hash of the remark.
+    pub hash: T::Hash,
+}
+
+impl<T: Config> Event for Remarked<T> {
+    const PALLET: &'static str = "System";
+    const EVENT: &'static str = "Remarked";
+}
+
+/// Whether a dispatch pays a fee.
+///
+/// # Note
+///
+/// This is copied from `frame-support` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode)]
+pub enum Pays {
+    /// The dispatch pays a fee.
+    Yes,
+    /// The dispatch does not pay a fee.
+    No,
+}
+
+/// The weight, class and fee of a dispatch, as reported by its `System::ExtrinsicSuccess` or
+/// `System::ExtrinsicFailed` event.
+///
+/// # Note
+///
+/// This is copied from `frame-support` to avoid a dependency on that crate. Therefore it
+/// must be kept compatible with that type from the target substrate version.
+///
+/// The encoding of the weight depends on the [`WeightShape`] of the runtime. [`Decode`]
+/// expects the scalar weights of legacy runtimes, use [`DispatchInfo::decode_with_shape`] to
+/// decode the dispatch info of any runtime.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DispatchInfo {
+    /// The weight of the dispatch.
+    pub weight: Weight,
+    /// The class of the dispatch.
+    pub class: DispatchClass,
+    /// Whether the dispatch pays a fee.
+    pub pays_fee: Pays,
+}
+
+impl DispatchInfo {
+    /// Decode the dispatch info of a runtime whose weights have the given shape, see
+    /// [`WeightShape::from_metadata`].
+    pub fn decode_with_shape<I: Input>(
+        input: &mut I,
+        shape: WeightShape,
+    ) -> Result<Self, CodecError> {
+        let weight = match shape {
+            WeightShape::Scalar => Weight::from_ref_time(u64::decode(input)?),
+            WeightShape::V2 => {
+                let ref_time = <Compact<u64>>::decode(input)?.0;
+                let proof_size = <Compact<u64>>::decode(input)?.0;
+                Weight::from_parts(ref_time, proof_size)
+            }
+        };
+        Ok(Self {
+            weight,
+            class: DispatchClass::decode(input)?,
+            pays_fee: Pays::decode(input)?,
+        })
+    }
+}
+
+impl Decode for DispatchInfo {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        Self::decode_with_shape(input, WeightShape::Scalar)
+    }
+}
+
+/// The `System::ExtrinsicSuccess` event, emitted by every extrinsic which succeeded.
+#[derive(Clone, Debug, Eq, PartialEq, Decode)]
+pub struct ExtrinsicSuccess {
+    /// The dispatch info of the extrinsic.
+    pub dispatch_info: DispatchInfo,
+}
+
+impl ExtrinsicSuccess {
+    /// Decode the event of a runtime whose weights have the given shape.
+    pub fn decode_with_shape<I: Input>(
+        input: &mut I,
+        shape: WeightShape,
+    ) -> Result<Self, CodecError> {
+        Ok(Self {
+            dispatch_info: DispatchInfo::decode_with_shape(input, shape)?,
+        })
+    }
+}
+
+impl Event for ExtrinsicSuccess {
+    const PALLET: &'static str = "System";
+    const EVENT: &'static str = "ExtrinsicSuccess";
+}
+
+/// The `System::ExtrinsicFailed` event, emitted by every extrinsic which failed.
+///
+/// Use [`crate::RuntimeError::from_dispatch`] to resolve the pallet error of the dispatch
+/// error.
+#[derive(Clone, Debug, Eq, PartialEq, Decode)]
+pub struct ExtrinsicFailed {
+    /// The error the extrinsic failed with.
+    pub dispatch_error: DispatchError,
+    /// The dispatch info of the extrinsic.
+    pub dispatch_info: DispatchInfo,
+}
+
+impl ExtrinsicFailed {
+    /// Decode the event of a runtime whose weights have the given shape.
+    pub fn decode_with_shape<I: Input>(
+        input: &mut I,
+        shape: WeightShape,
+    ) -> Result<Self, CodecError> {
+        Ok(Self {
+            dispatch_error: DispatchError::decode(input)?,
+            dispatch_info: DispatchInfo::decode_with_shape(input, shape)?,
+        })
+    }
+}
+
+impl Event for ExtrinsicFailed {
+    const PALLET: &'static str = "System";
+    const EVENT: &'static str = "ExtrinsicFailed";
+}
+
+/// The `System::NewAccount` event, emitted when an account is created.
+#[derive(Debug, Eq, PartialEq, Decode)]
+pub struct NewAccount<T: Config> {
+    /// The account created.
+    pub account: T::AccountId,
+}
+
+impl<T: Config> Event for NewAccount<T> {
+    const PALLET: &'static str = "System";
+    const EVENT: &'static str = "NewAccount";
+}
+
+/// The `System::KilledAccount` event, emitted when an account is reaped.
+#[derive(Debug, Eq, PartialEq, Decode)]
+pub struct KilledAccount<T: Config> {
+    /// The account reaped.
+    pub account: T::AccountId,
+}
+
+impl<T: Config> Event for KilledAccount<T> {
+    const PALLET: &'static str = "System";
+    const EVENT: &'static str = "KilledAccount";
+}
@@ -0,0 +1,172 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fixtures shared by the unit tests of the crate.
+
+use codec::Encode;
+use frame_metadata::{
+    v14::{
+        PalletMetadata,
+        SignedExtensionMetadata,
+    },
+    ExtrinsicMetadata,
+    RuntimeMetadataPrefixed,
+    RuntimeMetadataV14,
+};
+use scale_info::meta_type;
+
+use crate::{
+    storage::{
+        StorageEntryKey,
+        StorageMapKey,
+    },
+    AccountData,
+    Config,
+    DefaultExtra,
+    ExtrinsicExtraData,
+    Metadata,
+    StorageEntry,
+    StorageHasher,
+};
+
+/// A [`Config`] with the types of the substrate node template.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct MockConfig;
+
+impl Config for MockConfig {
+    type Index = u32;
+    type BlockNumber = u32;
+    type Hash = sp_core::H256;
+    type Hashing = sp_runtime::traits::BlakeTwo256;
+    type AccountId = sp_runtime::AccountId32;
+    type Balance = u128;
+    type Address = sp_runtime::MultiAddress<Self::AccountId, u32>;
+    type Header =
+        sp_runtime::generic::Header<Self::BlockNumber, sp_runtime::traits::BlakeTwo256>;
+    type Signature = sp_runtime::MultiSignature;
+    type Extrinsic = sp_runtime::OpaqueExtrinsic;
+}
+
+impl ExtrinsicExtraData<MockConfig> for MockConfig {
+    type AccountData = MockAccountData;
+    type Extra = DefaultExtra<MockConfig>;
+}
+
+/// The `System::Account` entry of an account of [`MockConfig`], holding just its nonce.
+pub(crate) struct MockAccountData(sp_runtime::AccountId32);
+
+impl StorageEntry for MockAccountData {
+    const PALLET: &'static str = "System";
+    const STORAGE: &'static str = "Account";
+    type Value = u32;
+
+    fn key(&self) -> StorageEntryKey {
+        StorageEntryKey::Map(vec![StorageMapKey::new(
+            &self.0,
+            StorageHasher::Blake2_128Concat,
+        )])
+    }
+}
+
+impl AccountData<MockConfig> for MockAccountData {
+    fn storage_entry(account_id: sp_runtime::AccountId32) -> Self {
+        Self(account_id)
+    }
+
+    fn nonce(result: &u32) -> u32 {
+        *result
+    }
+}
+
+/// Builds the metadata of a runtime from its pallets and signed extensions.
+pub(crate) struct MetadataBuilder {
+    pallets: Vec<PalletMetadata>,
+    signed_extensions: Vec<SignedExtensionMetadata>,
+    extrinsic_version: u8,
+}
+
+impl MetadataBuilder {
+    /// A runtime without pallets or signed extensions, with extrinsics of version 4.
+    pub(crate) fn new() -> Self {
+        Self {
+            pallets: Vec::new(),
+            signed_extensions: Vec::new(),
+            extrinsic_version: 4,
+        }
+    }
+
+    /// Add a pallet, see [`pallet`].
+    pub(crate) fn with_pallet(mut self, pallet: PalletMetadata) -> Self {
+        self.pallets.push(pallet);
+        self
+    }
+
+    /// Add a signed extension, in the order they are encoded in extrinsics.
+    pub(crate) fn with_signed_extension(
+        mut self,
+        signed_extension: SignedExtensionMetadata,
+    ) -> Self {
+        self.signed_extensions.push(signed_extension);
+        self
+    }
+
+    /// Set the version of the extrinsics of the runtime.
+    pub(crate) fn set_extrinsic_version(mut self, version: u8) -> Self {
+        self.extrinsic_version = version;
+        self
+    }
+
+    pub(crate) fn build(self) -> Metadata {
+        self.prefixed().try_into().unwrap()
+    }
+
+    /// Build the SCALE encoded metadata, as returned by `state_getMetadata`.
+    pub(crate) fn encode(self) -> Vec<u8> {
+        self.prefixed().encode()
+    }
+
+    fn prefixed(self) -> RuntimeMetadataPrefixed {
+        let metadata = RuntimeMetadataV14::new(
+            self.pallets,
+            ExtrinsicMetadata {
+                ty: meta_type::<()>(),
+                version: self.extrinsic_version,
+                signed_extensions: self.signed_extensions,
+            },
+            meta_type::<()>(),
+        );
+        RuntimeMetadataPrefixed::from(metadata)
+    }
+}
+
+/// Metadata without any pallets, so that every call, event and error is unknown.
+pub(crate) fn empty_metadata() -> Metadata {
+    MetadataBuilder::new().build()
+}
+
+/// A pallet without calls, events, errors, constants or storage, to be filled in with the
+/// struct update syntax.
+pub(crate) fn pallet(name: &'static str, index: u8) -> PalletMetadata {
+    PalletMetadata {
+        name,
+        storage: None,
+        calls: None,
+        event: None,
+        constants: vec![],
+        error: None,
+        index,
+    }
+}
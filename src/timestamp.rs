@@ -0,0 +1,38 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage of the `Timestamp` pallet which is independent of the runtime, backing
+//! [`crate::Client::timestamp`].
+
+use crate::{
+    StorageEntry,
+    StorageEntryKey,
+};
+
+/// The `Timestamp::Now` storage entry, the time at which the block was produced, in
+/// milliseconds since the Unix epoch.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Now;
+
+impl StorageEntry for Now {
+    const PALLET: &'static str = "Timestamp";
+    const STORAGE: &'static str = "Now";
+    type Value = u64;
+
+    fn key(&self) -> StorageEntryKey {
+        StorageEntryKey::Plain
+    }
+}
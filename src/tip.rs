@@ -0,0 +1,218 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Estimating the tip which gives an extrinsic a transaction pool priority above a target.
+//!
+//! The transaction payment pallet sets the priority of a signed extrinsic to its tip times the
+//! number of such extrinsics which would fit in a block, so that extrinsics which use more of
+//! the block need a larger tip for the same priority. Operational extrinsics additionally get a
+//! virtual tip of their inclusion fee times `TransactionPayment::OperationalFeeMultiplier`.
+
+use sp_runtime::FixedU128;
+
+use crate::{
+    rpc::{
+        DispatchClass,
+        RuntimeDispatchInfo,
+    },
+    value::Value,
+    Error,
+    Metadata,
+    StorageEntry,
+    StorageEntryKey,
+};
+
+/// The `TransactionPayment::NextFeeMultiplier` storage entry, the multiplier of the weight
+/// fees of the next block, which rises while blocks are fuller than targeted.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct NextFeeMultiplier;
+
+impl StorageEntry for NextFeeMultiplier {
+    const PALLET: &'static str = "TransactionPayment";
+    const STORAGE: &'static str = "NextFeeMultiplier";
+    type Value = FixedU128;
+
+    fn key(&self) -> StorageEntryKey {
+        StorageEntryKey::Plain
+    }
+}
+
+/// The limits of a block for a dispatch class, which the priority of an extrinsic depends on.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlockLimits {
+    /// The maximum total computation time of a block, `BlockWeights::max_block`.
+    pub max_weight: u64,
+    /// The maximum total length of the extrinsics of the class in a block,
+    /// `BlockLength::max` of the class.
+    pub max_length: u64,
+    /// The multiplier of the inclusion fee giving the virtual tip of operational extrinsics.
+    pub operational_fee_multiplier: u128,
+}
+
+impl BlockLimits {
+    /// Read the limits for `class` from the `System::BlockWeights`, `System::BlockLength` and
+    /// `TransactionPayment::OperationalFeeMultiplier` constants.
+    ///
+    /// Like the transaction payment pallet, this uses `BlockWeights::max_block` rather than
+    /// the `max_total` weight of `class`.
+    ///
+    /// Runtimes without an operational fee multiplier are assumed not to give operational
+    /// extrinsics a virtual tip.
+    pub fn from_metadata(
+        metadata: &Metadata,
+        class: DispatchClass,
+    ) -> Result<Self, Error> {
+        let constant = |pallet: &str, name: &'static str| -> Result<Value, Error> {
+            let constant = metadata.pallet(pallet)?.constant(name)?;
+            Value::decode(metadata, constant.ty.id(), &mut &constant.value[..])
+        };
+        let class_name = match class {
+            DispatchClass::Normal => "normal",
+            DispatchClass::Operational => "operational",
+            DispatchClass::Mandatory => "mandatory",
+        };
+
+        let weights = constant("System", "BlockWeights")?;
+        let max_weight = field(&weights, "max_block")
+            .and_then(ref_time)
+            .ok_or_else(|| Error::Other("BlockWeights has no max_block".into()))?;
+
+        let length = constant("System", "BlockLength")?;
+        let max_length = field(&length, "max")
+            .and_then(|max| field(max, class_name))
+            .and_then(Value::as_u128)
+            .ok_or_else(|| Error::Other("BlockLength has no max length".into()))?;
+
+        let operational_fee_multiplier =
+            match constant("TransactionPayment", "OperationalFeeMultiplier") {
+                Ok(multiplier) => multiplier.as_u128().unwrap_or_default(),
+                Err(_) => 0,
+            };
+
+        Ok(Self {
+            max_weight: max_weight as u64,
+            max_length: max_length as u64,
+            operational_fee_multiplier,
+        })
+    }
+
+    /// The number of extrinsics like the one described by `info` which fit in a block.
+    fn max_per_block(&self, info: &RuntimeDispatchInfo) -> u64 {
+        let by_weight = self.max_weight / info.weight.ref_time.max(1);
+        let by_length = self.max_length / u64::from(info.len).max(1);
+        by_weight.min(by_length).max(1)
+    }
+}
+
+/// Computes the tip which gives an extrinsic a transaction pool priority above a target, see
+/// [`SubmittableExtrinsic::tip_for_priority`](crate::SubmittableExtrinsic::tip_for_priority).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PriorityTip {
+    priority: u64,
+    margin_percent: u32,
+}
+
+impl PriorityTip {
+    /// Target a priority above `priority`, e.g. that of the extrinsics an extrinsic should be
+    /// included before.
+    pub fn new(priority: u64) -> Self {
+        Self {
+            priority,
+            margin_percent: 100,
+        }
+    }
+
+    /// Scale the computed tip by `percent`, e.g. `150` to tip half as much again, as a margin
+    /// for changes of the fees or of the pool before the extrinsic is included. Defaults to
+    /// `100`.
+    pub fn set_margin_percent(mut self, percent: u32) -> Self {
+        self.margin_percent = percent;
+        self
+    }
+
+    /// The tip giving the extrinsic described by `info` a priority above the target.
+    ///
+    /// `info.len` should be the length of the extrinsic with the tip set: a larger tip is
+    /// encoded in more bytes, which lowers the priority it gives.
+    pub fn tip(&self, limits: &BlockLimits, info: &RuntimeDispatchInfo) -> u128 {
+        let virtual_tip = match info.class {
+            DispatchClass::Operational => {
+                info.partial_fee
+                    .saturating_mul(limits.operational_fee_multiplier)
+            }
+            DispatchClass::Normal | DispatchClass::Mandatory => 0,
+        };
+        let per_block = u128::from(limits.max_per_block(info));
+        let tip = (u128::from(self.priority) / per_block + 1).saturating_sub(virtual_tip);
+        tip.saturating_mul(self.margin_percent.into()) / 100
+    }
+}
+
+/// The computation time of a scalar or two dimensional weight.
+fn ref_time(weight: &Value) -> Option<u128> {
+    weight
+        .as_u128()
+        .or_else(|| field(weight, "ref_time").and_then(Value::as_u128))
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    match value {
+        Value::Composite(composite) => composite.field(name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Weight;
+
+    fn info(class: DispatchClass, ref_time: u64, len: u32) -> RuntimeDispatchInfo {
+        RuntimeDispatchInfo {
+            weight: Weight::from_ref_time(ref_time),
+            class,
+            partial_fee: 1_000,
+            len,
+        }
+    }
+
+    const LIMITS: BlockLimits = BlockLimits {
+        max_weight: 1_000_000,
+        max_length: 10_000,
+        operational_fee_multiplier: 5,
+    };
+
+    #[test]
+    fn tip_raises_the_priority_above_the_target() {
+        // 100 such extrinsics fit in a block by weight, and 50 by length.
+        let info = info(DispatchClass::Normal, 10_000, 200);
+        let tip = PriorityTip::new(1_000).tip(&LIMITS, &info);
+        assert_eq!(tip, 21);
+        assert!(tip * 50 > 1_000);
+
+        let tip = PriorityTip::new(1_000)
+            .set_margin_percent(200)
+            .tip(&LIMITS, &info);
+        assert_eq!(tip, 42);
+    }
+
+    #[test]
+    fn operational_extrinsics_get_a_virtual_tip() {
+        let info = info(DispatchClass::Operational, 10_000, 200);
+        assert_eq!(PriorityTip::new(1_000).tip(&LIMITS, &info), 0);
+        assert_eq!(PriorityTip::new(500_000).tip(&LIMITS, &info), 5_001);
+    }
+}
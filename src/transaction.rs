@@ -14,34 +14,81 @@
 // You should have received a copy of the GNU General Public License
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
+use codec::Encode;
+use futures::{
+    future::{
+        self,
+        Either,
+    },
+    stream,
+    Stream,
+};
 use sp_core::storage::StorageKey;
-use sp_runtime::traits::Hash;
 pub use sp_runtime::traits::SignedExtension;
+use sp_runtime::traits::{
+    Hash,
+    Header,
+};
 pub use sp_version::RuntimeVersion;
+use std::{
+    collections::VecDeque,
+    time::Duration,
+};
 
 use crate::{
-    client::Client,
+    client::{
+        account_nonce,
+        Client,
+        SubmittableExtrinsic,
+    },
     error::{
         Error,
         TransactionError,
+        WatchedStatus,
     },
-    rpc::SubstrateTransactionStatus,
-    subscription::SystemEvents,
+    events::decoder_for,
+    extrinsic::{
+        SignedExtra,
+        SignedPayload,
+        Signer,
+        UncheckedExtrinsic,
+    },
+    rpc::{
+        Subscription as RpcSubscription,
+        SubstrateTransactionStatus,
+    },
+    subscription::{
+        newly_finalized,
+        SystemEvents,
+    },
+    sudo::Sudid,
+    value::Composite,
+    weight::WeightShape,
+    Call,
     Config,
+    EventRecord,
+    ExtrinsicExtraData,
     Phase,
 };
-use jsonrpsee::types::{
-    Error as RpcError,
-    Subscription as RpcSubscription,
-};
+use jsonrpsee::types::Error as RpcError;
 
 /// This struct represents a subscription to the progress of some transaction, and is
 /// returned from [`crate::SubmittableExtrinsic::sign_and_submit_then_watch()`].
+///
+/// Besides waiting for an outcome with the `wait_for_*` methods, every status reported by the
+/// node can be followed with [`TransactionProgress::next`] or
+/// [`TransactionProgress::into_stream`], e.g. to show the progress of the transaction and
+/// notice when its block is retracted or it is dropped from the pool.
+///
+/// Dropping it, or any future waiting on it, unsubscribes from the status of the transaction,
+/// see [`TransactionProgress::cancel`].
 #[derive(Debug)]
 pub struct TransactionProgress<'client, T: Config> {
     sub: Option<RpcSubscription<SubstrateTransactionStatus<T::Hash, T::Hash>>>,
     ext_hash: T::Hash,
     client: &'client Client<T>,
+    timeout: Option<futures_timer::Delay>,
+    last_status: WatchedStatus,
 }
 
 impl<'client, T: Config> TransactionProgress<'client, T> {
@@ -54,9 +101,51 @@ impl<'client, T: Config> TransactionProgress<'client, T> {
             sub: Some(sub),
             client,
             ext_hash,
+            timeout: None,
+            last_status: WatchedStatus::Unknown,
         }
     }
 
+    /// Give up watching the transaction if it is not finalized within `timeout`.
+    ///
+    /// [`TransactionProgress::next`], and so the `wait_for_*` methods, then fail with
+    /// [`Error::WatchTimeout`] and the last status received, telling apart transactions which
+    /// never got into a block from those which were not finalized in time.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(futures_timer::Delay::new(timeout));
+        self
+    }
+
+    /// Stop watching the transaction, unsubscribing so that the node stops sending statuses.
+    ///
+    /// This is the same as dropping the [`TransactionProgress`]. The transaction itself stays
+    /// in the pool, and may still be included in a block.
+    pub fn cancel(mut self) {
+        self.sub = None;
+    }
+
+    /// Returns the hash of the extrinsic being watched.
+    pub fn extrinsic_hash(&self) -> T::Hash {
+        self.ext_hash
+    }
+
+    /// Turn the subscription into a stream of the statuses returned by
+    /// [`TransactionProgress::next`], which ends after the first error.
+    pub fn into_stream(
+        self,
+    ) -> impl Stream<Item = Result<TransactionStatus<'client, T>, Error>> + 'client {
+        stream::unfold(Some(self), |progress| {
+            async move {
+                let mut progress = progress?;
+                match progress.next().await {
+                    Ok(Some(status)) => Some((Ok(status), Some(progress))),
+                    Ok(None) => None,
+                    Err(err) => Some((Err(err), None)),
+                }
+            }
+        })
+    }
+
     /// Return the next transaction status when it's emitted.
     pub async fn next(&mut self) -> Result<Option<TransactionStatus<'client, T>>, Error> {
         // Return `None` if the subscription has been dropped:
@@ -65,8 +154,26 @@ impl<'client, T: Config> TransactionProgress<'client, T> {
             None => return Ok(None),
         };
 
-        // Return the next item otherwise:
-        let res = sub.next().await?;
+        // Return the next item otherwise, unless the watch timed out first:
+        let res = match &mut self.timeout {
+            Some(delay) => {
+                match future::select(Box::pin(sub.next()), delay).await {
+                    Either::Left((res, _)) => Some(res),
+                    Either::Right(_) => None,
+                }
+            }
+            None => Some(sub.next().await),
+        };
+        let res = match res {
+            Some(res) => res?,
+            None => {
+                self.sub = None;
+                return Err(Error::WatchTimeout(self.last_status.clone()))
+            }
+        };
+        if let Some(status) = &res {
+            self.last_status = watched_status(status);
+        }
         Ok(res.map(|status| {
             match status {
                 SubstrateTransactionStatus::Future => TransactionStatus::Future,
@@ -190,6 +297,32 @@ impl<'client, T: Config> TransactionProgress<'client, T> {
     }
 }
 
+/// The owned counterpart of `status`, reported by [`Error::WatchTimeout`].
+fn watched_status<Hash: Encode>(
+    status: &SubstrateTransactionStatus<Hash, Hash>,
+) -> WatchedStatus {
+    match status {
+        SubstrateTransactionStatus::Future => WatchedStatus::Future,
+        SubstrateTransactionStatus::Ready => WatchedStatus::Ready,
+        SubstrateTransactionStatus::Broadcast(peers) => {
+            WatchedStatus::Broadcast(peers.clone())
+        }
+        SubstrateTransactionStatus::InBlock(hash)
+        | SubstrateTransactionStatus::FinalityTimeout(hash)
+        | SubstrateTransactionStatus::Finalized(hash) => {
+            WatchedStatus::InBlock(hash.encode())
+        }
+        SubstrateTransactionStatus::Retracted(hash) => {
+            WatchedStatus::Retracted(hash.encode())
+        }
+        SubstrateTransactionStatus::Usurped(hash) => {
+            WatchedStatus::Usurped(hash.encode())
+        }
+        SubstrateTransactionStatus::Dropped => WatchedStatus::Dropped,
+        SubstrateTransactionStatus::Invalid => WatchedStatus::Invalid,
+    }
+}
+
 //* Dev note: The below is adapted from the substrate docs on `TransactionStatus`, which this
 //* enum was adapted from (and which is an exact copy of `SubstrateTransactionStatus` in this crate).
 //* Note that the number of finality watchers is, at the time of writing, found in the constant
@@ -318,20 +451,28 @@ impl<'client, T: Config> TransactionInBlock<'client, T> {
     /// from them.
     pub async fn wait_for_success(&self) -> Result<TransactionEvents<T>, Error> {
         let events = self.fetch_events().await?;
-
-        // Try to find any errors; return the first one we encounter.
-        for ev in events.as_slice() {
-            if &ev.pallet == "System" && &ev.variant == "ExtrinsicFailed" {
-                use codec::Decode;
-                let dispatch_error = sp_runtime::DispatchError::decode(&mut &*ev.data)?;
-                let runtime_error = crate::RuntimeError::from_dispatch(
-                    self.client.metadata(),
-                    dispatch_error,
-                )?;
-                return Err(runtime_error.into())
-            }
+        if let Some(runtime_error) = events.failure.clone() {
+            return Err(runtime_error.into())
         }
+        Ok(events)
+    }
 
+    /// Like [`TransactionInBlock::wait_for_success`], but additionally checks the result of a
+    /// call dispatched through [`crate::SubmittableExtrinsic::as_sudo`], returning its error if
+    /// it failed.
+    ///
+    /// Returns an error if the transaction did not emit a `Sudo::Sudid` event.
+    pub async fn wait_for_sudo_success(&self) -> Result<TransactionEvents<T>, Error> {
+        let events = self.wait_for_success().await?;
+        let sudid = events
+            .find_first_event::<Sudid>()?
+            .ok_or_else(|| Error::Other("No Sudo::Sudid event found".into()))?;
+        if let Err(dispatch_error) = sudid.sudo_result {
+            let metadata = self.client.metadata_provider().at(self.block_hash).await?;
+            let runtime_error =
+                crate::RuntimeError::from_dispatch(&metadata, dispatch_error)?;
+            return Err(runtime_error.into())
+        }
         Ok(events)
     }
 
@@ -370,32 +511,128 @@ impl<'client, T: Config> TransactionInBlock<'client, T> {
             .map(|s| s.0)
             .unwrap_or_else(Vec::new);
 
-        let events = self
-            .client
-            .events_decoder()
-            .decode_events(&mut &*raw_events)?
-            .into_iter()
-            .filter(move |(phase, _raw)| {
-                phase == &Phase::ApplyExtrinsic(extrinsic_idx as u32)
-            })
-            .map(|(_phase, event)| event)
-            .collect();
+        // Decode with the metadata of the runtime at the block, which may have been upgraded
+        // since the client was built.
+        let metadata = self.client.metadata_provider().at(self.block_hash).await?;
+        let records = decoder_for(self.client, metadata.clone())
+            .decode_event_records(&mut &*raw_events)?;
+        let (all_events, events) = extrinsic_events(records, extrinsic_idx as u32)?;
+        let failure = find_failure(&metadata, &events)?;
 
         Ok(TransactionEvents {
             block_hash: self.block_hash,
+            block_number: *block.block.header.number(),
             ext_hash: self.ext_hash,
+            ext_index: extrinsic_idx as u32,
             events,
+            all_events,
+            failure,
+            weight_shape: WeightShape::from_metadata(&metadata),
         })
     }
 }
 
+/// Decode the first `System::ExtrinsicFailed` event found in the given events into a
+/// [`crate::RuntimeError`], if there is one.
+fn find_failure(
+    metadata: &crate::Metadata,
+    events: &[crate::RawEvent],
+) -> Result<Option<crate::RuntimeError>, Error> {
+    for ev in events {
+        if &ev.pallet == "System" && &ev.variant == "ExtrinsicFailed" {
+            let runtime_error = decode_failure(metadata, ev)?;
+            return Ok(Some(runtime_error))
+        }
+    }
+    Ok(None)
+}
+
+/// Split the event `records` of a block into all of its decoded events and the events
+/// emitted by the extrinsic at `extrinsic_idx`.
+///
+/// Returns an error if the events of the extrinsic may follow an undecodable event: its
+/// length is unknown, so the bytes of all of the events after it are left undecoded, and
+/// a `System::ExtrinsicFailed` event among them would go unnoticed.
+fn extrinsic_events(
+    records: Vec<(Phase, EventRecord)>,
+    extrinsic_idx: u32,
+) -> Result<(Vec<(Phase, crate::RawEvent)>, Vec<crate::RawEvent>), Error> {
+    let mut all_events = Vec::with_capacity(records.len());
+    let mut events = Vec::new();
+    for (phase, record) in records {
+        match record {
+            EventRecord::Decoded(event) => {
+                if phase == Phase::ApplyExtrinsic(extrinsic_idx) {
+                    events.push(event.clone());
+                }
+                all_events.push((phase, event));
+            }
+            EventRecord::Raw {
+                pallet_index,
+                variant_index,
+                ..
+            } => {
+                let hides_extrinsic = match phase {
+                    Phase::Initialization => true,
+                    Phase::ApplyExtrinsic(idx) => idx <= extrinsic_idx,
+                    Phase::Finalization => false,
+                };
+                if hides_extrinsic {
+                    return Err(TransactionError::UndecodedEvents {
+                        pallet_index,
+                        variant_index,
+                    }
+                    .into())
+                }
+                log::warn!(
+                    "Skipping undecodable event {}:{} emitted after the transaction",
+                    pallet_index,
+                    variant_index
+                );
+            }
+        }
+    }
+    Ok((all_events, events))
+}
+
+/// Decode the dispatch error of a `System::ExtrinsicFailed` event into a
+/// [`crate::RuntimeError`].
+fn decode_failure(
+    metadata: &crate::Metadata,
+    event: &crate::RawEvent,
+) -> Result<crate::RuntimeError, Error> {
+    use codec::Decode;
+    if let Ok(dispatch_error) = sp_runtime::DispatchError::decode(&mut &*event.data) {
+        return crate::RuntimeError::from_dispatch(metadata, dispatch_error)
+    }
+    // The `DispatchError` of the runtime has variants unknown to this version of
+    // `sp_runtime`, so decode it with the metadata instead.
+    let event_metadata = metadata.event(event.pallet_index, event.variant_index)?;
+    let fields = Composite::decode(
+        metadata,
+        event_metadata.variant().fields(),
+        &mut &event.data[..],
+    )?;
+    let dispatch_error = fields
+        .values()
+        .first()
+        .copied()
+        .ok_or_else(|| Error::Other("ExtrinsicFailed has no fields".into()))?;
+    crate::RuntimeError::from_dispatch_value(metadata, dispatch_error)
+}
+
 /// This represents the events related to our transaction.
 /// We can iterate over the events, or look for a specific one.
 #[derive(Debug)]
 pub struct TransactionEvents<T: Config> {
     block_hash: T::Hash,
+    block_number: T::BlockNumber,
     ext_hash: T::Hash,
+    ext_index: u32,
     events: Vec<crate::RawEvent>,
+    all_events: Vec<(Phase, crate::RawEvent)>,
+    failure: Option<crate::RuntimeError>,
+    weight_shape: WeightShape,
 }
 
 impl<T: Config> TransactionEvents<T> {
@@ -404,16 +641,38 @@ impl<T: Config> TransactionEvents<T> {
         self.block_hash
     }
 
+    /// Return the number of the block that the transaction has made it into.
+    pub fn block_number(&self) -> T::BlockNumber {
+        self.block_number
+    }
+
     /// Return the hash of the extrinsic.
     pub fn extrinsic_hash(&self) -> T::Hash {
         self.ext_hash
     }
 
+    /// Return the index of the extrinsic in the body of the block that it has made it into.
+    ///
+    /// Together with [`TransactionEvents::block_hash`] this locates the extrinsic on chain,
+    /// e.g. to prove its inclusion with [`crate::proof::prove_extrinsic`].
+    pub fn extrinsic_index(&self) -> u32 {
+        self.ext_index
+    }
+
     /// Return a slice of the returned events.
     pub fn as_slice(&self) -> &[crate::RawEvent] {
         &self.events
     }
 
+    /// Return every event of the block that the transaction has made it into with its phase,
+    /// including those emitted by other extrinsics and during block initialization or
+    /// finalization.
+    ///
+    /// The other methods only consider the events emitted by the transaction itself.
+    pub fn all_block_events(&self) -> &[(Phase, crate::RawEvent)] {
+        &self.all_events
+    }
+
     /// Find all of the events matching the event type provided as a generic parameter. This
     /// will return an error if a matching event is found but cannot be properly decoded.
     pub fn find_events<E: crate::Event>(&self) -> Result<Vec<E>, Error> {
@@ -423,6 +682,22 @@ impl<T: Config> TransactionEvents<T> {
             .collect()
     }
 
+    /// Decode the events of the transaction which are one of the well known events of
+    /// [`crate::events::prelude`], skipping any other events.
+    ///
+    /// The weights of the `System` events are decoded in the [`WeightShape`] of the runtime
+    /// at the block.
+    pub fn well_known_events(&self) -> Result<Vec<crate::WellKnownEvent<T>>, Error> {
+        self.events
+            .iter()
+            .filter_map(|e| {
+                crate::WellKnownEvent::from_raw_with_shape(e, self.weight_shape)
+                    .transpose()
+            })
+            .collect::<Result<_, _>>()
+            .map_err(Into::into)
+    }
+
     /// Find the first event that matches the event type provided as a generic parameter. This
     /// will return an error if a matching event is found but cannot be properly decoded.
     ///
@@ -437,6 +712,26 @@ impl<T: Config> TransactionEvents<T> {
             .map_err(Into::into)
     }
 
+    /// Find the first event that matches the event type provided as a generic parameter,
+    /// treating its absence as an error.
+    ///
+    /// If the event is not found and the extrinsic failed, the decoded `ExtrinsicFailed`
+    /// reason is returned. Otherwise [`TransactionError::EventNotFound`] names the pallet
+    /// and event that were expected.
+    pub fn find_event_required<E: crate::Event>(&self) -> Result<E, Error> {
+        if let Some(event) = self.find_first_event::<E>()? {
+            return Ok(event)
+        }
+        if let Some(runtime_error) = self.failure.clone() {
+            return Err(runtime_error.into())
+        }
+        Err(TransactionError::EventNotFound {
+            pallet: E::PALLET,
+            event: E::EVENT,
+        }
+        .into())
+    }
+
     /// Find an event. Returns true if it was found.
     pub fn has_event<E: crate::Event>(&self) -> Result<bool, Error> {
         Ok(self.find_first_event::<E>()?.is_some())
@@ -449,3 +744,325 @@ impl<T: Config> std::ops::Deref for TransactionEvents<T> {
         &self.events
     }
 }
+
+/// Tracks many submitted extrinsics until they are finalized, using a single subscription to
+/// finalized blocks rather than one subscription per extrinsic.
+///
+/// Extrinsics submitted through the watcher by the same signer are given consecutive nonces,
+/// starting from the nonce of the signer or of its account. An extrinsic with an explicit
+/// nonce, e.g. set with [`SubmittableExtrinsic::nonce`], is signed with it, and the following
+/// extrinsics of the signer count on from there.
+pub struct BatchWatcher<'client, T: Config> {
+    client: &'client Client<T>,
+    finalized: RpcSubscription<T::Header>,
+    last_finalized: (T::Hash, T::BlockNumber),
+    nonces: Vec<(T::AccountId, T::Index)>,
+    pending: Vec<T::Hash>,
+    ready: VecDeque<TransactionInBlock<'client, T>>,
+}
+
+impl<'client, T: Config> BatchWatcher<'client, T> {
+    /// Subscribe to finalized blocks, ready to watch submitted extrinsics.
+    pub async fn new(client: &'client Client<T>) -> Result<Self, Error> {
+        let finalized = client.rpc().subscribe_finalized_blocks().await?;
+        let hash = client.rpc().finalized_head().await?;
+        let header = client
+            .rpc()
+            .header(Some(hash))
+            .await?
+            .ok_or(Error::Transaction(TransactionError::BlockHashNotFound))?;
+        Ok(Self {
+            client,
+            finalized,
+            last_finalized: (hash, *header.number()),
+            nonces: Vec::new(),
+            pending: Vec::new(),
+            ready: VecDeque::new(),
+        })
+    }
+
+    /// Wait for the next watched extrinsic to be finalized, returning the block it was
+    /// included in. Returns `None` once all of them have been.
+    ///
+    /// **Note:** extrinsics which are dropped from the transaction pool are never finalized, so
+    /// this waits forever unless a subscription idle timeout is set, see
+    /// [`crate::ClientBuilder::set_subscription_idle_timeout`].
+    pub async fn next(
+        &mut self,
+    ) -> Option<Result<TransactionInBlock<'client, T>, Error>> {
+        loop {
+            if let Some(tx) = self.ready.pop_front() {
+                return Some(Ok(tx))
+            }
+            if self.pending.is_empty() {
+                return None
+            }
+            let header = match self.finalized.next().await {
+                Ok(Some(header)) => header,
+                Ok(None) => {
+                    return Some(Err(
+                        RpcError::Custom("RPC subscription dropped".into()).into()
+                    ))
+                }
+                Err(err) => return Some(Err(err)),
+            };
+            if let Err(err) = self.process_finalized(header).await {
+                return Some(Err(err))
+            }
+        }
+    }
+
+    /// Wait for all watched extrinsics to be finalized, returning the blocks they were included
+    /// in, in the order they were submitted.
+    pub async fn wait_for_finalized(
+        mut self,
+    ) -> Result<Vec<TransactionInBlock<'client, T>>, Error> {
+        let order = self.pending.clone();
+        let mut finalized = Vec::with_capacity(order.len());
+        while let Some(tx) = self.next().await {
+            finalized.push(tx?);
+        }
+        finalized.sort_by_key(|tx| order.iter().position(|hash| *hash == tx.ext_hash));
+        Ok(finalized)
+    }
+
+    /// Look for watched extrinsics in the newly finalized blocks up to `header`.
+    async fn process_finalized(&mut self, header: T::Header) -> Result<(), Error> {
+        if *header.number() <= self.last_finalized.1 {
+            return Ok(())
+        }
+        let hashes =
+            newly_finalized(self.client.rpc(), self.last_finalized, &header).await?;
+        for block_hash in hashes {
+            let block = self
+                .client
+                .rpc()
+                .block(Some(block_hash))
+                .await?
+                .ok_or(Error::Transaction(TransactionError::BlockHashNotFound))?;
+            for ext in &block.block.extrinsics {
+                let ext_hash = T::Hashing::hash_of(ext);
+                if let Some(index) =
+                    self.pending.iter().position(|hash| *hash == ext_hash)
+                {
+                    self.pending.remove(index);
+                    self.ready.push_back(TransactionInBlock {
+                        block_hash,
+                        ext_hash,
+                        client: self.client,
+                    });
+                }
+            }
+        }
+        self.last_finalized = (header.hash(), *header.number());
+        Ok(())
+    }
+}
+
+impl<'client, T> BatchWatcher<'client, T>
+where
+    T: Config + ExtrinsicExtraData<T>,
+{
+    /// Sign and submit an extrinsic, watching it until it is finalized. Returns its hash.
+    pub async fn submit<C: Call + Send + Sync>(
+        &mut self,
+        extrinsic: SubmittableExtrinsic<'client, T, C>,
+        signer: &(dyn Signer<T> + Send + Sync),
+    ) -> Result<T::Hash, Error>
+    where
+        <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static
+    {
+        let account = signer.account_id();
+        let explicit_nonce =
+            <T::Extra as SignedExtra<T>>::explicit_nonce(extrinsic.additional_params());
+        let nonce = match (
+            explicit_nonce,
+            self.nonces.iter().find(|(id, _)| id == account),
+        ) {
+            (Some(nonce), _) => nonce,
+            (None, Some((_, nonce))) => *nonce,
+            (None, None) => account_nonce(self.client, signer).await?,
+        };
+        let hash = extrinsic
+            .sign_and_submit(&WithNonce { signer, nonce })
+            .await?;
+        match self.nonces.iter_mut().find(|(id, _)| id == account) {
+            Some((_, next)) => *next = nonce + 1u32.into(),
+            None => self.nonces.push((account.clone(), nonce + 1u32.into())),
+        }
+        self.pending.push(hash);
+        Ok(hash)
+    }
+}
+
+/// A signer signing with the given nonce.
+struct WithNonce<'a, T: Config + ExtrinsicExtraData<T>> {
+    signer: &'a (dyn Signer<T> + Send + Sync),
+    nonce: T::Index,
+}
+
+#[async_trait::async_trait]
+impl<'a, T> Signer<T> for WithNonce<'a, T>
+where
+    T: Config + ExtrinsicExtraData<T>,
+    <<<T as ExtrinsicExtraData<T>>::Extra as SignedExtra<T>>::Extra as SignedExtension>::AdditionalSigned: Send + Sync + 'static,
+{
+    fn account_id(&self) -> &T::AccountId {
+        self.signer.account_id()
+    }
+
+    fn nonce(&self) -> Option<T::Index> {
+        Some(self.nonce)
+    }
+
+    async fn sign(
+        &self,
+        extrinsic: SignedPayload<T>,
+    ) -> Result<UncheckedExtrinsic<T>, String> {
+        self.signer.sign(extrinsic).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::{
+            DecodingMode,
+            EventsDecoder,
+        },
+        mock::MockClient,
+        rpc::RpcClient,
+        test_utils::{
+            pallet,
+            MetadataBuilder,
+            MockConfig,
+        },
+        ClientBuilder,
+        PairSigner,
+    };
+    use codec::Compact;
+    use frame_metadata::v14::{
+        PalletCallMetadata,
+        PalletEventMetadata,
+        PalletMetadata,
+    };
+    use sp_keyring::AccountKeyring;
+
+    #[derive(Encode, scale_info::TypeInfo)]
+    enum SystemEvent {
+        #[codec(index = 1)]
+        ExtrinsicFailed(u8),
+    }
+
+    /// Decode `(extrinsic_idx, pallet_index, event)` records of `ApplyExtrinsic` phases in
+    /// [`DecodingMode::SkipUnknown`], with the metadata of a `System` pallet only.
+    fn decode_records(records: &[(u32, u8, Vec<u8>)]) -> Vec<(Phase, EventRecord)> {
+        let metadata = MetadataBuilder::new()
+            .with_pallet(PalletMetadata {
+                event: Some(PalletEventMetadata {
+                    ty: scale_info::meta_type::<SystemEvent>(),
+                }),
+                ..pallet("System", 0)
+            })
+            .build();
+        let mut events = Compact(records.len() as u32).encode();
+        for (extrinsic_idx, pallet_index, event) in records {
+            // `Phase::ApplyExtrinsic(extrinsic_idx)`
+            events.extend((0u8, extrinsic_idx).encode());
+            events.push(*pallet_index);
+            events.extend(event);
+            // No topics.
+            events.extend(Vec::<sp_core::H256>::new().encode());
+        }
+        let mut decoder = EventsDecoder::<MockConfig>::new(metadata);
+        decoder.set_mode(DecodingMode::SkipUnknown);
+        decoder.decode_event_records(&mut &events[..]).unwrap()
+    }
+
+    #[test]
+    fn unknown_event_before_failure_is_an_error() {
+        let failed = SystemEvent::ExtrinsicFailed(3).encode();
+        let records = decode_records(&[(0, 9, vec![1, 42]), (0, 0, failed)]);
+
+        assert_eq!(
+            extrinsic_events(records, 0).unwrap_err().to_string(),
+            Error::from(TransactionError::UndecodedEvents {
+                pallet_index: 9,
+                variant_index: 1,
+            })
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn unknown_event_after_the_extrinsic_is_skipped() {
+        let failed = SystemEvent::ExtrinsicFailed(3).encode();
+        let records = decode_records(&[(0, 0, failed), (1, 9, vec![1, 42])]);
+
+        let (all_events, events) = extrinsic_events(records, 0).unwrap();
+        assert_eq!(all_events.len(), 1);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].pallet, "System");
+        assert_eq!(events[0].variant, "ExtrinsicFailed");
+    }
+
+    #[allow(dead_code, non_camel_case_types)]
+    #[derive(scale_info::TypeInfo)]
+    enum SystemCall {
+        remark { remark: Vec<u8> },
+    }
+
+    #[derive(Encode)]
+    struct Remark {
+        remark: Vec<u8>,
+    }
+
+    impl Call for Remark {
+        const PALLET: &'static str = "System";
+        const FUNCTION: &'static str = "remark";
+    }
+
+    #[async_std::test]
+    async fn batch_watcher_counts_on_from_an_explicit_nonce() {
+        let metadata = MetadataBuilder::new()
+            .with_pallet(PalletMetadata {
+                calls: Some(PalletCallMetadata {
+                    ty: scale_info::meta_type::<SystemCall>(),
+                }),
+                ..pallet("System", 0)
+            })
+            .encode();
+        let genesis = sp_core::H256::zero();
+        let genesis_header = <MockConfig as Config>::Header::new(
+            0,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        );
+        let mock = MockClient::new();
+        mock.set_metadata(metadata)
+            .set_response("chain_getFinalizedHead", genesis)
+            .set_header(genesis, genesis_header)
+            .set_notifications(
+                "chain_subscribeFinalizedHeads",
+                Vec::<<MockConfig as Config>::Header>::new(),
+            )
+            .set_response("author_submitExtrinsic", sp_core::H256::repeat_byte(1));
+        let client = ClientBuilder::new()
+            .set_client(RpcClient::new(mock))
+            .build::<MockConfig>()
+            .await
+            .unwrap();
+        let signer = PairSigner::<MockConfig, _>::new(AccountKeyring::Alice.pair());
+        let account = signer.account_id().clone();
+        let remark = || SubmittableExtrinsic::new(&client, Remark { remark: vec![1] });
+
+        let mut watcher = BatchWatcher::new(&client).await.unwrap();
+        watcher.submit(remark().nonce(7), &signer).await.unwrap();
+        assert_eq!(watcher.nonces, [(account.clone(), 8)]);
+        watcher.submit(remark(), &signer).await.unwrap();
+        assert_eq!(watcher.nonces, [(account, 9)]);
+    }
+}
@@ -0,0 +1,834 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A dynamic representation of SCALE encoded values, decoded using the type registry of the
+//! runtime metadata rather than types generated at compile time.
+
+use codec::{
+    Compact,
+    Decode,
+    Encode,
+};
+use scale_info::{
+    form::PortableForm,
+    Field,
+    Type,
+    TypeDef,
+    TypeDefPrimitive,
+};
+use serde::ser::{
+    Serialize,
+    SerializeMap,
+    SerializeSeq,
+    Serializer,
+};
+
+use crate::{
+    error::DecodeError,
+    Encoded,
+    Error,
+    Metadata,
+    MetadataError,
+};
+
+/// Encode a call by the names of its pallet and function, checking the number of `args` and
+/// encoding each of them as the type of the corresponding call argument.
+pub fn encode_call(
+    metadata: &Metadata,
+    pallet: &str,
+    call: &str,
+    args: &[Value],
+) -> Result<Encoded, Error> {
+    let pallet_metadata = metadata.pallet(pallet)?;
+    let variant = pallet_metadata.call(call).ok_or_else(|| {
+        Error::InvalidCallArgs(format!("{}::{} not found in metadata", pallet, call))
+    })?;
+    let fields = variant.fields();
+    if args.len() != fields.len() {
+        let names = fields
+            .iter()
+            .map(|field| field.name().map_or("_", |name| name.as_str()))
+            .collect::<Vec<_>>();
+        return Err(Error::InvalidCallArgs(format!(
+            "{}::{} expects {} arguments ({}), got {}",
+            pallet,
+            call,
+            fields.len(),
+            names.join(", "),
+            args.len()
+        )))
+    }
+    let mut bytes = vec![pallet_metadata.index(), variant.index()];
+    for (i, (arg, field)) in args.iter().zip(fields).enumerate() {
+        arg.encode_as(metadata, field.ty().id(), &mut bytes)
+            .map_err(|err| {
+                let name = field.name().cloned().unwrap_or_else(|| i.to_string());
+                Error::InvalidCallArgs(format!(
+                    "{}::{} argument `{}`: {}",
+                    pallet, call, name, err
+                ))
+            })?;
+    }
+    Ok(Encoded(bytes))
+}
+
+/// A value of any type described by the metadata type registry.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Value {
+    /// A struct or tuple.
+    Composite(Composite),
+    /// An enum variant.
+    Variant {
+        /// The name of the variant.
+        name: String,
+        /// The fields of the variant.
+        fields: Composite,
+    },
+    /// A sequence or fixed length array.
+    Sequence(Vec<Value>),
+    /// A primitive value.
+    Primitive(Primitive),
+}
+
+/// The fields of a struct, tuple or enum variant.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Composite {
+    /// Named fields, e.g. of a struct.
+    Named(Vec<(String, Value)>),
+    /// Unnamed fields, e.g. of a tuple.
+    Unnamed(Vec<Value>),
+}
+
+/// A primitive value.
+///
+/// Unsigned and signed integers are widened to 128 bits, whether or not they were compact
+/// encoded; 256 bit integers are kept as their little endian bytes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Primitive {
+    /// A boolean.
+    Bool(bool),
+    /// A unicode character.
+    Char(char),
+    /// A string.
+    Str(String),
+    /// An unsigned integer of up to 128 bits.
+    U128(u128),
+    /// A signed integer of up to 128 bits.
+    I128(i128),
+    /// An unsigned 256 bit integer.
+    U256([u8; 32]),
+    /// A signed 256 bit integer.
+    I256([u8; 32]),
+}
+
+impl Value {
+    /// Decode a value of the type with the given id in the metadata type registry.
+    ///
+    /// Fails with [`Error::Decode`], locating the type, field and byte offset at which
+    /// decoding failed, if the input does not hold a value of the type.
+    pub fn decode(
+        metadata: &Metadata,
+        type_id: u32,
+        input: &mut &[u8],
+    ) -> Result<Self, Error> {
+        let total = input.len();
+        Value::decode_at(metadata, type_id, input, total)
+    }
+
+    /// Decode a value from `input`, which holds the last bytes of an input of `total` bytes.
+    fn decode_at(
+        metadata: &Metadata,
+        type_id: u32,
+        input: &mut &[u8],
+        total: usize,
+    ) -> Result<Self, Error> {
+        let ty = metadata
+            .resolve_type(type_id)
+            .ok_or(MetadataError::TypeNotFound(type_id))?;
+        Value::decode_type(metadata, ty, input, total)
+            .map_err(|err| DecodeError::within_type(err, ty, total - input.len()))
+    }
+
+    fn decode_type(
+        metadata: &Metadata,
+        ty: &Type<PortableForm>,
+        input: &mut &[u8],
+        total: usize,
+    ) -> Result<Self, Error> {
+        match ty.type_def() {
+            TypeDef::Composite(composite) => {
+                Ok(Value::Composite(Composite::decode_at(
+                    metadata,
+                    composite.fields(),
+                    input,
+                    total,
+                )?))
+            }
+            TypeDef::Variant(variant) => {
+                let index = u8::decode(input)?;
+                let variant = variant
+                    .variants()
+                    .iter()
+                    .find(|v| v.index() == index)
+                    .ok_or_else(|| {
+                        Error::Other(format!("Variant {} not found", index))
+                    })?;
+                let fields =
+                    Composite::decode_at(metadata, variant.fields(), input, total)
+                        .map_err(|err| DecodeError::within_field(err, variant.name()))?;
+                Ok(Value::Variant {
+                    name: variant.name().clone(),
+                    fields,
+                })
+            }
+            TypeDef::Sequence(seq) => {
+                let len = <Compact<u32>>::decode(input)?.0;
+                let values = (0..len)
+                    .map(|i| {
+                        Value::decode_at(metadata, seq.type_param().id(), input, total)
+                            .map_err(|err| DecodeError::within_field(err, i.to_string()))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::Sequence(values))
+            }
+            TypeDef::Array(arr) => {
+                let values = (0..arr.len())
+                    .map(|i| {
+                        Value::decode_at(metadata, arr.type_param().id(), input, total)
+                            .map_err(|err| DecodeError::within_field(err, i.to_string()))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::Sequence(values))
+            }
+            TypeDef::Tuple(tuple) => {
+                let values = tuple
+                    .fields()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        Value::decode_at(metadata, field.id(), input, total)
+                            .map_err(|err| DecodeError::within_field(err, i.to_string()))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::Composite(Composite::Unnamed(values)))
+            }
+            TypeDef::Primitive(primitive) => {
+                Ok(Value::Primitive(Primitive::decode(primitive, input)?))
+            }
+            TypeDef::Compact(compact) => {
+                Value::decode_compact(metadata, compact.type_param().id(), input)
+            }
+            TypeDef::BitSequence(_) => {
+                Err(Error::Other("Bit sequences are not supported".into()))
+            }
+        }
+    }
+
+    fn decode_compact(
+        metadata: &Metadata,
+        type_id: u32,
+        input: &mut &[u8],
+    ) -> Result<Self, Error> {
+        let ty = metadata
+            .resolve_type(type_id)
+            .ok_or(MetadataError::TypeNotFound(type_id))?;
+        match ty.type_def() {
+            TypeDef::Primitive(primitive) => {
+                let value = match primitive {
+                    TypeDefPrimitive::U8 => <Compact<u8>>::decode(input)?.0 as u128,
+                    TypeDefPrimitive::U16 => <Compact<u16>>::decode(input)?.0 as u128,
+                    TypeDefPrimitive::U32 => <Compact<u32>>::decode(input)?.0 as u128,
+                    TypeDefPrimitive::U64 => <Compact<u64>>::decode(input)?.0 as u128,
+                    TypeDefPrimitive::U128 => <Compact<u128>>::decode(input)?.0,
+                    prim => {
+                        return Err(Error::Other(format!(
+                            "Compact encoding of {:?} is not supported",
+                            prim
+                        )))
+                    }
+                };
+                Ok(Value::Primitive(Primitive::U128(value)))
+            }
+            // Compact encoded single field wrappers, e.g. `Perbill`.
+            TypeDef::Composite(composite) => {
+                match composite.fields() {
+                    [field] => {
+                        let value =
+                            Value::decode_compact(metadata, field.ty().id(), input)?;
+                        let fields = match field.name() {
+                            Some(name) => Composite::Named(vec![(name.clone(), value)]),
+                            None => Composite::Unnamed(vec![value]),
+                        };
+                        Ok(Value::Composite(fields))
+                    }
+                    _ => {
+                        Err(Error::Other(
+                            "Compact composite type must have a single field".into(),
+                        ))
+                    }
+                }
+            }
+            _ => {
+                Err(Error::Other(
+                    "Compact type must be a primitive or a composite type".into(),
+                ))
+            }
+        }
+    }
+
+    /// A sequence of `u8` values, e.g. to pass an account id as a storage map key.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Value::Sequence(
+            bytes
+                .iter()
+                .map(|b| Value::Primitive(Primitive::U128((*b).into())))
+                .collect(),
+        )
+    }
+
+    /// An unsigned integer.
+    pub fn u128(n: u128) -> Self {
+        Value::Primitive(Primitive::U128(n))
+    }
+
+    /// The unsigned integer the value holds, if any. Composites with a single field, such as
+    /// a compact weight dimension or a wrapped balance, are unwrapped.
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Primitive(Primitive::U128(n)) => Some(*n),
+            Value::Composite(composite) => {
+                match composite.values().as_slice() {
+                    [value] => value.as_u128(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// A call of the runtime `Call` enum, e.g. to pass as the boxed call argument of
+    /// `Sudo::sudo` or `Scheduler::schedule`.
+    ///
+    /// Runtime calls are variants of the pallet, holding the variant of the call with its
+    /// arguments. A boxed call is encoded like any other, without a length prefix, so the
+    /// already encoded bytes of a call cannot be passed instead.
+    pub fn call(pallet: &str, call: &str, args: Composite) -> Self {
+        Value::Variant {
+            name: pallet.to_string(),
+            fields: Composite::Unnamed(vec![Value::Variant {
+                name: call.to_string(),
+                fields: args,
+            }]),
+        }
+    }
+
+    /// Convert the value to JSON, e.g. to print values of any type, see the `Serialize`
+    /// implementation for the representation.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self)
+            .expect("values serialize to JSON; map keys are strings; qed")
+    }
+
+    /// Encode the value as the type with the given id in the metadata type registry.
+    ///
+    /// A value which is not a composite can be encoded as a composite type with a single
+    /// field, such as a wrapped account id.
+    pub fn encode_as(
+        &self,
+        metadata: &Metadata,
+        type_id: u32,
+        output: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let ty = metadata
+            .resolve_type(type_id)
+            .ok_or(MetadataError::TypeNotFound(type_id))?;
+        let mismatch = || {
+            Error::Other(format!(
+                "Value {:?} does not match the type {} with id {}",
+                self,
+                ty.path().segments().join("::"),
+                type_id
+            ))
+        };
+
+        match (ty.type_def(), self) {
+            (TypeDef::Composite(composite), Value::Composite(fields)) => {
+                fields.encode_as(metadata, composite.fields(), output)
+            }
+            (TypeDef::Composite(composite), value) => {
+                match composite.fields() {
+                    [field] => value.encode_as(metadata, field.ty().id(), output),
+                    _ => Err(mismatch()),
+                }
+            }
+            (TypeDef::Variant(variant), Value::Variant { name, fields }) => {
+                let variant = variant
+                    .variants()
+                    .iter()
+                    .find(|v| v.name() == name)
+                    .ok_or_else(mismatch)?;
+                variant.index().encode_to(output);
+                fields.encode_as(metadata, variant.fields(), output)
+            }
+            (TypeDef::Sequence(seq), Value::Sequence(values)) => {
+                Compact(values.len() as u32).encode_to(output);
+                for value in values {
+                    value.encode_as(metadata, seq.type_param().id(), output)?;
+                }
+                Ok(())
+            }
+            (TypeDef::Array(arr), Value::Sequence(values))
+                if values.len() == arr.len() as usize =>
+            {
+                for value in values {
+                    value.encode_as(metadata, arr.type_param().id(), output)?;
+                }
+                Ok(())
+            }
+            (TypeDef::Tuple(tuple), Value::Composite(fields))
+                if fields.values().len() == tuple.fields().len() =>
+            {
+                for (value, field) in fields.values().into_iter().zip(tuple.fields()) {
+                    value.encode_as(metadata, field.id(), output)?;
+                }
+                Ok(())
+            }
+            (TypeDef::Primitive(primitive), Value::Primitive(value)) => {
+                value.encode_as(primitive, output).ok_or_else(mismatch)
+            }
+            (TypeDef::Compact(compact), value) => {
+                let inner_id = compact.type_param().id();
+                let inner = metadata
+                    .resolve_type(inner_id)
+                    .ok_or(MetadataError::TypeNotFound(inner_id))?;
+                let n = match (inner.type_def(), value) {
+                    (TypeDef::Primitive(_), Value::Primitive(Primitive::U128(n))) => *n,
+                    (TypeDef::Composite(_), value) => {
+                        // Unwrap single field wrappers such as `Perbill`.
+                        let mut fields = match value {
+                            Value::Composite(fields) => fields.values(),
+                            value => vec![value],
+                        };
+                        match (fields.pop(), fields.is_empty()) {
+                            (Some(Value::Primitive(Primitive::U128(n))), true) => *n,
+                            _ => return Err(mismatch()),
+                        }
+                    }
+                    _ => return Err(mismatch()),
+                };
+                Compact(n).encode_to(output);
+                Ok(())
+            }
+            _ => Err(mismatch()),
+        }
+    }
+}
+
+impl Composite {
+    /// Decode the given fields, named if the fields themselves are named.
+    ///
+    /// Fails with [`Error::Decode`] if the input does not hold values of the fields, see
+    /// [`Value::decode`].
+    pub fn decode(
+        metadata: &Metadata,
+        fields: &[Field<PortableForm>],
+        input: &mut &[u8],
+    ) -> Result<Self, Error> {
+        let total = input.len();
+        Composite::decode_at(metadata, fields, input, total)
+    }
+
+    fn decode_at(
+        metadata: &Metadata,
+        fields: &[Field<PortableForm>],
+        input: &mut &[u8],
+        total: usize,
+    ) -> Result<Self, Error> {
+        let mut decode_field = |i: usize, field: &Field<PortableForm>| {
+            Value::decode_at(metadata, field.ty().id(), input, total).map_err(|err| {
+                let name = field.name().cloned().unwrap_or_else(|| i.to_string());
+                DecodeError::within_field(err, name)
+            })
+        };
+        if fields.iter().all(|field| field.name().is_some()) && !fields.is_empty() {
+            let values = fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let name = field.name().cloned().unwrap_or_default();
+                    Ok((name, decode_field(i, field)?))
+                })
+                .collect::<Result<_, Error>>()?;
+            Ok(Composite::Named(values))
+        } else {
+            let values = fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| decode_field(i, field))
+                .collect::<Result<_, _>>()?;
+            Ok(Composite::Unnamed(values))
+        }
+    }
+
+    /// Returns the value of the field with the given name, if the fields are named.
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        match self {
+            Composite::Named(fields) => {
+                fields.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+            }
+            Composite::Unnamed(_) => None,
+        }
+    }
+
+    /// Returns the values of the fields in order.
+    pub fn values(&self) -> Vec<&Value> {
+        match self {
+            Composite::Named(fields) => fields.iter().map(|(_, v)| v).collect(),
+            Composite::Unnamed(values) => values.iter().collect(),
+        }
+    }
+
+    /// Encode the fields as the given type fields, matching named fields by name and
+    /// unnamed fields by position.
+    fn encode_as(
+        &self,
+        metadata: &Metadata,
+        fields: &[Field<PortableForm>],
+        output: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        if self.values().len() != fields.len() {
+            return Err(Error::Other(format!(
+                "Expected {} fields, got {}",
+                fields.len(),
+                self.values().len()
+            )))
+        }
+        for (i, field) in fields.iter().enumerate() {
+            let value = match (self, field.name()) {
+                (Composite::Named(_), Some(name)) => {
+                    self.field(name)
+                        .ok_or_else(|| Error::Other(format!("Missing field {}", name)))?
+                }
+                _ => self.values()[i],
+            };
+            value.encode_as(metadata, field.ty().id(), output)?;
+        }
+        Ok(())
+    }
+}
+
+impl Primitive {
+    fn decode(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<Self, Error> {
+        let value = match primitive {
+            TypeDefPrimitive::Bool => Primitive::Bool(bool::decode(input)?),
+            TypeDefPrimitive::Char => {
+                let c = u32::decode(input)?;
+                Primitive::Char(
+                    char::from_u32(c)
+                        .ok_or_else(|| Error::Other(format!("Invalid char {}", c)))?,
+                )
+            }
+            TypeDefPrimitive::Str => Primitive::Str(String::decode(input)?),
+            TypeDefPrimitive::U8 => Primitive::U128(u8::decode(input)?.into()),
+            TypeDefPrimitive::U16 => Primitive::U128(u16::decode(input)?.into()),
+            TypeDefPrimitive::U32 => Primitive::U128(u32::decode(input)?.into()),
+            TypeDefPrimitive::U64 => Primitive::U128(u64::decode(input)?.into()),
+            TypeDefPrimitive::U128 => Primitive::U128(u128::decode(input)?),
+            TypeDefPrimitive::U256 => Primitive::U256(<[u8; 32]>::decode(input)?),
+            TypeDefPrimitive::I8 => Primitive::I128(i8::decode(input)?.into()),
+            TypeDefPrimitive::I16 => Primitive::I128(i16::decode(input)?.into()),
+            TypeDefPrimitive::I32 => Primitive::I128(i32::decode(input)?.into()),
+            TypeDefPrimitive::I64 => Primitive::I128(i64::decode(input)?.into()),
+            TypeDefPrimitive::I128 => Primitive::I128(i128::decode(input)?),
+            TypeDefPrimitive::I256 => Primitive::I256(<[u8; 32]>::decode(input)?),
+        };
+        Ok(value)
+    }
+
+    /// Encode as the given primitive type, or `None` if the value does not fit it.
+    fn encode_as(
+        &self,
+        primitive: &TypeDefPrimitive,
+        output: &mut Vec<u8>,
+    ) -> Option<()> {
+        match (primitive, self) {
+            (TypeDefPrimitive::Bool, Primitive::Bool(b)) => b.encode_to(output),
+            (TypeDefPrimitive::Char, Primitive::Char(c)) => (*c as u32).encode_to(output),
+            (TypeDefPrimitive::Str, Primitive::Str(s)) => s.encode_to(output),
+            (TypeDefPrimitive::U8, Primitive::U128(n)) => {
+                u8::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::U16, Primitive::U128(n)) => {
+                u16::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::U32, Primitive::U128(n)) => {
+                u32::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::U64, Primitive::U128(n)) => {
+                u64::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::U128, Primitive::U128(n)) => n.encode_to(output),
+            (TypeDefPrimitive::U256, Primitive::U256(bytes)) => bytes.encode_to(output),
+            (TypeDefPrimitive::I8, Primitive::I128(n)) => {
+                i8::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::I16, Primitive::I128(n)) => {
+                i16::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::I32, Primitive::I128(n)) => {
+                i32::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::I64, Primitive::I128(n)) => {
+                i64::try_from(*n).ok()?.encode_to(output)
+            }
+            (TypeDefPrimitive::I128, Primitive::I128(n)) => n.encode_to(output),
+            (TypeDefPrimitive::I256, Primitive::I256(bytes)) => bytes.encode_to(output),
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// Composites serialize as maps or sequences, and variants as a map from the variant name
+/// to its fields, or just the name if it has none.
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Composite(composite) => composite.serialize(serializer),
+            Value::Variant { name, fields } => {
+                if fields.values().is_empty() {
+                    serializer.serialize_str(name)
+                } else {
+                    let mut map = serializer.serialize_map(Some(1))?;
+                    map.serialize_entry(name, fields)?;
+                    map.end()
+                }
+            }
+            Value::Sequence(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+            Value::Primitive(primitive) => primitive.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for Composite {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Composite::Named(fields) => {
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, value) in fields {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            }
+            Composite::Unnamed(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for value in values {
+                    seq.serialize_element(value)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+/// Integers which do not fit in 64 bits serialize as decimal strings, and 256 bit integers
+/// as hex strings of their little endian bytes.
+impl Serialize for Primitive {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Primitive::Bool(b) => serializer.serialize_bool(*b),
+            Primitive::Char(c) => serializer.serialize_char(*c),
+            Primitive::Str(s) => serializer.serialize_str(s),
+            Primitive::U128(n) => {
+                match u64::try_from(*n) {
+                    Ok(n) => serializer.serialize_u64(n),
+                    Err(_) => serializer.serialize_str(&n.to_string()),
+                }
+            }
+            Primitive::I128(n) => {
+                match i64::try_from(*n) {
+                    Ok(n) => serializer.serialize_i64(n),
+                    Err(_) => serializer.serialize_str(&n.to_string()),
+                }
+            }
+            Primitive::U256(bytes) | Primitive::I256(bytes) => {
+                serializer.serialize_str(&format!("0x{}", hex::encode(bytes)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{
+        pallet,
+        MetadataBuilder,
+    };
+    use frame_metadata::v14::{
+        PalletCallMetadata,
+        PalletMetadata,
+    };
+
+    #[derive(Encode, scale_info::TypeInfo)]
+    enum RuntimeCall {
+        System(SystemCall),
+        Scheduler(SchedulerCall),
+    }
+
+    #[allow(non_camel_case_types)]
+    #[derive(Encode, scale_info::TypeInfo)]
+    enum SystemCall {
+        remark { remark: Vec<u8> },
+    }
+
+    #[allow(non_camel_case_types)]
+    #[derive(Encode, scale_info::TypeInfo)]
+    enum SchedulerCall {
+        schedule { when: u32, call: Box<RuntimeCall> },
+    }
+
+    /// A pallet with calls of type `C`.
+    fn pallet_with_calls<C: scale_info::TypeInfo + 'static>(
+        name: &'static str,
+        index: u8,
+    ) -> PalletMetadata {
+        PalletMetadata {
+            calls: Some(PalletCallMetadata {
+                ty: scale_info::meta_type::<C>(),
+            }),
+            ..pallet(name, index)
+        }
+    }
+
+    fn metadata() -> Metadata {
+        MetadataBuilder::new()
+            .with_pallet(pallet_with_calls::<SystemCall>("System", 0))
+            .with_pallet(pallet_with_calls::<SchedulerCall>("Scheduler", 1))
+            .build()
+    }
+
+    #[test]
+    fn encodes_and_decodes_boxed_calls() {
+        let metadata = metadata();
+        let remark = Value::call(
+            "System",
+            "remark",
+            Composite::Named(vec![("remark".into(), Value::from_bytes(b"boxed"))]),
+        );
+        let encoded = encode_call(
+            &metadata,
+            "Scheduler",
+            "schedule",
+            &[Value::u128(5), remark.clone()],
+        )
+        .unwrap();
+
+        let expected = RuntimeCall::Scheduler(SchedulerCall::schedule {
+            when: 5,
+            call: Box::new(RuntimeCall::System(SystemCall::remark {
+                remark: b"boxed".to_vec(),
+            })),
+        });
+        assert_eq!(encoded.0, expected.encode());
+
+        let schedule = metadata
+            .pallet("Scheduler")
+            .unwrap()
+            .call("schedule")
+            .unwrap();
+        let args = Composite::decode(&metadata, schedule.fields(), &mut &encoded.0[2..])
+            .unwrap();
+        assert_eq!(args.field("call"), Some(&remark));
+    }
+
+    #[test]
+    fn decodes_values_by_type_path() {
+        let metadata = metadata();
+        let bytes = SystemCall::remark {
+            remark: b"hex".to_vec(),
+        }
+        .encode();
+        let expected = Value::Variant {
+            name: "remark".into(),
+            fields: Composite::Named(vec![("remark".into(), Value::from_bytes(b"hex"))]),
+        };
+
+        let by_path = metadata
+            .decode_value("subxt::value::tests::SystemCall", &bytes)
+            .unwrap();
+        assert_eq!(by_path, expected);
+        let by_name = metadata.decode_value("SystemCall", &bytes).unwrap();
+        assert_eq!(by_name, expected);
+        let id = metadata.type_id_by_path("SystemCall").unwrap();
+        assert_eq!(metadata.decode_value(id, &bytes).unwrap(), expected);
+
+        assert!(matches!(
+            metadata.decode_value("BalancesCall", &bytes),
+            Err(Error::Metadata(MetadataError::TypePathNotFound(_)))
+        ));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(metadata.decode_value("SystemCall", &trailing).is_err());
+    }
+
+    #[test]
+    fn reads_unsigned_integers_of_wrapped_values() {
+        assert_eq!(Value::u128(7).as_u128(), Some(7));
+        let wrapped = Value::Composite(Composite::Unnamed(vec![Value::u128(7)]));
+        assert_eq!(wrapped.as_u128(), Some(7));
+        let weight = Value::Composite(Composite::Named(vec![
+            ("ref_time".into(), Value::u128(7)),
+            ("proof_size".into(), Value::u128(8)),
+        ]));
+        assert_eq!(weight.as_u128(), None);
+        assert_eq!(Value::from_bytes(&[7]).as_u128(), None);
+    }
+
+    #[test]
+    fn serializes_variants_and_composites() {
+        let value = Value::Variant {
+            name: "Transfer".into(),
+            fields: Composite::Named(vec![
+                ("amount".into(), Value::Primitive(Primitive::U128(10))),
+                (
+                    "memo".into(),
+                    Value::Sequence(vec![Value::Primitive(Primitive::U128(1))]),
+                ),
+            ]),
+        };
+        assert_eq!(
+            serde_json::to_value(&value).unwrap(),
+            serde_json::json!({ "Transfer": { "amount": 10, "memo": [1] } })
+        );
+
+        let unit = Value::Variant {
+            name: "None".into(),
+            fields: Composite::Unnamed(vec![]),
+        };
+        assert_eq!(
+            serde_json::to_value(&unit).unwrap(),
+            serde_json::json!("None")
+        );
+        assert_eq!(unit.to_json(), serde_json::json!("None"));
+    }
+}
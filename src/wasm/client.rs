@@ -0,0 +1,316 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::{
+    pin::Pin,
+    sync::{
+        Arc,
+        Mutex,
+    },
+    task::{
+        Context,
+        Poll,
+    },
+};
+
+use futures::{
+    channel::{
+        mpsc,
+        oneshot,
+    },
+    Stream,
+};
+use jsonrpsee::types::{
+    Error as RpcError,
+    JsonValue,
+};
+use wasm_bindgen::{
+    closure::Closure,
+    JsCast,
+};
+use web_sys::{
+    CloseEvent,
+    Event,
+    MessageEvent,
+    WebSocket,
+};
+
+use super::state::{
+    subscription_id,
+    Response,
+    State,
+};
+use crate::rpc::{
+    RawSubscription,
+    RpcClientT,
+};
+
+/// The browser objects owned by a [`WasmClient`].
+struct Socket {
+    socket: WebSocket,
+    _on_open: Closure<dyn FnMut(Event)>,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+    _on_error: Closure<dyn FnMut(Event)>,
+}
+
+// SAFETY: JavaScript objects can only be used from the thread which created them, and
+// `wasm32-unknown-unknown` without the `atomics` target feature has a single thread, so a
+// `Socket` is never actually sent to or shared with another thread.
+unsafe impl Send for Socket {}
+// SAFETY: See the `Send` implementation.
+unsafe impl Sync for Socket {}
+
+/// A JSON-RPC client using the browser `WebSocket` API.
+pub struct WasmClient {
+    socket: Socket,
+    state: Arc<Mutex<State>>,
+}
+
+impl WasmClient {
+    /// Open a connection to the given `ws://` or `wss://` URL.
+    ///
+    /// Fails if the connection is refused or closed before it is open.
+    pub async fn connect(url: &str) -> Result<Self, RpcError> {
+        let (client, opened) = Self::open(url)?;
+        match opened.await {
+            Ok(Ok(())) => Ok(client),
+            Ok(Err(err)) => {
+                Err(RpcError::Custom(format!(
+                    "Failed to connect to {}: {}",
+                    url, err
+                )))
+            }
+            Err(_) => Err(RpcError::Custom(format!("Failed to connect to {}", url))),
+        }
+    }
+
+    fn open(
+        url: &str,
+    ) -> Result<(Self, oneshot::Receiver<Result<(), RpcError>>), RpcError> {
+        let socket = WebSocket::new(url).map_err(|err| {
+            RpcError::Custom(format!("Failed to connect to {}: {:?}", url, err))
+        })?;
+        let (opened_tx, opened_rx) = oneshot::channel();
+        let state = Arc::new(Mutex::new(State::new(opened_tx)));
+
+        // The handlers are all set before the browser can report the outcome of the
+        // connection attempt, which happens once this task yields.
+        let error_state = state.clone();
+        let on_error = Closure::wrap(Box::new(move |_: Event| {
+            log::warn!("WebSocket error");
+            error_state
+                .lock()
+                .expect("lock is never poisoned; qed")
+                .error();
+        }) as Box<dyn FnMut(Event)>);
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        let close_state = state.clone();
+        let on_close = Closure::wrap(Box::new(move |_: CloseEvent| {
+            close_state
+                .lock()
+                .expect("lock is never poisoned; qed")
+                .close();
+        }) as Box<dyn FnMut(CloseEvent)>);
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        let message_state = state.clone();
+        let on_message = Closure::wrap(Box::new(move |event: MessageEvent| {
+            if let Some(text) = event.data().as_string() {
+                message_state
+                    .lock()
+                    .expect("lock is never poisoned; qed")
+                    .handle_message(&text);
+            }
+        }) as Box<dyn FnMut(MessageEvent)>);
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+
+        let open_state = state.clone();
+        let on_open = Closure::wrap(Box::new(move |_: Event| {
+            open_state
+                .lock()
+                .expect("lock is never poisoned; qed")
+                .open();
+        }) as Box<dyn FnMut(Event)>);
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+        let client = Self {
+            socket: Socket {
+                socket,
+                _on_open: on_open,
+                _on_message: on_message,
+                _on_close: on_close,
+                _on_error: on_error,
+            },
+            state,
+        };
+        Ok((client, opened_rx))
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+        subscribe: bool,
+    ) -> Result<oneshot::Receiver<Response>, RpcError> {
+        let mut state = self.state.lock().expect("lock is never poisoned; qed");
+        let id = state.next_id()?;
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        self.socket
+            .socket
+            .send_with_str(&request.to_string())
+            .map_err(|err| {
+                RpcError::Custom(format!("Failed to send request: {:?}", err))
+            })?;
+        Ok(state.add_request(id, subscribe))
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+        subscribe: bool,
+    ) -> Result<JsonValue, RpcError> {
+        let response = self.send(method, params, subscribe)?;
+        response
+            .await
+            .map_err(|_| RpcError::Custom("Connection closed".into()))?
+    }
+}
+
+impl Drop for WasmClient {
+    fn drop(&mut self) {
+        // The handlers are dropped along with the client, so the browser must not call them
+        // once it has closed the connection.
+        let socket = &self.socket.socket;
+        socket.set_onopen(None);
+        socket.set_onmessage(None);
+        socket.set_onclose(None);
+        socket.set_onerror(None);
+        let _ = socket.close();
+        self.state
+            .lock()
+            .expect("lock is never poisoned; qed")
+            .close();
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcClientT for WasmClient {
+    async fn request_raw(
+        &self,
+        method: &str,
+        params: Vec<JsonValue>,
+    ) -> Result<JsonValue, RpcError> {
+        self.request(method, params, false).await
+    }
+
+    async fn subscribe_raw(
+        &self,
+        subscribe_method: &str,
+        params: Vec<JsonValue>,
+        unsubscribe_method: &str,
+    ) -> Result<RawSubscription, RpcError> {
+        let id = subscription_id(&self.request(subscribe_method, params, true).await?);
+        let receiver = self
+            .state
+            .lock()
+            .expect("lock is never poisoned; qed")
+            .add_subscription(id.clone());
+        Ok(Box::pin(WasmSubscription {
+            receiver,
+            unsubscribe: Some(Unsubscribe {
+                socket: self.socket.socket.clone(),
+                method: unsubscribe_method.to_owned(),
+                id,
+            }),
+            state: self.state.clone(),
+        }))
+    }
+
+    /// Close the connection, failing the requests in flight, and wait for the browser to
+    /// report it closed.
+    async fn disconnect(&self) -> Result<(), RpcError> {
+        let closed = self
+            .state
+            .lock()
+            .expect("lock is never poisoned; qed")
+            .start_closing();
+        if let Some(closed) = closed {
+            let _ = self.socket.socket.close();
+            let _ = closed.await;
+        }
+        Ok(())
+    }
+}
+
+/// What is needed to unsubscribe when a [`WasmSubscription`] is dropped.
+struct Unsubscribe {
+    socket: WebSocket,
+    method: String,
+    id: String,
+}
+
+// SAFETY: See the `Send` implementation of `Socket`.
+unsafe impl Send for Unsubscribe {}
+
+struct WasmSubscription {
+    receiver: mpsc::UnboundedReceiver<Response>,
+    unsubscribe: Option<Unsubscribe>,
+    state: Arc<Mutex<State>>,
+}
+
+impl Stream for WasmSubscription {
+    type Item = Response;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+impl Drop for WasmSubscription {
+    fn drop(&mut self) {
+        let unsubscribe = match self.unsubscribe.take() {
+            Some(unsubscribe) => unsubscribe,
+            None => return,
+        };
+        let mut state = self.state.lock().expect("lock is never poisoned; qed");
+        if !state.remove_subscription(&unsubscribe.id) {
+            return
+        }
+        let id = match state.next_id() {
+            Ok(id) => id,
+            Err(_) => return,
+        };
+        // Nobody waits for the response, which is dropped when it arrives.
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": unsubscribe.method,
+            "params": [unsubscribe.id],
+        });
+        let _ = unsubscribe.socket.send_with_str(&request.to_string());
+    }
+}
@@ -0,0 +1,30 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A JSON-RPC transport for `wasm32-unknown-unknown`, speaking to a node over the browser's
+//! `WebSocket` API.
+//!
+//! Used by [`RpcClient::try_from_url`](crate::RpcClient::try_from_url) for `ws://` and
+//! `wss://` URLs when compiled to wasm, where the jsonrpsee clients are not available.
+
+#[cfg(target_arch = "wasm32")]
+mod client;
+// The routing of messages does not depend on the browser, so it is tested natively too.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+mod state;
+
+#[cfg(target_arch = "wasm32")]
+pub use self::client::WasmClient;
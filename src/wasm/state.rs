@@ -0,0 +1,346 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The bookkeeping of a [`WasmClient`](super::WasmClient): routing the messages received from
+//! the node to the requests and subscriptions awaiting them.
+
+use std::collections::HashMap;
+
+use futures::channel::{
+    mpsc,
+    oneshot,
+};
+use jsonrpsee::types::{
+    Error as RpcError,
+    JsonValue,
+};
+
+pub(super) type Response = Result<JsonValue, RpcError>;
+
+/// The maximum number of notifications buffered for a subscription before it is registered.
+const MAX_BUFFERED_NOTIFICATIONS: usize = 256;
+
+/// A request awaiting its response.
+struct Request {
+    sender: oneshot::Sender<Response>,
+    /// Whether the request creates a subscription, whose notifications may arrive before the
+    /// subscription is registered.
+    subscribe: bool,
+}
+
+/// The state of a connection.
+pub(super) struct State {
+    next_id: u64,
+    closing: bool,
+    closed: bool,
+    /// Resolved once the connection is open, or fails before it is.
+    opened: Option<oneshot::Sender<Result<(), RpcError>>>,
+    /// Resolved once the connection is closed.
+    on_closed: Vec<oneshot::Sender<()>>,
+    requests: HashMap<u64, Request>,
+    subscriptions: HashMap<String, mpsc::UnboundedSender<Response>>,
+    /// Notifications received after the response to a subscribe request, but before the
+    /// subscription was registered. Notifications of other unknown subscriptions are dropped.
+    orphans: HashMap<String, Vec<JsonValue>>,
+}
+
+impl State {
+    /// Create the state of a connection being opened, reporting the outcome to `opened`.
+    pub(super) fn new(opened: oneshot::Sender<Result<(), RpcError>>) -> Self {
+        Self {
+            next_id: 0,
+            closing: false,
+            closed: false,
+            opened: Some(opened),
+            on_closed: Vec::new(),
+            requests: HashMap::new(),
+            subscriptions: HashMap::new(),
+            orphans: HashMap::new(),
+        }
+    }
+
+    /// The connection was opened.
+    pub(super) fn open(&mut self) {
+        if let Some(opened) = self.opened.take() {
+            let _ = opened.send(Ok(()));
+        }
+    }
+
+    /// The connection reported an error, which fails the connection attempt if it is not
+    /// open yet.
+    pub(super) fn error(&mut self) {
+        if let Some(opened) = self.opened.take() {
+            let _ = opened.send(Err(RpcError::Custom("WebSocket error".into())));
+        }
+    }
+
+    /// The connection was closed, failing everything awaiting it.
+    pub(super) fn close(&mut self) {
+        self.closed = true;
+        if let Some(opened) = self.opened.take() {
+            let _ = opened.send(Err(RpcError::Custom("Connection closed".into())));
+        }
+        for (_, request) in self.requests.drain() {
+            let _ = request
+                .sender
+                .send(Err(RpcError::Custom("Connection closed".into())));
+        }
+        self.subscriptions.clear();
+        self.orphans.clear();
+        for on_closed in self.on_closed.drain(..) {
+            let _ = on_closed.send(());
+        }
+    }
+
+    /// Start closing the connection, returning a receiver resolved once it is closed, or
+    /// `None` if it already is.
+    pub(super) fn start_closing(&mut self) -> Option<oneshot::Receiver<()>> {
+        if self.closed {
+            return None
+        }
+        self.closing = true;
+        let (sender, receiver) = oneshot::channel();
+        self.on_closed.push(sender);
+        Some(receiver)
+    }
+
+    /// Allocate the id of the next request, failing if the connection is closing.
+    pub(super) fn next_id(&mut self) -> Result<u64, RpcError> {
+        if self.closing || self.closed {
+            return Err(RpcError::Custom("Connection closed".into()))
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        Ok(id)
+    }
+
+    /// Await the response to the request sent with `id`.
+    pub(super) fn add_request(
+        &mut self,
+        id: u64,
+        subscribe: bool,
+    ) -> oneshot::Receiver<Response> {
+        let (sender, receiver) = oneshot::channel();
+        self.requests.insert(id, Request { sender, subscribe });
+        receiver
+    }
+
+    /// Register the subscription with `id`, which receives the notifications buffered so far.
+    pub(super) fn add_subscription(
+        &mut self,
+        id: String,
+    ) -> mpsc::UnboundedReceiver<Response> {
+        let (sender, receiver) = mpsc::unbounded();
+        for notification in self.orphans.remove(&id).unwrap_or_default() {
+            let _ = sender.unbounded_send(Ok(notification));
+        }
+        if !self.closed {
+            self.subscriptions.insert(id, sender);
+        }
+        receiver
+    }
+
+    /// Forget the subscription with `id`, returning whether the node should be told to end it.
+    pub(super) fn remove_subscription(&mut self, id: &str) -> bool {
+        self.subscriptions.remove(id);
+        self.orphans.remove(id);
+        !self.closed
+    }
+
+    pub(super) fn handle_message(&mut self, text: &str) {
+        let message: JsonValue = match serde_json::from_str(text) {
+            Ok(message) => message,
+            Err(err) => {
+                log::warn!("Ignoring malformed JSON-RPC message: {}", err);
+                return
+            }
+        };
+        if let Some(id) = message.get("id").and_then(JsonValue::as_u64) {
+            if let Some(request) = self.requests.remove(&id) {
+                let response = match message.get("error") {
+                    Some(error) => Err(RpcError::Request(error.to_string())),
+                    None => Ok(message.get("result").cloned().unwrap_or(JsonValue::Null)),
+                };
+                let subscription = match (&response, request.subscribe) {
+                    (Ok(result), true) => Some(subscription_id(result)),
+                    _ => None,
+                };
+                if let Some(subscription) = &subscription {
+                    self.orphans.insert(subscription.clone(), Vec::new());
+                }
+                // The subscribe request was cancelled, so nobody will register the subscription.
+                if request.sender.send(response).is_err() {
+                    if let Some(subscription) = subscription {
+                        self.orphans.remove(&subscription);
+                    }
+                }
+            }
+        } else if let Some(params) = message.get("params") {
+            let id = match params.get("subscription").map(subscription_id) {
+                Some(id) => id,
+                None => return,
+            };
+            let result = params.get("result").cloned().unwrap_or(JsonValue::Null);
+            if let Some(sender) = self.subscriptions.get(&id) {
+                if sender.unbounded_send(Ok(result)).is_err() {
+                    self.subscriptions.remove(&id);
+                }
+            } else if let Some(orphans) = self.orphans.get_mut(&id) {
+                if orphans.len() < MAX_BUFFERED_NOTIFICATIONS {
+                    orphans.push(result);
+                } else {
+                    log::warn!(
+                        "Dropping notification of subscription {}: too many are buffered",
+                        id
+                    );
+                }
+            } else {
+                log::debug!("Ignoring notification of unknown subscription {}", id);
+            }
+        }
+    }
+}
+
+pub(super) fn subscription_id(id: &JsonValue) -> String {
+    match id {
+        JsonValue::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(id: u64, result: JsonValue) -> String {
+        serde_json::json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string()
+    }
+
+    fn notification(subscription: &str, result: JsonValue) -> String {
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "chain_newHead",
+            "params": { "subscription": subscription, "result": result },
+        })
+        .to_string()
+    }
+
+    fn open_state() -> State {
+        let (opened, _) = oneshot::channel();
+        let mut state = State::new(opened);
+        state.open();
+        state
+    }
+
+    #[test]
+    fn responses_resolve_their_requests() {
+        let mut state = open_state();
+        let first = state.next_id().unwrap();
+        let second = state.next_id().unwrap();
+        let mut first_response = state.add_request(first, false);
+        let mut second_response = state.add_request(second, false);
+
+        state.handle_message(&response(second, 2u64.into()));
+        assert!(first_response.try_recv().unwrap().is_none());
+        assert_eq!(
+            second_response.try_recv().unwrap().unwrap().unwrap(),
+            JsonValue::from(2u64)
+        );
+    }
+
+    #[test]
+    fn notifications_before_registration_are_buffered() {
+        let mut state = open_state();
+        let id = state.next_id().unwrap();
+        let mut subscribed = state.add_request(id, true);
+        state.handle_message(&response(id, "sub".into()));
+        state.handle_message(&notification("sub", 1u64.into()));
+
+        let subscription =
+            subscription_id(&subscribed.try_recv().unwrap().unwrap().unwrap());
+        let mut notifications = state.add_subscription(subscription);
+        state.handle_message(&notification("sub", 2u64.into()));
+        for expected in [1u64, 2] {
+            assert_eq!(
+                notifications.try_next().unwrap().unwrap().unwrap(),
+                JsonValue::from(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn notifications_of_unknown_subscriptions_are_dropped() {
+        let mut state = open_state();
+        for n in 0..10u64 {
+            state.handle_message(&notification("unknown", n.into()));
+        }
+        assert!(state.orphans.is_empty());
+
+        // Nobody awaits this subscription, so its notifications are not buffered either.
+        let id = state.next_id().unwrap();
+        drop(state.add_request(id, true));
+        state.handle_message(&response(id, "cancelled".into()));
+        state.handle_message(&notification("cancelled", 1u64.into()));
+        assert!(state.orphans.is_empty());
+    }
+
+    #[test]
+    fn buffered_notifications_are_bounded() {
+        let mut state = open_state();
+        let id = state.next_id().unwrap();
+        let _subscribed = state.add_request(id, true);
+        state.handle_message(&response(id, "sub".into()));
+        for n in 0..MAX_BUFFERED_NOTIFICATIONS + 10 {
+            state.handle_message(&notification("sub", n.into()));
+        }
+
+        let mut notifications = state.add_subscription("sub".into());
+        let mut buffered = 0;
+        while let Ok(Some(_)) = notifications.try_next() {
+            buffered += 1;
+        }
+        assert_eq!(buffered, MAX_BUFFERED_NOTIFICATIONS);
+    }
+
+    #[test]
+    fn connection_attempts_fail_on_error_or_close() {
+        let (opened, mut error_outcome) = oneshot::channel();
+        let mut state = State::new(opened);
+        state.error();
+        // Browsers report a closed connection after the error.
+        state.close();
+        assert!(error_outcome.try_recv().unwrap().unwrap().is_err());
+
+        let (opened, mut close_outcome) = oneshot::channel();
+        State::new(opened).close();
+        assert!(close_outcome.try_recv().unwrap().unwrap().is_err());
+    }
+
+    #[test]
+    fn closing_fails_requests_and_resolves_waiters() {
+        let mut state = open_state();
+        let id = state.next_id().unwrap();
+        let mut response = state.add_request(id, false);
+        let mut closed = state.start_closing().unwrap();
+        assert!(state.next_id().is_err());
+        assert!(closed.try_recv().unwrap().is_none());
+
+        state.close();
+        assert!(response.try_recv().unwrap().unwrap().is_err());
+        assert!(closed.try_recv().unwrap().is_some());
+        assert!(state.start_closing().is_none());
+    }
+}
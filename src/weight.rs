@@ -0,0 +1,317 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A weight type covering both the scalar weights of legacy runtimes and the two dimensional
+//! `{ ref_time, proof_size }` weights of newer runtimes.
+
+use jsonrpsee::types::JsonValue;
+use scale_info::TypeDef;
+use serde::{
+    Deserialize,
+    Serialize,
+};
+
+use crate::{
+    value::{
+        Composite,
+        Value,
+    },
+    Error,
+    Metadata,
+    MetadataError,
+};
+
+/// The weight of a dispatch.
+///
+/// On runtimes with scalar weights only [`Weight::ref_time`] is used, and `proof_size` is
+/// always zero.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize)]
+pub struct Weight {
+    /// The computation time, in picoseconds.
+    pub ref_time: u64,
+    /// The size of the storage proof, in bytes.
+    pub proof_size: u64,
+}
+
+impl Weight {
+    /// Create a weight of the given computation time, and no proof size.
+    pub const fn from_ref_time(ref_time: u64) -> Self {
+        Self {
+            ref_time,
+            proof_size: 0,
+        }
+    }
+
+    /// Create a weight from both of its dimensions.
+    pub const fn from_parts(ref_time: u64, proof_size: u64) -> Self {
+        Self {
+            ref_time,
+            proof_size,
+        }
+    }
+
+    /// Add the dimensions of `other`, saturating at the numeric bounds.
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self {
+            ref_time: self.ref_time.saturating_add(other.ref_time),
+            proof_size: self.proof_size.saturating_add(other.proof_size),
+        }
+    }
+
+    /// Subtract the dimensions of `other`, saturating at zero.
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self {
+            ref_time: self.ref_time.saturating_sub(other.ref_time),
+            proof_size: self.proof_size.saturating_sub(other.proof_size),
+        }
+    }
+
+    /// Returns `true` if every dimension is less than or equal to that of `other`.
+    pub fn all_lte(self, other: Self) -> bool {
+        self.ref_time <= other.ref_time && self.proof_size <= other.proof_size
+    }
+
+    /// Represent the weight as JSON in the given shape, e.g. for RPC parameters.
+    pub fn to_json(&self, shape: WeightShape) -> JsonValue {
+        match shape {
+            WeightShape::Scalar => self.ref_time.into(),
+            WeightShape::V2 => {
+                serde_json::json!({
+                    "ref_time": self.ref_time,
+                    "proof_size": self.proof_size,
+                })
+            }
+        }
+    }
+
+    /// Encode the weight as the type with the given id in the metadata type registry, which
+    /// may be either a scalar or a struct of the weight dimensions.
+    pub fn encode_as(
+        &self,
+        metadata: &Metadata,
+        type_id: u32,
+        output: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let ty = metadata
+            .resolve_type(type_id)
+            .ok_or(MetadataError::TypeNotFound(type_id))?;
+        let value = if WeightShape::of_type_def(ty.type_def()) == WeightShape::V2 {
+            Value::Composite(Composite::Named(vec![
+                ("ref_time".into(), Value::u128(self.ref_time.into())),
+                ("proof_size".into(), Value::u128(self.proof_size.into())),
+            ]))
+        } else {
+            Value::u128(self.ref_time.into())
+        };
+        value.encode_as(metadata, type_id, output)
+    }
+
+    /// Read a weight from a value decoded using the metadata type registry, which may be
+    /// either a scalar or a struct of the weight dimensions.
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let uint = |value: &Value| u64::try_from(value.as_u128()?).ok();
+        match value {
+            Value::Composite(composite) => {
+                let ref_time = uint(composite.field("ref_time")?)?;
+                let proof_size = match composite.field("proof_size") {
+                    Some(proof_size) => uint(proof_size)?,
+                    None => 0,
+                };
+                Some(Self::from_parts(ref_time, proof_size))
+            }
+            value => uint(value).map(Self::from_ref_time),
+        }
+    }
+}
+
+/// The resources consumed by a block, see
+/// [`Client::block_resources`](crate::Client::block_resources).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BlockResources {
+    /// The weight consumed by normal dispatches.
+    pub normal: Weight,
+    /// The weight consumed by operational dispatches.
+    pub operational: Weight,
+    /// The weight consumed by mandatory dispatches, including block initialization.
+    pub mandatory: Weight,
+    /// The total encoded length of the extrinsics of the block, in bytes.
+    pub length: u64,
+}
+
+impl BlockResources {
+    /// Read the weights from the value of the `System::BlockWeight` storage entry, decoded
+    /// using the metadata type registry.
+    pub fn from_block_weight(block_weight: &Value, length: u64) -> Result<Self, Error> {
+        let class = |name: &str| {
+            let weight = match block_weight {
+                Value::Composite(composite) => {
+                    composite.field(name).and_then(Weight::from_value)
+                }
+                _ => None,
+            };
+            weight.ok_or_else(|| {
+                Error::Other(format!("BlockWeight has no {} weight", name))
+            })
+        };
+        Ok(Self {
+            normal: class("normal")?,
+            operational: class("operational")?,
+            mandatory: class("mandatory")?,
+            length,
+        })
+    }
+
+    /// The weight consumed by dispatches of all classes.
+    pub fn total_weight(&self) -> Weight {
+        self.normal
+            .saturating_add(self.operational)
+            .saturating_add(self.mandatory)
+    }
+}
+
+/// Weights are returned by the RPCs of legacy runtimes as a number, and otherwise as a
+/// struct whose field names depend on the RPC.
+impl<'de> Deserialize<'de> for Weight {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Scalar(u64),
+            V2 {
+                #[serde(alias = "refTime")]
+                ref_time: u64,
+                #[serde(alias = "proofSize", default)]
+                proof_size: u64,
+            },
+        }
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Scalar(ref_time) => Weight::from_ref_time(ref_time),
+            Repr::V2 {
+                ref_time,
+                proof_size,
+            } => Weight::from_parts(ref_time, proof_size),
+        })
+    }
+}
+
+/// The shape of the weight type of a runtime.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WeightShape {
+    /// A single `u64`.
+    Scalar,
+    /// A struct of `ref_time` and `proof_size`.
+    V2,
+}
+
+impl WeightShape {
+    /// Detect the weight shape of a runtime, by looking for a `Weight` struct in its type
+    /// registry.
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        let is_v2 = metadata
+            .runtime_metadata()
+            .types
+            .types()
+            .iter()
+            .map(|ty| ty.ty())
+            .any(|ty| {
+                ty.path().segments().last().map(String::as_str) == Some("Weight")
+                    && Self::of_type_def(ty.type_def()) == WeightShape::V2
+            });
+        if is_v2 {
+            WeightShape::V2
+        } else {
+            WeightShape::Scalar
+        }
+    }
+
+    fn of_type_def(type_def: &TypeDef<scale_info::form::PortableForm>) -> Self {
+        match type_def {
+            TypeDef::Composite(composite)
+                if composite.fields().iter().any(|field| {
+                    field.name().map(String::as_str) == Some("ref_time")
+                }) =>
+            {
+                WeightShape::V2
+            }
+            _ => WeightShape::Scalar,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_scalar_and_v2_weights() {
+        let scalar: Weight = serde_json::from_str("125000000").unwrap();
+        assert_eq!(scalar, Weight::from_ref_time(125_000_000));
+
+        let v2: Weight =
+            serde_json::from_str(r#"{ "ref_time": 10, "proof_size": 20 }"#).unwrap();
+        assert_eq!(v2, Weight::from_parts(10, 20));
+
+        let camel_case: Weight =
+            serde_json::from_str(r#"{ "refTime": 10, "proofSize": 20 }"#).unwrap();
+        assert_eq!(camel_case, v2);
+    }
+
+    #[test]
+    fn reads_block_weight_of_either_shape() {
+        let v2 = |ref_time: u128, proof_size: u128| {
+            Value::Composite(Composite::Named(vec![
+                ("ref_time".into(), Value::u128(ref_time)),
+                ("proof_size".into(), Value::u128(proof_size)),
+            ]))
+        };
+        let block_weight = Value::Composite(Composite::Named(vec![
+            ("normal".into(), v2(10, 1)),
+            ("operational".into(), v2(20, 2)),
+            ("mandatory".into(), v2(30, 3)),
+        ]));
+        let resources = BlockResources::from_block_weight(&block_weight, 100).unwrap();
+        assert_eq!(resources.operational, Weight::from_parts(20, 2));
+        assert_eq!(resources.total_weight(), Weight::from_parts(60, 6));
+        assert_eq!(resources.length, 100);
+
+        let scalar = Value::Composite(Composite::Named(vec![
+            ("normal".into(), Value::u128(10)),
+            ("operational".into(), Value::u128(20)),
+            ("mandatory".into(), Value::u128(30)),
+        ]));
+        let resources = BlockResources::from_block_weight(&scalar, 0).unwrap();
+        assert_eq!(resources.mandatory, Weight::from_ref_time(30));
+    }
+
+    #[test]
+    fn rejects_weights_out_of_range() {
+        let too_large = Value::u128(u128::from(u64::MAX) + 1);
+        assert_eq!(Weight::from_value(&too_large), None);
+        assert_eq!(
+            Weight::from_value(&Value::u128(u64::MAX.into())),
+            Some(Weight::from_ref_time(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn compares_all_dimensions() {
+        let limit = Weight::from_parts(10, 10);
+        assert!(Weight::from_parts(10, 5).all_lte(limit));
+        assert!(!Weight::from_parts(5, 11).all_lte(limit));
+    }
+}
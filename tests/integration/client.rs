@@ -17,14 +17,44 @@
 use crate::{
     test_node_process,
     test_node_process_with,
-    utils::node_runtime::system,
+    utils::node_runtime::{
+        self,
+        system,
+        DefaultConfig,
+    },
 };
 
+use codec::Encode;
 use sp_core::storage::{
     well_known_keys,
     StorageKey,
 };
 use sp_keyring::AccountKeyring;
+use sp_runtime::traits::Header as _;
+use std::{
+    sync::Arc,
+    time::Duration,
+};
+use subxt::{
+    governance::GovernancePallet,
+    intercept::{
+        InterceptedExtrinsic,
+        Verdict,
+    },
+    value::{
+        Composite,
+        Value,
+    },
+    BestBlock,
+    ClientBuilder,
+    DefaultExtraParams,
+    Error,
+    MetadataCache,
+    PairSigner,
+    ReadAt,
+    Signer as _,
+    WellKnownKey,
+};
 
 #[async_std::test]
 async fn insert_key() {
@@ -47,6 +77,40 @@ async fn insert_key() {
         .unwrap());
 }
 
+#[test]
+fn pair_signer_from_phrase_and_uri() {
+    type Sr25519Signer = PairSigner<DefaultConfig, sp_core::sr25519::Pair>;
+    let alice = AccountKeyring::Alice.to_account_id();
+
+    let signer = Sr25519Signer::from_uri("//Alice", None).unwrap();
+    assert_eq!(signer.account_id(), &alice);
+    let uri = format!("{}//Alice", sp_core::crypto::DEV_PHRASE);
+    let signer = Sr25519Signer::from_uri(&uri, None).unwrap();
+    assert_eq!(signer.account_id(), &alice);
+
+    let root = Sr25519Signer::from_phrase(sp_core::crypto::DEV_PHRASE, None).unwrap();
+    assert_ne!(root.account_id(), &alice);
+    let root_with_password =
+        Sr25519Signer::from_phrase(sp_core::crypto::DEV_PHRASE, Some("secret")).unwrap();
+    assert_ne!(root_with_password.account_id(), root.account_id());
+
+    let bad_phrase = Sr25519Signer::from_phrase("not a valid mnemonic", None);
+    assert!(matches!(bad_phrase, Err(Error::SecretString(_))));
+}
+
+#[test]
+fn pair_signer_with_account() {
+    type Sr25519Signer = PairSigner<DefaultConfig, sp_core::sr25519::Pair>;
+    let bob = AccountKeyring::Bob.to_account_id();
+
+    let signer = Sr25519Signer::with_account(AccountKeyring::Alice.pair(), bob.clone());
+    assert_eq!(signer.account_id(), &bob);
+    assert_eq!(
+        sp_core::Pair::public(signer.signer()),
+        AccountKeyring::Alice.public()
+    );
+}
+
 #[async_std::test]
 async fn fetch_block_hash() {
     let node_process = test_node_process().await;
@@ -79,6 +143,93 @@ async fn fetch_read_proof() {
         .unwrap();
 }
 
+#[async_std::test]
+async fn verify_read_proof() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let block_hash = client.rpc().block_hash(None).await.unwrap().unwrap();
+    let header = client
+        .rpc()
+        .header(Some(block_hash))
+        .await
+        .unwrap()
+        .unwrap();
+    let key = StorageKey(well_known_keys::CODE.to_vec());
+    let proof = client
+        .rpc()
+        .read_proof(vec![key.clone()], Some(block_hash))
+        .await
+        .unwrap();
+
+    let proven =
+        subxt::storage::verify_proof::<DefaultConfig>(header.state_root(), &proof, &key)
+            .unwrap();
+    let expected = client
+        .storage()
+        .fetch_raw(key, Some(block_hash))
+        .await
+        .unwrap();
+    assert_eq!(proven, expected);
+}
+
+#[async_std::test]
+async fn fetch_well_known_keys() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let block_hash = client.rpc().block_hash(None).await.unwrap();
+
+    let code = client
+        .storage()
+        .fetch_well_known(WellKnownKey::Code, block_hash)
+        .await
+        .unwrap()
+        .expect("The runtime code is always set");
+    let expected = client
+        .storage()
+        .fetch_raw(StorageKey(well_known_keys::CODE.to_vec()), block_hash)
+        .await
+        .unwrap();
+    assert_eq!(Some(code), expected);
+}
+
+#[async_std::test]
+async fn runtime_api_call() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let version: sp_version::RuntimeVersion = client
+        .runtime_api_call("Core_version", (), None)
+        .await
+        .unwrap();
+    let expected = client.rpc().runtime_version(None).await.unwrap();
+    assert_eq!(version.spec_version, expected.spec_version);
+}
+
+#[async_std::test]
+async fn extrinsic_inclusion_proof_verifies() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let block_hash = client.rpc().finalized_head().await.unwrap();
+    let header = client
+        .rpc()
+        .header(Some(block_hash))
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The timestamp inherent is the first extrinsic of every block.
+    let proof = client
+        .extrinsic_inclusion_proof(block_hash, 0)
+        .await
+        .unwrap();
+    assert_eq!(&proof.extrinsics_root, header.extrinsics_root());
+    proof.verify().unwrap();
+
+    assert!(client
+        .extrinsic_inclusion_proof(block_hash, 100)
+        .await
+        .is_err());
+}
+
 #[async_std::test]
 async fn chain_subscribe_blocks() {
     let node_process = test_node_process().await;
@@ -95,6 +246,305 @@ async fn chain_subscribe_finalized_blocks() {
     blocks.next().await.unwrap();
 }
 
+#[async_std::test]
+async fn wait_for_block_returns_the_finalized_block_hash() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+
+    let hash = client.wait_for_block(3).await.unwrap();
+    let expected = client.rpc().block_hash(Some(3u32.into())).await.unwrap();
+    assert_eq!(expected, Some(hash));
+    let finalized = client.rpc().finalized_head().await.unwrap();
+    let finalized = client.rpc().header(Some(finalized)).await.unwrap().unwrap();
+    assert!(*finalized.number() >= 3);
+
+    // Blocks which are already finalized are returned right away.
+    let hash = client.wait_for_block(1).await.unwrap();
+    let expected = client.rpc().block_hash(Some(1u32.into())).await.unwrap();
+    assert_eq!(expected, Some(hash));
+}
+
+#[async_std::test]
+async fn tracked_finalized_head_follows_the_chain() {
+    let node_process = test_node_process().await;
+    let rpc = node_process.client().rpc();
+    let client = ClientBuilder::new()
+        .set_client(rpc.client.clone())
+        .set_track_finalized_head(true)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+
+    let first = client.finalized_head().await.unwrap();
+    let first_number = *rpc.header(Some(first)).await.unwrap().unwrap().number();
+
+    // Wait for a later block to be finalized.
+    node_process
+        .client()
+        .wait_for_block(first_number + 1)
+        .await
+        .unwrap();
+
+    let latest = client.finalized_head().await.unwrap();
+    let latest_number = *rpc.header(Some(latest)).await.unwrap().unwrap().number();
+    assert!(latest_number > first_number);
+}
+
+#[async_std::test]
+async fn submit_interceptor_rejects_extrinsics() {
+    let node_process = test_node_process().await;
+    let client = ClientBuilder::new()
+        .set_client(node_process.client().rpc().client.clone())
+        .set_submit_interceptor(|extrinsic: &InterceptedExtrinsic| {
+            match extrinsic.call {
+                Some(call) if call.pallet == "Balances" => {
+                    Verdict::Reject(format!("{} is not allowed", call.name))
+                }
+                _ => Verdict::Allow,
+            }
+        })
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+
+    let dest = Value::Variant {
+        name: "Id".into(),
+        fields: Composite::Unnamed(vec![Value::from_bytes(
+            AccountKeyring::Bob.to_account_id().as_ref(),
+        )]),
+    };
+    let rejected = client
+        .submit_dynamic(
+            "Balances",
+            "transfer",
+            vec![dest, Value::u128(10_000)],
+            &alice,
+        )
+        .await;
+    assert!(matches!(
+        rejected,
+        Err(Error::SubmissionRejected(reason)) if reason == "transfer is not allowed"
+    ));
+
+    client
+        .remark_with_event(b"allowed".to_vec(), &alice)
+        .await
+        .unwrap();
+}
+
+#[async_std::test]
+async fn subscribe_best_with_reorgs_follows_the_chain() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let mut sub = client.subscribe_best_with_reorgs().await.unwrap();
+
+    // A single dev node does not fork, so each block extends the previous one.
+    let mut hashes = Vec::new();
+    while hashes.len() < 3 {
+        match sub.next().await.unwrap().unwrap() {
+            BestBlock::NewBlock(events) => hashes.push(events.block_hash()),
+            BestBlock::Retracted(hash) => panic!("Unexpected reorg of {:?}", hash),
+        }
+    }
+    for pair in hashes.windows(2) {
+        let header = client.rpc().header(Some(pair[1])).await.unwrap().unwrap();
+        assert_eq!(header.parent_hash(), &pair[0]);
+    }
+}
+
+#[async_std::test]
+async fn genesis_hash_override_is_used_for_signing() {
+    let node_process = test_node_process().await;
+    let genesis = sp_core::H256::repeat_byte(1);
+    let client = ClientBuilder::new()
+        .set_client(node_process.client().rpc().client.clone())
+        .set_genesis_hash(genesis)
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    assert_eq!(client.genesis(), &genesis);
+
+    // The node rejects the signature, which covers the wrong genesis hash.
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let result = client.remark_with_event(b"forked".to_vec(), &alice).await;
+    assert!(result.is_err());
+}
+
+#[async_std::test]
+async fn metadata_cache_is_shared_between_clients() {
+    let node_process = test_node_process().await;
+    let rpc_client = node_process.client().rpc().client.clone();
+    let cache = MetadataCache::new();
+    let first = ClientBuilder::new()
+        .set_client(rpc_client.clone())
+        .set_metadata_cache(cache.clone())
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    assert_eq!(cache.len(), 1);
+
+    let second = ClientBuilder::new()
+        .set_client(rpc_client)
+        .set_metadata_cache(cache.clone())
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    let metadata = first.metadata().unwrap();
+    assert!(std::ptr::eq(metadata, second.metadata().unwrap()));
+
+    // A runtime upgrade evicts the metadata of the previous runtime.
+    let genesis = first.genesis().encode();
+    let spec_version = first.runtime_version(None).await.unwrap().spec_version;
+    let cached = cache.get(&genesis, spec_version).unwrap();
+    cache.insert(&genesis, spec_version + 1, cached);
+    assert!(cache.get(&genesis, spec_version).is_none());
+    assert_eq!(cache.len(), 1);
+}
+
+#[async_std::test]
+async fn read_at_sets_the_default_block_of_reads() {
+    let node_process = test_node_process().await;
+    let online = node_process.client();
+    let genesis = *online.genesis();
+    let client = ClientBuilder::new()
+        .set_client(online.rpc().client.clone())
+        .set_read_at(ReadAt::At(genesis))
+        .build::<DefaultConfig>()
+        .await
+        .unwrap();
+    assert_eq!(client.read_at(), ReadAt::At(genesis));
+
+    let number = client
+        .storage()
+        .fetch_dynamic("System", "Number", vec![], None)
+        .await
+        .unwrap();
+    assert_eq!(number, Some(Value::u128(0)));
+    let events = client.events().latest().await.unwrap();
+    assert_eq!(events.block_hash(), genesis);
+
+    // A block hash passed to a read overrides the default.
+    let best = online.rpc().block_hash(None).await.unwrap().unwrap();
+    let number = client
+        .storage()
+        .fetch_dynamic("System", "Number", vec![], Some(best))
+        .await
+        .unwrap();
+    let expected = online
+        .storage()
+        .fetch_dynamic("System", "Number", vec![], Some(best))
+        .await
+        .unwrap();
+    assert_eq!(number, expected);
+}
+
+#[async_std::test]
+async fn offline_client_signs_extrinsics() {
+    let node_process = test_node_process().await;
+    let online = node_process.client();
+    let offline = ClientBuilder::new().build_offline::<DefaultConfig>(
+        online.metadata().unwrap().clone(),
+        *online.genesis(),
+        online.runtime_version(None).await.unwrap(),
+    );
+
+    let err = offline.rpc().finalized_head().await.unwrap_err();
+    assert!(
+        err.to_string().contains("chain_getFinalizedHead"),
+        "{}",
+        err
+    );
+
+    // Extrinsics signed offline are accepted by the node.
+    let mut alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    alice.set_nonce(0);
+    let api = offline.to_runtime_api::<node_runtime::RuntimeApi<DefaultConfig>>();
+    let extrinsic = api
+        .tx()
+        .system()
+        .remark(b"signed offline".to_vec())
+        .create_signed(&alice, Default::default())
+        .await
+        .unwrap();
+    online.rpc().submit_extrinsic(&extrinsic).await.unwrap();
+
+    // The nonce and tip can be set in the parameters, so that the account is not looked up.
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let params = DefaultExtraParams::default().nonce(1).tip(1_000);
+    let extrinsic = api
+        .tx()
+        .system()
+        .remark(b"tipped offline".to_vec())
+        .create_signed(&alice, params)
+        .await
+        .unwrap();
+    online.rpc().submit_extrinsic(&extrinsic).await.unwrap();
+}
+
+#[async_std::test]
+async fn disconnect_ends_subscriptions() {
+    let node_process = test_node_process().await;
+    let client = node_process.client().clone();
+    let mut blocks = client.rpc().subscribe_blocks().await.unwrap();
+    let rpc = client.rpc().clone();
+
+    let disconnected = async {
+        client.disconnect().await.unwrap();
+
+        assert!(rpc.finalized_head().await.is_err());
+        while let Ok(Some(_)) = blocks.next().await {}
+    };
+    async_std::future::timeout(Duration::from_secs(10), disconnected)
+        .await
+        .expect("disconnecting ends the subscriptions");
+}
+
+#[async_std::test]
+async fn metadata_provider_caches_metadata_per_runtime() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let provider = client.metadata_provider();
+    let spec_version = client.runtime_version(None).await.unwrap().spec_version;
+    assert_eq!(provider.len(), 1);
+
+    // The test node is never upgraded, so genesis runs the runtime the client was built with.
+    let genesis = *client.genesis();
+    let metadata = provider.at(genesis).await.unwrap();
+    assert!(Arc::ptr_eq(&metadata, &provider.get(spec_version).unwrap()));
+    assert_eq!(provider.len(), 1);
+
+    let events = client.events().at(genesis).await.unwrap();
+    assert!(events.metadata().pallet("System").is_ok());
+    let value = client
+        .storage()
+        .fetch_dynamic("System", "Number", vec![], Some(genesis))
+        .await
+        .unwrap();
+    assert!(value.is_some());
+}
+
+#[async_std::test]
+async fn block_resources_include_the_inherents() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let hash = client.wait_for_block(1).await.unwrap();
+    let resources = client.block_resources(Some(hash)).await.unwrap();
+    // Every block holds at least the timestamp inherent, which is mandatory.
+    assert!(resources.mandatory.ref_time > 0);
+    assert!(resources.length > 0);
+}
+
+#[async_std::test]
+async fn is_finalized_checks_the_finalized_chain() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let hash = client.wait_for_block(2).await.unwrap();
+    assert!(client.is_finalized(hash).await.unwrap());
+    assert!(client.is_finalized(*client.genesis()).await.unwrap());
+    assert!(!client.is_finalized(Default::default()).await.unwrap());
+}
+
 #[async_std::test]
 async fn fetch_keys() {
     let node_process = test_node_process().await;
@@ -107,6 +557,32 @@ async fn fetch_keys() {
     assert_eq!(keys.len(), 4)
 }
 
+#[async_std::test]
+async fn key_pager_fetches_all_keys() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let storage = client.storage();
+    let mut pager = storage
+        .key_pager::<system::storage::Account>(4, None)
+        .await
+        .unwrap();
+    let mut keys = Vec::new();
+    while !pager.is_exhausted() {
+        keys.extend(pager.next_page().await.unwrap());
+    }
+    assert_eq!(keys.len(), 13);
+    assert_eq!(pager.cursor(), keys.last());
+    assert!(pager.next_page().await.unwrap().is_empty());
+
+    // Resuming from a checkpoint continues after it.
+    let mut resumed = storage
+        .key_pager::<system::storage::Account>(100, Some(pager.block_hash()))
+        .await
+        .unwrap()
+        .with_cursor(Some(keys[3].clone()));
+    assert_eq!(resumed.next_page().await.unwrap(), keys[4..].to_vec());
+}
+
 #[async_std::test]
 async fn test_iter() {
     let node_process = test_node_process().await;
@@ -122,3 +598,30 @@ async fn test_iter() {
     }
     assert_eq!(i, 13);
 }
+
+#[async_std::test]
+async fn test_iter_into_stream() {
+    use futures::TryStreamExt;
+
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    let entries: Vec<_> = client
+        .storage()
+        .iter::<system::storage::Account>(None)
+        .await
+        .unwrap()
+        .with_page_size(3)
+        .into_stream()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 13);
+}
+
+#[async_std::test]
+async fn referendum_info_of_unknown_referendum_is_none() {
+    let node_process = test_node_process().await;
+    let client = node_process.client();
+    assert!(GovernancePallet::detect(client.metadata().unwrap()).is_some());
+    assert_eq!(client.referendum_info(u32::MAX, None).await.unwrap(), None);
+}
@@ -1,58 +1,611 @@
 #[allow(dead_code, unused_imports, non_camel_case_types)]
 pub mod api {
-    #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
+    #[doc = "The Event enums of all pallets.\n\nA pallet or variant which is not in the metadata the code was generated from decodes into [`Event::Other`] rather than failing. Since the length of its fields is unknown, `Other` takes the rest of the input, so decode from the bytes of exactly one `Event`."]
     pub enum Event {
-        #[codec(index = 0)]
         System(system::Event),
-        #[codec(index = 3)]
         Indices(indices::Event),
-        #[codec(index = 4)]
         Balances(balances::Event),
-        #[codec(index = 7)]
         Offences(offences::Event),
-        #[codec(index = 9)]
         Session(session::Event),
-        #[codec(index = 10)]
         Grandpa(grandpa::Event),
-        #[codec(index = 11)]
         ImOnline(im_online::Event),
-        #[codec(index = 16)]
         ParaInclusion(para_inclusion::Event),
-        #[codec(index = 19)]
         Paras(paras::Event),
-        #[codec(index = 22)]
         Ump(ump::Event),
-        #[codec(index = 23)]
         Hrmp(hrmp::Event),
-        #[codec(index = 25)]
         ParasDisputes(paras_disputes::Event),
-        #[codec(index = 26)]
         Registrar(registrar::Event),
-        #[codec(index = 27)]
         Auctions(auctions::Event),
-        #[codec(index = 28)]
         Crowdloan(crowdloan::Event),
-        #[codec(index = 29)]
         Slots(slots::Event),
-        #[codec(index = 31)]
         Sudo(sudo::Event),
-        #[codec(index = 35)]
         ValidatorManager(validator_manager::Event),
-        #[codec(index = 80)]
         Collective(collective::Event),
-        #[codec(index = 81)]
         Membership(membership::Event),
-        #[codec(index = 90)]
         Utility(utility::Event),
-        #[codec(index = 91)]
         Proxy(proxy::Event),
-        #[codec(index = 92)]
         Multisig(multisig::Event),
-        #[codec(index = 99)]
         XcmPallet(xcm_pallet::Event),
+        #[doc = r" A pallet or variant unknown when this code was generated: the pallet index,"]
+        #[doc = r" the variant index and the encoded fields."]
+        Other(u8, u8, ::std::vec::Vec<u8>),
+    }
+    impl ::subxt::codec::Encode for Event {
+        fn encode_to<O: ::subxt::codec::Output + ?Sized>(&self, dest: &mut O) {
+            match self {
+                Self::System(inner) => {
+                    dest.push_byte(0);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Indices(inner) => {
+                    dest.push_byte(3);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Balances(inner) => {
+                    dest.push_byte(4);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Offences(inner) => {
+                    dest.push_byte(7);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Session(inner) => {
+                    dest.push_byte(9);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Grandpa(inner) => {
+                    dest.push_byte(10);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ImOnline(inner) => {
+                    dest.push_byte(11);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ParaInclusion(inner) => {
+                    dest.push_byte(16);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Paras(inner) => {
+                    dest.push_byte(19);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Ump(inner) => {
+                    dest.push_byte(22);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Hrmp(inner) => {
+                    dest.push_byte(23);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ParasDisputes(inner) => {
+                    dest.push_byte(25);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Registrar(inner) => {
+                    dest.push_byte(26);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Auctions(inner) => {
+                    dest.push_byte(27);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Crowdloan(inner) => {
+                    dest.push_byte(28);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Slots(inner) => {
+                    dest.push_byte(29);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Sudo(inner) => {
+                    dest.push_byte(31);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ValidatorManager(inner) => {
+                    dest.push_byte(35);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Collective(inner) => {
+                    dest.push_byte(80);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Membership(inner) => {
+                    dest.push_byte(81);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Utility(inner) => {
+                    dest.push_byte(90);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Proxy(inner) => {
+                    dest.push_byte(91);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Multisig(inner) => {
+                    dest.push_byte(92);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::XcmPallet(inner) => {
+                    dest.push_byte(99);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Other(pallet_index, variant_index, fields) => {
+                    dest.push_byte(*pallet_index);
+                    dest.push_byte(*variant_index);
+                    dest.write(fields)
+                }
+            }
+        }
+    }
+    impl ::subxt::codec::Decode for Event {
+        fn decode<I: ::subxt::codec::Input>(
+            input: &mut I,
+        ) -> ::core::result::Result<Self, ::subxt::codec::Error> {
+            let pallet_index = input.read_byte()?;
+            let variant_index = input.read_byte()?;
+            match (pallet_index, variant_index) {
+                (0, 0 | 1 | 2 | 3 | 4 | 5) => {
+                    ::core::result::Result::Ok(Self::System(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (3, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Indices(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (4, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7) => {
+                    ::core::result::Result::Ok(Self::Balances(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (7, 0) => {
+                    ::core::result::Result::Ok(Self::Offences(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (9, 0) => {
+                    ::core::result::Result::Ok(Self::Session(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (10, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Grandpa(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (11, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::ImOnline(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (16, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::ParaInclusion(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (19, 0 | 1 | 2 | 3 | 4) => {
+                    ::core::result::Result::Ok(Self::Paras(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (22, 0 | 1 | 2 | 3 | 4 | 5 | 6) => {
+                    ::core::result::Result::Ok(Self::Ump(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (23, 0 | 1 | 2 | 3) => {
+                    ::core::result::Result::Ok(Self::Hrmp(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (25, 0 | 1 | 2 | 3) => {
+                    ::core::result::Result::Ok(Self::ParasDisputes(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (26, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Registrar(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (27, 0 | 1 | 2 | 3 | 4 | 5 | 6) => {
+                    ::core::result::Result::Ok(Self::Auctions(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (28, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9) => {
+                    ::core::result::Result::Ok(Self::Crowdloan(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (29, 0 | 1) => {
+                    ::core::result::Result::Ok(Self::Slots(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (31, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Sudo(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (35, 0 | 1) => {
+                    ::core::result::Result::Ok(Self::ValidatorManager(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (80, 0 | 1 | 2 | 3 | 4 | 5 | 6) => {
+                    ::core::result::Result::Ok(Self::Collective(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (81, 0 | 1 | 2 | 3 | 4 | 5) => {
+                    ::core::result::Result::Ok(Self::Membership(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (90, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Utility(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (91, 0 | 1 | 2 | 3) => {
+                    ::core::result::Result::Ok(Self::Proxy(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (92, 0 | 1 | 2 | 3) => {
+                    ::core::result::Result::Ok(Self::Multisig(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (99, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 | 13 | 14 | 15) => {
+                    ::core::result::Result::Ok(Self::XcmPallet(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                _ => {
+                    ::core::result::Result::Ok(Self::Other(
+                        pallet_index,
+                        variant_index,
+                        ::subxt::outer_enum::decode_unknown(input)?,
+                    ))
+                }
+            }
+        }
+    }
+    #[doc = "The Call enums of all pallets.\n\nA pallet or variant which is not in the metadata the code was generated from decodes into [`Call::Other`] rather than failing. Since the length of its fields is unknown, `Other` takes the rest of the input, so decode from the bytes of exactly one `Call`."]
+    pub enum Call {
+        System(system::Call),
+        Babe(babe::Call),
+        Timestamp(timestamp::Call),
+        Indices(indices::Call),
+        Balances(balances::Call),
+        Authorship(authorship::Call),
+        Session(session::Call),
+        Grandpa(grandpa::Call),
+        ImOnline(im_online::Call),
+        Configuration(configuration::Call),
+        ParasShared(paras_shared::Call),
+        ParaInclusion(para_inclusion::Call),
+        ParaInherent(para_inherent::Call),
+        Paras(paras::Call),
+        Initializer(initializer::Call),
+        Dmp(dmp::Call),
+        Ump(ump::Call),
+        Hrmp(hrmp::Call),
+        Registrar(registrar::Call),
+        Auctions(auctions::Call),
+        Crowdloan(crowdloan::Call),
+        Slots(slots::Call),
+        ParasSudoWrapper(paras_sudo_wrapper::Call),
+        Sudo(sudo::Call),
+        ValidatorManager(validator_manager::Call),
+        Collective(collective::Call),
+        Membership(membership::Call),
+        Utility(utility::Call),
+        Proxy(proxy::Call),
+        Multisig(multisig::Call),
+        XcmPallet(xcm_pallet::Call),
+        #[doc = r" A pallet or variant unknown when this code was generated: the pallet index,"]
+        #[doc = r" the variant index and the encoded fields."]
+        Other(u8, u8, ::std::vec::Vec<u8>),
+    }
+    impl ::subxt::codec::Encode for Call {
+        fn encode_to<O: ::subxt::codec::Output + ?Sized>(&self, dest: &mut O) {
+            match self {
+                Self::System(inner) => {
+                    dest.push_byte(0);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Babe(inner) => {
+                    dest.push_byte(1);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Timestamp(inner) => {
+                    dest.push_byte(2);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Indices(inner) => {
+                    dest.push_byte(3);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Balances(inner) => {
+                    dest.push_byte(4);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Authorship(inner) => {
+                    dest.push_byte(6);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Session(inner) => {
+                    dest.push_byte(9);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Grandpa(inner) => {
+                    dest.push_byte(10);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ImOnline(inner) => {
+                    dest.push_byte(11);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Configuration(inner) => {
+                    dest.push_byte(14);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ParasShared(inner) => {
+                    dest.push_byte(15);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ParaInclusion(inner) => {
+                    dest.push_byte(16);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ParaInherent(inner) => {
+                    dest.push_byte(17);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Paras(inner) => {
+                    dest.push_byte(19);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Initializer(inner) => {
+                    dest.push_byte(20);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Dmp(inner) => {
+                    dest.push_byte(21);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Ump(inner) => {
+                    dest.push_byte(22);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Hrmp(inner) => {
+                    dest.push_byte(23);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Registrar(inner) => {
+                    dest.push_byte(26);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Auctions(inner) => {
+                    dest.push_byte(27);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Crowdloan(inner) => {
+                    dest.push_byte(28);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Slots(inner) => {
+                    dest.push_byte(29);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ParasSudoWrapper(inner) => {
+                    dest.push_byte(30);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Sudo(inner) => {
+                    dest.push_byte(31);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::ValidatorManager(inner) => {
+                    dest.push_byte(35);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Collective(inner) => {
+                    dest.push_byte(80);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Membership(inner) => {
+                    dest.push_byte(81);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Utility(inner) => {
+                    dest.push_byte(90);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Proxy(inner) => {
+                    dest.push_byte(91);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Multisig(inner) => {
+                    dest.push_byte(92);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::XcmPallet(inner) => {
+                    dest.push_byte(99);
+                    ::subxt::codec::Encode::encode_to(inner, dest)
+                }
+                Self::Other(pallet_index, variant_index, fields) => {
+                    dest.push_byte(*pallet_index);
+                    dest.push_byte(*variant_index);
+                    dest.write(fields)
+                }
+            }
+        }
+    }
+    impl ::subxt::codec::Decode for Call {
+        fn decode<I: ::subxt::codec::Input>(
+            input: &mut I,
+        ) -> ::core::result::Result<Self, ::subxt::codec::Error> {
+            let pallet_index = input.read_byte()?;
+            let variant_index = input.read_byte()?;
+            match (pallet_index, variant_index) {
+                (0, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9) => {
+                    ::core::result::Result::Ok(Self::System(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (1, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Babe(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (2, 0) => {
+                    ::core::result::Result::Ok(Self::Timestamp(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (3, 0 | 1 | 2 | 3 | 4) => {
+                    ::core::result::Result::Ok(Self::Indices(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (4, 0 | 1 | 2 | 3 | 4 | 5) => {
+                    ::core::result::Result::Ok(Self::Balances(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (6, 0) => {
+                    ::core::result::Result::Ok(Self::Authorship(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (9, 0 | 1) => {
+                    ::core::result::Result::Ok(Self::Session(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (10, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Grandpa(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (11, 0) => {
+                    ::core::result::Result::Ok(Self::ImOnline(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (14, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 | 13 | 14 | 15 | 16 | 17 | 18 | 19 | 20 | 21 | 22 | 23 | 24 | 25 | 26 | 27 | 28 | 29 | 30 | 31 | 32 | 33 | 34 | 35 | 36 | 37 | 38 | 39 | 40) => {
+                    ::core::result::Result::Ok(Self::Configuration(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (17, 0) => {
+                    ::core::result::Result::Ok(Self::ParaInherent(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (19, 0 | 1 | 2 | 3 | 4) => {
+                    ::core::result::Result::Ok(Self::Paras(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (20, 0) => {
+                    ::core::result::Result::Ok(Self::Initializer(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (22, 0) => {
+                    ::core::result::Result::Ok(Self::Ump(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (23, 0 | 1 | 2 | 3 | 4 | 5 | 6) => {
+                    ::core::result::Result::Ok(Self::Hrmp(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (26, 0 | 1 | 2 | 3 | 4 | 5) => {
+                    ::core::result::Result::Ok(Self::Registrar(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (27, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Auctions(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (28, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7) => {
+                    ::core::result::Result::Ok(Self::Crowdloan(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (29, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Slots(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (30, 0 | 1 | 2 | 3 | 4 | 5) => {
+                    ::core::result::Result::Ok(Self::ParasSudoWrapper(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (31, 0 | 1 | 2 | 3) => {
+                    ::core::result::Result::Ok(Self::Sudo(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (35, 0 | 1) => {
+                    ::core::result::Result::Ok(Self::ValidatorManager(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (80, 0 | 1 | 2 | 3 | 4 | 5) => {
+                    ::core::result::Result::Ok(Self::Collective(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (81, 0 | 1 | 2 | 3 | 4 | 5 | 6) => {
+                    ::core::result::Result::Ok(Self::Membership(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (90, 0 | 1 | 2) => {
+                    ::core::result::Result::Ok(Self::Utility(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (91, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9) => {
+                    ::core::result::Result::Ok(Self::Proxy(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (92, 0 | 1 | 2 | 3) => {
+                    ::core::result::Result::Ok(Self::Multisig(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                (99, 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7) => {
+                    ::core::result::Result::Ok(Self::XcmPallet(
+                        ::subxt::outer_enum::decode_variant(variant_index, input)?,
+                    ))
+                }
+                _ => {
+                    ::core::result::Result::Ok(Self::Other(
+                        pallet_index,
+                        variant_index,
+                        ::subxt::outer_enum::decode_unknown(input)?,
+                    ))
+                }
+            }
+        }
     }
     pub mod system {
         use super::runtime_types;
+        pub type Call = runtime_types::frame_system::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -671,6 +1224,7 @@ pub mod api {
     }
     pub mod babe {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_babe::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -1085,6 +1639,7 @@ pub mod api {
     }
     pub mod timestamp {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_timestamp::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -1166,6 +1721,7 @@ pub mod api {
     }
     pub mod indices {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_indices::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -1346,6 +1902,7 @@ pub mod api {
     }
     pub mod balances {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_balances::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -1804,6 +2361,7 @@ pub mod api {
     }
     pub mod authorship {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_authorship::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -2071,6 +2629,7 @@ pub mod api {
     }
     pub mod session {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_session::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -2304,6 +2863,7 @@ pub mod api {
     }
     pub mod grandpa {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_grandpa::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -2567,6 +3127,7 @@ pub mod api {
     }
     pub mod im_online {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_im_online::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -2768,6 +3329,7 @@ pub mod api {
     }
     pub mod configuration {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::configuration::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -3490,6 +4052,7 @@ pub mod api {
     }
     pub mod paras_shared {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::shared::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             pub struct TransactionApi<
@@ -3584,6 +4147,7 @@ pub mod api {
     }
     pub mod para_inclusion {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::inclusion::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             pub struct TransactionApi<
@@ -3750,6 +4314,7 @@ pub mod api {
     }
     pub mod para_inherent {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::paras_inherent::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -3954,6 +4519,7 @@ pub mod api {
     }
     pub mod paras {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::paras::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -4675,6 +5241,7 @@ pub mod api {
     }
     pub mod initializer {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::initializer::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -4750,6 +5317,7 @@ pub mod api {
     }
     pub mod dmp {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::dmp::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             pub struct TransactionApi<
@@ -4855,6 +5423,7 @@ pub mod api {
     }
     pub mod ump {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::ump::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -5135,6 +5704,7 @@ pub mod api {
     }
     pub mod hrmp {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_parachains::hrmp::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -6014,6 +6584,7 @@ pub mod api {
     }
     pub mod registrar {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_common::paras_registrar::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -6279,6 +6850,7 @@ pub mod api {
     }
     pub mod auctions {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_common::auctions::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -6565,6 +7137,7 @@ pub mod api {
     }
     pub mod crowdloan {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_common::crowdloan::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -6965,6 +7538,7 @@ pub mod api {
     }
     pub mod slots {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_common::slots::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -7118,6 +7692,7 @@ pub mod api {
     }
     pub mod paras_sudo_wrapper {
         use super::runtime_types;
+        pub type Call = runtime_types::polkadot_runtime_common::paras_sudo_wrapper::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -7250,6 +7825,7 @@ pub mod api {
     }
     pub mod sudo {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_sudo::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -7589,6 +8165,7 @@ pub mod api {
     }
     pub mod validator_manager {
         use super::runtime_types;
+        pub type Call = runtime_types::rococo_runtime::validator_manager::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -7712,6 +8289,7 @@ pub mod api {
     }
     pub mod collective {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_collective::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -8097,6 +8675,7 @@ pub mod api {
     }
     pub mod membership {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_membership::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -8310,6 +8889,7 @@ pub mod api {
     }
     pub mod utility {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_utility::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -8402,6 +8982,7 @@ pub mod api {
     }
     pub mod proxy {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_proxy::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -8800,6 +9381,7 @@ pub mod api {
     }
     pub mod multisig {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_multisig::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -9099,6 +9681,7 @@ pub mod api {
     }
     pub mod xcm_pallet {
         use super::runtime_types;
+        pub type Call = runtime_types::pallet_xcm::pallet::Call;
         pub mod calls {
             use super::runtime_types;
             #[derive(:: subxt :: codec :: Encode, :: subxt :: codec :: Decode)]
@@ -14646,6 +15229,7 @@ pub mod api {
         type Hash = ::subxt::sp_core::H256;
         type Hashing = ::subxt::sp_runtime::traits::BlakeTwo256;
         type AccountId = ::subxt::sp_runtime::AccountId32;
+        type Balance = u128;
         type Address = ::subxt::sp_runtime::MultiAddress<Self::AccountId, u32>;
         type Header = ::subxt::sp_runtime::generic::Header<
             Self::BlockNumber,
@@ -14925,5 +15509,27 @@ pub mod api {
         pub fn xcm_pallet(&self) -> xcm_pallet::calls::TransactionApi<'a, T> {
             xcm_pallet::calls::TransactionApi::new(self.client)
         }
+        pub async fn submit_dynamic(
+            &self,
+            pallet: &str,
+            call: &str,
+            args: ::std::vec::Vec<::subxt::value::Value>,
+            signer: &(dyn ::subxt::Signer<T> + Send + Sync),
+        ) -> ::core::result::Result<::subxt::TransactionProgress<'a, T>, ::subxt::Error>
+        where
+            <<<T as ::subxt::ExtrinsicExtraData<T>>::Extra as ::subxt::SignedExtra<
+                T,
+            >>::Extra as ::subxt::sp_runtime::traits::SignedExtension>::AdditionalSigned:
+                Send + Sync + 'static,
+        {
+            self.client.submit_dynamic(pallet, call, args, signer).await
+        }
+        pub async fn watch_calls(
+            &self,
+            pallet: &str,
+            call: &str,
+        ) -> ::core::result::Result<::subxt::CallSubscription<'a, T>, ::subxt::Error> {
+            self.client.watch_calls(pallet, call).await
+        }
     }
 }
@@ -15,6 +15,7 @@
 // along with subxt.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
+    node_runtime,
     node_runtime::{
         balances,
         runtime_types,
@@ -23,21 +24,41 @@ use crate::{
     },
     test_context,
 };
-use codec::Decode;
+use codec::{
+    Decode,
+    Encode,
+};
+use futures::StreamExt;
 use sp_core::{
     sr25519::Pair,
     Pair as _,
 };
 use sp_keyring::AccountKeyring;
+use sp_runtime::traits::{
+    Hash as _,
+    Header as _,
+};
 use subxt::{
     extrinsic::{
         PairSigner,
         Signer,
     },
+    tip::PriorityTip,
+    value::{
+        Composite,
+        Primitive,
+        Value,
+    },
+    BatchWatcher,
+    Config,
     Error,
+    EventRecord,
     EventSubscription,
     PalletError,
     RuntimeError,
+    ScanOptions,
+    TransactionError,
+    WellKnownEvent,
 };
 
 #[async_std::test]
@@ -75,6 +96,10 @@ async fn tx_basic_transfer() -> Result<(), subxt::Error> {
         .find_first_event::<system::events::ExtrinsicSuccess>()
         .expect("Failed to decode ExtrinisicSuccess")
         .expect("Failed to find ExtrinisicSuccess");
+    let well_known = events.well_known_events()?;
+    assert!(well_known
+        .iter()
+        .any(|event| matches!(event, WellKnownEvent::ExtrinsicSuccess(_))));
 
     let expected_event = balances::events::Transfer {
         from: alice.account_id().clone(),
@@ -99,6 +124,259 @@ async fn tx_basic_transfer() -> Result<(), subxt::Error> {
     Ok(())
 }
 
+#[async_std::test]
+async fn transfer_reports_its_extrinsic_index() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let events = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    // The timestamp inherent comes first.
+    assert!(events.extrinsic_index() > 0);
+
+    let proof = cxt
+        .client()
+        .extrinsic_inclusion_proof(events.block_hash(), events.extrinsic_index())
+        .await?;
+    proof.verify()?;
+    assert_eq!(
+        <DefaultConfig as Config>::Hashing::hash(&proof.extrinsic),
+        events.extrinsic_hash()
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn outer_event_decodes_raw_events() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let events = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    let raw = events
+        .iter()
+        .find(|raw| raw.pallet == "Balances" && raw.variant == "Transfer")
+        .expect("Failed to find balances::events::Transfer");
+    let event = node_runtime::Event::decode(&mut &raw.bytes[..])?;
+    assert!(matches!(event, node_runtime::Event::Balances(_)));
+    assert_eq!(event.encode(), raw.bytes.0);
+
+    // An event of the balances pallet which is not in the metadata.
+    let bytes = vec![raw.pallet_index, 250, 7];
+    let event = node_runtime::Event::decode(&mut &bytes[..])?;
+    assert_eq!(
+        event,
+        node_runtime::Event::Other(raw.pallet_index, 250, vec![7])
+    );
+    Ok(())
+}
+
+#[test]
+fn outer_event_decodes_unknown_variants_into_other() {
+    // An event of a pallet which is not in the metadata.
+    let bytes = vec![200, 1, 7, 8, 9];
+    let event = node_runtime::Event::decode(&mut &bytes[..]).unwrap();
+    assert_eq!(event, node_runtime::Event::Other(200, 1, vec![7, 8, 9]));
+    assert_eq!(event.encode(), bytes);
+}
+
+#[async_std::test]
+async fn tx_transfer_fire_and_forget() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+    let rpc = cxt.client().rpc();
+
+    let mut blocks = rpc.subscribe_blocks().await?;
+    let hash = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit(&alice)
+        .await?;
+
+    // The returned hash identifies the extrinsic once it is included in a block.
+    while let Some(header) = blocks.next().await? {
+        let block = rpc.block(Some(header.hash())).await?.unwrap();
+        if block.block.extrinsics.iter().any(|extrinsic| {
+            <DefaultConfig as Config>::Hashing::hash_of(extrinsic) == hash
+        }) {
+            return Ok(())
+        }
+    }
+    panic!("Block subscription ended before the extrinsic was included")
+}
+
+#[async_std::test]
+async fn tx_transfer_payment_info() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let transfer = cxt.api.tx().balances().transfer(bob_address, 10_000);
+    let info = transfer.payment_info(&alice).await?;
+    assert!(info.partial_fee > 0);
+    assert!(info.weight.ref_time > 0);
+    assert_eq!(info.class, subxt::rpc::DispatchClass::Normal);
+
+    let extrinsic = transfer.create_signed(&alice, Default::default()).await?;
+    assert_eq!(info.len as usize, codec::Encode::encode(&extrinsic).len());
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_transfer_estimate_fee() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let transfer = cxt.api.tx().balances().transfer(bob_address, 10_000);
+    let estimate = transfer.estimate_fee(alice.account_id()).await?;
+    let info = transfer.payment_info(&alice).await?;
+    assert!(estimate.partial_fee > 0);
+    assert_eq!(estimate, info);
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_transfer_tip_for_priority() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address: <DefaultConfig as Config>::Address =
+        AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let events = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .tip_for_priority(&alice, PriorityTip::new(1_000_000))
+        .await?
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    events.find_event_required::<balances::events::Transfer>()?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_transfer_tip_scaled_by_fee_multiplier() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address: <DefaultConfig as Config>::Address =
+        AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let hash = cxt.client().rpc().block_hash(None).await?;
+    let multiplier = cxt.client().next_fee_multiplier(hash).await?;
+    let stored = cxt
+        .api
+        .storage()
+        .transaction_payment()
+        .next_fee_multiplier(hash)
+        .await?;
+    assert_eq!(
+        sp_runtime::FixedPointNumber::into_inner(multiplier),
+        stored.0
+    );
+
+    let events = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .tip_scaled_by_fee_multiplier(1_000_000)
+        .await?
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    events.find_event_required::<balances::events::Transfer>()?;
+    Ok(())
+}
+
+#[async_std::test]
+async fn check_funds_rejects_unaffordable_transfers() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let hans = PairSigner::<DefaultConfig, _>::new(Pair::generate().0);
+    let bob_address: <DefaultConfig as Config>::Address =
+        AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    cxt.api
+        .tx()
+        .balances()
+        .transfer(bob_address.clone(), 10_000)
+        .check_funds(&alice)
+        .await?;
+
+    let res = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .check_funds(&hans)
+        .await;
+    assert!(matches!(
+        res,
+        Err(Error::InsufficientFunds { available: 0, required }) if required > 10_000
+    ));
+    Ok(())
+}
+
+#[async_std::test]
+async fn batch_watcher_waits_for_all_transfers() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address: <DefaultConfig as Config>::Address =
+        AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let mut watcher = BatchWatcher::new(cxt.client()).await?;
+    let mut hashes = Vec::new();
+    for amount in [10_000, 20_000, 30_000] {
+        let transfer = cxt
+            .api
+            .tx()
+            .balances()
+            .transfer(bob_address.clone(), amount);
+        hashes.push(watcher.submit(transfer, &alice).await?);
+    }
+
+    let finalized = watcher.wait_for_finalized().await?;
+    assert_eq!(
+        finalized
+            .iter()
+            .map(|tx| tx.extrinsic_hash())
+            .collect::<Vec<_>>(),
+        hashes
+    );
+    for (tx, amount) in finalized.iter().zip([10_000, 20_000, 30_000]) {
+        let transfer = tx
+            .wait_for_success()
+            .await?
+            .find_event_required::<balances::events::Transfer>()?;
+        assert_eq!(transfer.amount, amount);
+    }
+    Ok(())
+}
+
 #[async_std::test]
 async fn storage_total_issuance() {
     let cxt = test_context().await;
@@ -195,6 +473,71 @@ async fn transfer_error() {
     }
 }
 
+#[async_std::test]
+async fn find_event_required_reports_missing_events() -> Result<(), subxt::Error> {
+    env_logger::try_init().ok();
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let hans = PairSigner::<DefaultConfig, _>::new(Pair::generate().0);
+    let cxt = test_context().await;
+
+    let events = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    let transfer = events.find_event_required::<balances::events::Transfer>()?;
+    assert_eq!(&transfer.from, alice.account_id());
+    assert!(matches!(
+        events.find_event_required::<system::events::NewAccount>(),
+        Err(Error::Transaction(TransactionError::EventNotFound {
+            pallet: "System",
+            event: "NewAccount",
+        }))
+    ));
+
+    // `hans` can pay the fees but not the transfer, so the reason is surfaced.
+    cxt.api
+        .tx()
+        .balances()
+        .transfer(hans.account_id().clone().into(), 100_000_000_000_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    let events = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(alice.account_id().clone().into(), 100_000_000_000_000_000)
+        .sign_and_submit_then_watch(&hans)
+        .await?
+        .wait_for_finalized()
+        .await?
+        .fetch_events()
+        .await?;
+    match events.find_event_required::<balances::events::Transfer>() {
+        Err(Error::Runtime(RuntimeError::Module(PalletError {
+            pallet, error, ..
+        }))) => {
+            assert_eq!(pallet, "Balances");
+            assert_eq!(error, "InsufficientBalance");
+        }
+        other => {
+            panic!(
+                "expected a runtime module error, got {:?}",
+                other.map(|_| ())
+            )
+        }
+    }
+
+    Ok(())
+}
+
 #[async_std::test]
 async fn transfer_subscription() {
     env_logger::try_init().ok();
@@ -203,7 +546,7 @@ async fn transfer_subscription() {
     let bob_addr = bob.clone().into();
     let cxt = test_context().await;
     let sub = cxt.client().rpc().subscribe_events().await.unwrap();
-    let decoder = cxt.client().events_decoder();
+    let decoder = cxt.client().events_decoder().unwrap();
     let mut sub = EventSubscription::<DefaultConfig>::new(sub, decoder);
     sub.filter_event::<balances::events::Transfer>();
 
@@ -263,8 +606,359 @@ async fn transfer_implicit_subscription() {
 #[async_std::test]
 async fn constant_existential_deposit() {
     let cxt = test_context().await;
-    let balances_metadata = cxt.client().metadata().pallet("Balances").unwrap();
+    let balances_metadata = cxt.client().metadata().unwrap().pallet("Balances").unwrap();
     let constant_metadata = balances_metadata.constant("ExistentialDeposit").unwrap();
     let existential_deposit = u128::decode(&mut &constant_metadata.value[..]).unwrap();
     assert_eq!(existential_deposit, 100_000_000_000_000);
 }
+
+#[async_std::test]
+async fn constant_and_runtime_version_at_block() {
+    let cxt = test_context().await;
+    let client = cxt.client();
+    let genesis = *client.genesis();
+
+    let existential_deposit: u128 = client
+        .constant("Balances", "ExistentialDeposit", Some(genesis))
+        .await
+        .unwrap();
+    assert_eq!(existential_deposit, 100_000_000_000_000);
+
+    let version = client.runtime_version(Some(genesis)).await.unwrap();
+    let latest = client.runtime_version(None).await.unwrap();
+    assert_eq!(version.spec_version, latest.spec_version);
+}
+
+#[async_std::test]
+async fn client_account_balance() -> Result<(), subxt::Error> {
+    let alice = AccountKeyring::Alice.to_account_id();
+    let cxt = test_context().await;
+
+    let metadata = cxt.client().metadata()?;
+    assert_eq!(
+        subxt::balances::BalanceLayout::from_metadata(metadata)?,
+        subxt::balances::BalanceLayout::System
+    );
+
+    let balance = cxt.client().account_balance(&alice, None).await?;
+    let account = cxt.api.storage().system().account(alice, None).await?;
+    assert_eq!(balance.free, account.data.free);
+    assert_eq!(balance.reserved, account.data.reserved);
+    assert_eq!(balance.misc_frozen, account.data.misc_frozen);
+    assert_eq!(balance.fee_frozen, account.data.fee_frozen);
+    Ok(())
+}
+
+#[async_std::test]
+async fn client_account_balances() -> Result<(), subxt::Error> {
+    let alice = AccountKeyring::Alice.to_account_id();
+    let bob = AccountKeyring::Bob.to_account_id();
+    let unknown = PairSigner::<DefaultConfig, _>::new(Pair::generate().0)
+        .account_id()
+        .clone();
+    let cxt = test_context().await;
+    let client = cxt.client();
+    let hash = client.rpc().finalized_head().await?;
+
+    let balances = client
+        .account_balances(&[alice.clone(), unknown, bob.clone()], Some(hash))
+        .await?;
+    assert_eq!(balances.len(), 3);
+    assert_eq!(
+        balances[0],
+        client.account_balance(&alice, Some(hash)).await?
+    );
+    assert_eq!(balances[1], subxt::balances::AccountBalance::empty());
+    assert_eq!(balances[2], client.account_balance(&bob, Some(hash)).await?);
+    assert!(client.account_balances(&[], None).await?.is_empty());
+    Ok(())
+}
+
+#[async_std::test]
+async fn subscribe_balance_yields_changes() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let cxt = test_context().await;
+
+    let mut balances = Box::pin(cxt.client().subscribe_balance(&bob).await?);
+    let initial = balances.next().await.expect("initial balance")?;
+    assert_eq!(initial, cxt.client().account_balance(&bob, None).await?);
+
+    cxt.client().transfer(bob.clone(), 10_000, &alice).await?;
+    let changed = balances.next().await.expect("changed balance")?;
+    assert_eq!(changed.free, initial.free + 10_000);
+    assert_eq!(changed.reserved, initial.reserved);
+    Ok(())
+}
+
+#[async_std::test]
+async fn client_transfer_helpers() {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let cxt = test_context().await;
+
+    let event = cxt
+        .client()
+        .transfer(bob.clone(), 10_000, &alice)
+        .await
+        .unwrap();
+    assert_eq!(
+        event,
+        subxt::balances::Transferred {
+            from: alice.account_id().clone(),
+            to: bob.clone(),
+            amount: 10_000,
+        }
+    );
+
+    // Draining the whole free balance would reap the sender's account.
+    let alice_account = cxt
+        .api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), None)
+        .await
+        .unwrap();
+    let res = cxt
+        .client()
+        .transfer_keep_alive(bob, alice_account.data.free, &alice)
+        .await;
+    assert!(matches!(
+        res,
+        Err(Error::Runtime(RuntimeError::Module(PalletError { ref pallet, .. })))
+            if pallet == "Balances"
+    ));
+}
+
+#[async_std::test]
+async fn block_events_iter_dynamic() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let block_hash = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?
+        .block_hash();
+
+    let block_events = cxt.client().events().at(block_hash).await?;
+    let events = block_events.iter_dynamic().collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(events.len(), block_events.iter_raw().count());
+
+    let transfer = events
+        .iter()
+        .find(|event| event.pallet == "Balances" && event.variant == "Transfer")
+        .expect("Failed to find the Balances::Transfer event");
+    // The fields are `from`, `to` and `amount`.
+    assert_eq!(
+        transfer.fields.values()[2],
+        &Value::Primitive(Primitive::U128(10_000))
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn scan_events_over_block_range() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let block_hash = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?
+        .block_hash();
+    let header = cxt.client().rpc().header(Some(block_hash)).await?.unwrap();
+    let number = *header.number();
+
+    let options = ScanOptions::new().set_concurrency(2).set_chunk_size(3);
+    let blocks = cxt
+        .client()
+        .events()
+        .scan_with_options(0, number, options)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(blocks.len(), number as usize + 1);
+
+    let (hash, records) = blocks.last().unwrap();
+    assert_eq!(*hash, block_hash);
+    let transferred = records.iter().any(|(_, record)| {
+        match record {
+            EventRecord::Decoded(raw) => {
+                raw.pallet == "Balances" && raw.variant == "Transfer"
+            }
+            EventRecord::Raw { .. } => false,
+        }
+    });
+    assert!(transferred);
+    Ok(())
+}
+
+#[async_std::test]
+async fn subscribe_finalized_events_from_checkpoint() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob_address = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let block_hash = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob_address, 10_000)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?
+        .block_hash();
+    let header = cxt.client().rpc().header(Some(block_hash)).await?.unwrap();
+    let number = *header.number();
+
+    // Resume from the block of the transfer, then follow a few more finalized blocks.
+    let blocks = cxt
+        .client()
+        .events()
+        .subscribe_finalized(Some(number))
+        .take(3)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0].0, block_hash);
+    for (offset, (hash, _)) in blocks.iter().enumerate() {
+        let expected = cxt
+            .client()
+            .rpc()
+            .block_hash(Some((number + offset as u32).into()))
+            .await?;
+        assert_eq!(Some(*hash), expected);
+    }
+    Ok(())
+}
+
+#[async_std::test]
+async fn submit_dynamic_transfer() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let cxt = test_context().await;
+
+    let dest = Value::Variant {
+        name: "Id".into(),
+        fields: Composite::Unnamed(vec![Value::from_bytes(bob.as_ref())]),
+    };
+    let event = cxt
+        .api
+        .tx()
+        .submit_dynamic(
+            "Balances",
+            "transfer",
+            vec![dest.clone(), Value::u128(10_000)],
+            &alice,
+        )
+        .await?
+        .wait_for_finalized_success()
+        .await?
+        .find_event_required::<balances::events::Transfer>()?;
+    assert_eq!(
+        event,
+        balances::events::Transfer {
+            from: alice.account_id().clone(),
+            to: bob,
+            amount: 10_000,
+        }
+    );
+
+    let missing_arg = cxt
+        .api
+        .tx()
+        .submit_dynamic("Balances", "transfer", vec![dest.clone()], &alice)
+        .await;
+    assert!(matches!(missing_arg, Err(Error::InvalidCallArgs(_))));
+
+    let wrong_type = cxt
+        .api
+        .tx()
+        .submit_dynamic(
+            "Balances",
+            "transfer",
+            vec![dest, Value::Primitive(Primitive::Str("10000".into()))],
+            &alice,
+        )
+        .await;
+    assert!(
+        matches!(wrong_type, Err(Error::InvalidCallArgs(msg)) if msg.contains("value"))
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn dynamic_api_transfer() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let cxt = test_context().await;
+    let dynamic = cxt.client().dynamic();
+
+    let account = vec![Value::from_bytes(bob.as_ref())];
+    let free_balance = |value: Option<Value>| {
+        match value {
+            Some(Value::Composite(info)) => {
+                match info.field("data") {
+                    Some(Value::Composite(data)) => data.field("free").cloned(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    };
+    let before =
+        free_balance(dynamic.storage("System", "Account", &account, None).await?);
+
+    let dest = Value::Variant {
+        name: "Id".into(),
+        fields: Composite::Unnamed(vec![Value::from_bytes(bob.as_ref())]),
+    };
+    let block_hash = dynamic
+        .tx("Balances", "transfer", &[dest, Value::u128(10_000)])?
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?
+        .block_hash();
+
+    let events = dynamic.events(Some(block_hash)).await?;
+    let transfer = events
+        .iter()
+        .find(|event| event.pallet == "Balances" && event.variant == "Transfer")
+        .expect("the transfer emits Balances::Transfer");
+    assert_eq!(transfer.fields.values()[2], &Value::u128(10_000));
+
+    let after = free_balance(
+        dynamic
+            .storage("System", "Account", &account, Some(block_hash))
+            .await?,
+    );
+    match (before, after) {
+        (
+            Some(Value::Primitive(Primitive::U128(before))),
+            Some(Value::Primitive(Primitive::U128(after))),
+        ) => assert_eq!(after, before + 10_000),
+        other => panic!("Expected free balances, got {:?}", other),
+    }
+
+    let missing_arg = dynamic.tx("Balances", "transfer", &[]);
+    assert!(matches!(missing_arg, Err(Error::InvalidCallArgs(_))));
+    Ok(())
+}
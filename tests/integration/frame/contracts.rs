@@ -36,6 +36,7 @@ use subxt::{
     Config,
     Error,
     PairSigner,
+    Signer,
     TransactionProgress,
 };
 
@@ -145,13 +146,25 @@ impl ContractsTestContext {
         input_data: Vec<u8>,
     ) -> Result<TransactionProgress<'_, DefaultConfig>, Error> {
         log::info!("call: {:?}", contract);
+        let estimate = self
+            .client()
+            .contracts()
+            .estimate_gas_call(
+                self.signer.account_id().clone(),
+                contract.clone(),
+                0,
+                input_data.clone(),
+                None,
+            )
+            .await?;
+        log::info!("Gas estimate: {:?}", estimate);
         let result = self
             .contracts_tx()
             .call(
                 MultiAddress::Id(contract),
-                0,           // value
-                500_000_000, // gas_limit
-                None,        // storage_deposit_limit
+                0,                                        // value
+                estimate.gas_required.ref_time * 11 / 10, // gas_limit
+                None,                                     // storage_deposit_limit
                 input_data,
             )
             .sign_and_submit_then_watch(&self.signer)
@@ -186,6 +199,12 @@ async fn tx_instantiate() {
         "Error instantiating contract: {:?}",
         instantiated
     );
+    let expected = subxt::contracts::derive_address::<DefaultConfig>(
+        ctx.signer.account_id(),
+        &code_hash,
+        &[1u8],
+    );
+    assert_eq!(instantiated.unwrap(), expected);
 }
 
 #[async_std::test]
@@ -213,7 +232,38 @@ async fn tx_call() {
         .collect::<Vec<_>>();
     println!("keys post: {:?}", keys);
 
+    let contracts = cxt
+        .client()
+        .storage()
+        .fetch_keys_decoded::<storage::ContractInfoOf, AccountId>(5, None, None)
+        .await
+        .unwrap();
+    assert!(contracts.contains(&contract));
+
     let executed = cxt.call(contract, vec![]).await;
 
     assert!(executed.is_ok(), "Error calling contract: {:?}", executed);
 }
+
+#[async_std::test]
+async fn estimate_gas_instantiate() {
+    let cxt = ContractsTestContext::init().await;
+    let (code_hash, _) = cxt.instantiate_with_code().await.unwrap();
+
+    let estimate = cxt
+        .client()
+        .contracts()
+        .estimate_gas_instantiate(
+            cxt.signer.account_id().clone(),
+            100_000_000_000_000_000,
+            subxt::contracts::Code::Existing(code_hash),
+            vec![],
+            vec![2u8],
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(estimate.gas_consumed.all_lte(estimate.gas_required));
+    assert!(estimate.gas_consumed.ref_time > 0);
+}
@@ -22,3 +22,4 @@ mod staking;
 mod sudo;
 mod system;
 mod timestamp;
+mod utility;
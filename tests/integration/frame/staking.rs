@@ -21,6 +21,7 @@ use crate::{
             ValidatorPrefs,
         },
         staking,
+        system,
         DefaultConfig,
     },
     test_context,
@@ -266,3 +267,28 @@ async fn storage_era_reward_points() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[async_std::test]
+async fn client_active_era_and_current_session() -> Result<(), Error> {
+    let cxt = test_context().await;
+    let active_era = cxt.client().active_era(None).await?;
+    let current_era = cxt.api.storage().staking().current_era(None).await?;
+    assert_eq!(active_era.map(|era| era.index), current_era);
+
+    let current_session = cxt.client().current_session(None).await?;
+    let index = cxt.api.storage().session().current_index(None).await?;
+    assert_eq!(current_session, Some(index));
+    Ok(())
+}
+
+#[async_std::test]
+async fn rotate_and_set_session_keys() -> Result<(), Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+    let (keys, events) = cxt.client().rotate_session_keys(&alice).await?;
+    assert!(cxt.client().rpc().has_session_keys(keys).await?);
+    assert!(events
+        .find_first_event::<system::events::ExtrinsicSuccess>()?
+        .is_some());
+    Ok(())
+}
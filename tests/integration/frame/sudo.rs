@@ -17,16 +17,28 @@
 use crate::{
     node_runtime::{
         runtime_types,
+        scheduler,
         sudo,
         DefaultConfig,
     },
     test_context,
 };
+use codec::Encode;
 use sp_keyring::AccountKeyring;
-use subxt::extrinsic::PairSigner;
+use subxt::{
+    extrinsic::PairSigner,
+    value::{
+        encode_call,
+        Composite,
+        Value,
+    },
+};
 
 type Call = runtime_types::node_runtime::Call;
 type BalancesCall = runtime_types::pallet_balances::pallet::Call;
+type SchedulerCall = runtime_types::pallet_scheduler::pallet::Call;
+type SudoCall = runtime_types::pallet_sudo::pallet::Call;
+type SystemCall = runtime_types::frame_system::pallet::Call;
 
 #[async_std::test]
 async fn test_sudo() -> Result<(), subxt::Error> {
@@ -79,3 +91,135 @@ async fn test_sudo_unchecked_weight() -> Result<(), subxt::Error> {
     assert!(found_event);
     Ok(())
 }
+
+#[async_std::test]
+async fn test_as_sudo_reports_dispatch_result() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let cxt = test_context().await;
+
+    cxt.api
+        .tx()
+        .system()
+        .remark(b"sudo remark".to_vec())
+        .as_sudo()?
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized()
+        .await?
+        .wait_for_sudo_success()
+        .await?;
+
+    // `transfer` requires a signed origin, so dispatching it as root fails.
+    let res = cxt
+        .api
+        .tx()
+        .balances()
+        .transfer(bob.into(), 10_000)
+        .as_sudo()?
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized()
+        .await?
+        .wait_for_sudo_success()
+        .await;
+
+    assert!(matches!(
+        res,
+        Err(subxt::Error::Runtime(subxt::RuntimeError::BadOrigin))
+    ));
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_schedule_boxed_remark() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+    let client = cxt.client();
+    let when = client
+        .rpc()
+        .header(None)
+        .await?
+        .expect("the best block has a header")
+        .number
+        + 5;
+
+    // The boxed call is encoded the same by the generated types and the dynamic values.
+    let call = Call::Scheduler(SchedulerCall::schedule {
+        when,
+        maybe_periodic: None,
+        priority: 0,
+        call: Box::new(Call::System(SystemCall::remark {
+            remark: b"scheduled".to_vec(),
+        })),
+    });
+    let remark = Value::call(
+        "System",
+        "remark",
+        Composite::Named(vec![("remark".into(), Value::from_bytes(b"scheduled"))]),
+    );
+    let schedule = Value::call(
+        "Scheduler",
+        "schedule",
+        Composite::Named(vec![
+            ("when".into(), Value::u128(when.into())),
+            (
+                "maybe_periodic".into(),
+                Value::Variant {
+                    name: "None".into(),
+                    fields: Composite::Unnamed(vec![]),
+                },
+            ),
+            ("priority".into(), Value::u128(0)),
+            ("call".into(), remark),
+        ]),
+    );
+    let encoded = encode_call(client.metadata()?, "Sudo", "sudo", &[schedule.clone()])?;
+    let sudo = Call::Sudo(SudoCall::sudo {
+        call: Box::new(call),
+    });
+    assert_eq!(encoded.0, sudo.encode());
+
+    let events = client
+        .submit_dynamic("Sudo", "sudo", vec![schedule], &alice)
+        .await?
+        .wait_for_finalized()
+        .await?
+        .wait_for_sudo_success()
+        .await?;
+    assert!(events.has_event::<scheduler::events::Scheduled>()?);
+    Ok(())
+}
+
+#[async_std::test]
+async fn test_schedule_wraps_the_call() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+    let when = cxt
+        .client()
+        .rpc()
+        .header(None)
+        .await?
+        .expect("the best block has a header")
+        .number
+        + 5;
+
+    let events = cxt
+        .api
+        .tx()
+        .system()
+        .remark(b"scheduled".to_vec())
+        .schedule(when, None, 0)?
+        .as_sudo()?
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized()
+        .await?
+        .wait_for_sudo_success()
+        .await?;
+    let scheduled = events
+        .find_first_event::<subxt::scheduler::Scheduled<DefaultConfig>>()?
+        .expect("the call was scheduled");
+    assert_eq!(scheduled.task_address().0, when);
+    Ok(())
+}
@@ -23,9 +23,17 @@ use crate::{
 };
 use assert_matches::assert_matches;
 use sp_keyring::AccountKeyring;
-use subxt::extrinsic::{
-    PairSigner,
-    Signer,
+use std::time::Duration;
+use subxt::{
+    extrinsic::{
+        PairSigner,
+        Signer,
+    },
+    value::{
+        Primitive,
+        Value,
+    },
+    Phase,
 };
 
 #[async_std::test]
@@ -44,6 +52,70 @@ async fn storage_account() -> Result<(), subxt::Error> {
     Ok(())
 }
 
+#[async_std::test]
+async fn storage_account_dynamic() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+    let hash = cxt.client().rpc().finalized_head().await?;
+
+    let account_info = cxt
+        .api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), Some(hash))
+        .await?;
+    let value = cxt
+        .client()
+        .storage()
+        .fetch_dynamic(
+            "System",
+            "Account",
+            vec![Value::from_bytes(alice.account_id().as_ref())],
+            Some(hash),
+        )
+        .await?
+        .expect("System::Account has a default");
+
+    let fields = match value {
+        Value::Composite(fields) => fields,
+        other => panic!("Expected AccountInfo, got {:?}", other),
+    };
+    assert_eq!(
+        fields.field("nonce"),
+        Some(&Value::u128(account_info.nonce.into()))
+    );
+
+    let number = cxt
+        .client()
+        .storage()
+        .fetch_dynamic("System", "Number", vec![], Some(hash))
+        .await?;
+    assert!(matches!(number, Some(Value::Primitive(Primitive::U128(_)))));
+    Ok(())
+}
+
+#[async_std::test]
+async fn storage_entries_describe_pallet_storage() -> Result<(), subxt::Error> {
+    let cxt = test_context().await;
+    let metadata = cxt.client().metadata()?;
+
+    let entries = metadata.storage_entries("System")?;
+    let account = entries
+        .iter()
+        .find(|entry| entry.name == "Account")
+        .expect("System::Account exists");
+    assert_eq!(account.hashers.len(), 1);
+    assert_eq!(account.key_types.len(), 1);
+    assert!(metadata.resolve_type(account.value_type).is_some());
+
+    let number = metadata.storage_entry("System", "Number")?;
+    assert!(number.hashers.is_empty());
+    assert!(number.key_types.is_empty());
+
+    assert!(metadata.storage_entries("NotAPallet").is_err());
+    Ok(())
+}
+
 #[async_std::test]
 async fn tx_remark_with_event() -> Result<(), subxt::Error> {
     let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
@@ -63,3 +135,255 @@ async fn tx_remark_with_event() -> Result<(), subxt::Error> {
     assert!(found_event);
     Ok(())
 }
+
+#[async_std::test]
+async fn tx_with_explicit_nonce_fills_the_gap() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+    let nonce = cxt
+        .api
+        .storage()
+        .system()
+        .account(alice.account_id().clone(), None)
+        .await?
+        .nonce;
+
+    // Leaves a gap at `nonce`, so the transaction waits in the future queue.
+    cxt.api
+        .tx()
+        .system()
+        .remark(b"after the gap".to_vec())
+        .nonce(nonce + 1)
+        .sign_and_submit(&alice)
+        .await?;
+    cxt.api
+        .tx()
+        .system()
+        .remark(b"in the gap".to_vec())
+        .nonce(nonce)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let stale = cxt
+        .api
+        .tx()
+        .system()
+        .remark(b"stale".to_vec())
+        .nonce(nonce)
+        .sign_and_submit(&alice)
+        .await;
+    assert!(stale.is_err());
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_events_report_block_number() -> Result<(), subxt::Error> {
+    use sp_runtime::traits::Header as _;
+
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+
+    let events = cxt
+        .api
+        .tx()
+        .system()
+        .remark(b"numbered".to_vec())
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+    let header = cxt
+        .client()
+        .rpc()
+        .header(Some(events.block_hash()))
+        .await?
+        .expect("block of the transaction exists");
+
+    assert_eq!(events.block_number(), *header.number());
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_watch_returns_hash_and_statuses() -> Result<(), subxt::Error> {
+    use futures::StreamExt;
+
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+
+    let (ext_hash, statuses) = cxt
+        .api
+        .tx()
+        .system()
+        .remark(b"watched".to_vec())
+        .sign_and_submit_watch(&alice)
+        .await?;
+    futures::pin_mut!(statuses);
+
+    let mut finalized = None;
+    let mut seen_in_block = false;
+    while let Some(status) = statuses.next().await {
+        let status = status?;
+        if let Some(in_block) = status.as_in_block() {
+            assert_eq!(in_block.extrinsic_hash(), ext_hash);
+            seen_in_block = true;
+        }
+        if let Some(in_block) = status.as_finalized() {
+            assert!(seen_in_block, "InBlock is reported before Finalized");
+            finalized = Some(in_block.extrinsic_hash());
+        }
+    }
+    assert_eq!(finalized, Some(ext_hash));
+    Ok(())
+}
+
+#[async_std::test]
+async fn signer_payload_is_what_gets_signed() -> Result<(), subxt::Error> {
+    use sp_runtime::traits::Verify;
+
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+
+    let remark = || cxt.api.tx().system().remark(b"reviewed".to_vec());
+    let payload = remark().signer_payload(&alice).await?;
+    let extrinsic = remark().create_signed(&alice, Default::default()).await?;
+
+    let (_, signature, _) = extrinsic.signature.expect("extrinsic is signed");
+    assert!(signature.verify(&payload[..], alice.account_id()));
+    Ok(())
+}
+
+#[async_std::test]
+async fn tx_with_expired_era_is_rejected() -> Result<(), subxt::Error> {
+    use sp_runtime::{
+        generic::Era,
+        traits::Header as _,
+    };
+
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+
+    // Wait until a mortal era of 4 blocks anchored at genesis has expired.
+    let mut blocks = cxt.client().rpc().subscribe_blocks().await?;
+    while let Some(header) = blocks.next().await? {
+        if *header.number() >= 8 {
+            break
+        }
+    }
+
+    let res = cxt
+        .api
+        .tx()
+        .system()
+        .remark(b"expired".to_vec())
+        .era(Era::mortal(4, 0), *cxt.client().genesis())
+        .sign_and_submit_then_watch(&alice)
+        .await;
+
+    assert_matches!(res, Err(subxt::Error::Invalid(_)));
+    Ok(())
+}
+
+#[async_std::test]
+async fn watch_calls_from_any_signer() -> Result<(), subxt::Error> {
+    let bob = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Bob.pair());
+    let cxt = test_context().await;
+
+    let mut calls = cxt.client().watch_calls("System", "remark").await?;
+    let block_hash = cxt
+        .api
+        .tx()
+        .system()
+        .remark(b"watched".to_vec())
+        .sign_and_submit_then_watch(&bob)
+        .await?
+        .wait_for_finalized_success()
+        .await?
+        .block_hash();
+
+    let call = calls.next().await.expect("subscription ended")?;
+    assert_eq!(call.block_hash, block_hash);
+    assert_eq!(call.signer, Some(bob.account_id().clone().into()));
+    assert_eq!(
+        call.args.field("remark"),
+        Some(&Value::from_bytes(b"watched"))
+    );
+
+    assert!(cxt
+        .client()
+        .watch_calls("System", "no_such_call")
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[async_std::test]
+async fn watch_timeout_reports_last_status() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+
+    let result = cxt
+        .api
+        .tx()
+        .system()
+        .remark(b"impatient".to_vec())
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .with_timeout(Duration::from_millis(1))
+        .wait_for_finalized()
+        .await;
+
+    // The transaction cannot make it into a block within a millisecond.
+    assert_matches!(
+        result,
+        Err(subxt::Error::WatchTimeout(status)) if !status.is_in_block()
+    );
+    Ok(())
+}
+
+#[async_std::test]
+async fn all_block_events_include_other_phases() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+
+    let events = cxt
+        .api
+        .tx()
+        .system()
+        .remark_with_event(b"remarkable".to_vec())
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    // The timestamp inherent is the first extrinsic of every block.
+    let all_events = events.all_block_events();
+    assert!(all_events.len() > events.len());
+    assert!(all_events
+        .iter()
+        .any(|(phase, _)| phase == &Phase::ApplyExtrinsic(0)));
+    assert!(all_events
+        .iter()
+        .any(|(_, event)| event.variant == "Remarked"));
+    Ok(())
+}
+
+#[async_std::test]
+async fn client_remark_with_event() -> Result<(), subxt::Error> {
+    use sp_runtime::traits::Hash as _;
+
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let cxt = test_context().await;
+
+    let event = cxt
+        .client()
+        .remark_with_event(b"heartbeat".to_vec(), &alice)
+        .await?;
+    assert_eq!(&event.sender, alice.account_id());
+    assert_eq!(
+        event.hash,
+        sp_runtime::traits::BlakeTwo256::hash(b"heartbeat")
+    );
+    Ok(())
+}
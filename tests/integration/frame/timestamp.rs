@@ -24,3 +24,12 @@ async fn storage_get_current_timestamp() {
 
     assert!(timestamp.is_ok())
 }
+
+#[async_std::test]
+async fn client_timestamp_matches_storage() {
+    let cxt = test_context().await;
+    let hash = cxt.client().rpc().block_hash(None).await.unwrap();
+    let timestamp = cxt.client().timestamp(hash).await.unwrap();
+    let now = cxt.api.storage().timestamp().now(hash).await.unwrap();
+    assert_eq!(timestamp, now);
+}
@@ -0,0 +1,144 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of subxt.
+//
+// subxt is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// subxt is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with subxt.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+    node_runtime::{
+        balances,
+        runtime_types,
+        utility,
+        DefaultConfig,
+    },
+    test_context,
+};
+use sp_keyring::AccountKeyring;
+use subxt::{
+    batch,
+    extrinsic::PairSigner,
+    PalletError,
+    RuntimeError,
+};
+
+type Call = runtime_types::node_runtime::Call;
+type BalancesCall = runtime_types::pallet_balances::pallet::Call;
+type SystemCall = runtime_types::frame_system::pallet::Call;
+
+#[async_std::test]
+async fn batch_completed_event_is_decoded() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    let calls = vec![
+        Call::System(SystemCall::remark {
+            remark: b"batched".to_vec(),
+        }),
+        Call::Balances(BalancesCall::transfer {
+            dest: bob,
+            value: 10_000,
+        }),
+    ];
+
+    let events = cxt
+        .api
+        .tx()
+        .utility()
+        .batch(calls)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    assert!(events.has_event::<utility::events::BatchCompleted>()?);
+    Ok(())
+}
+
+#[async_std::test]
+async fn batch_interrupted_event_carries_dispatch_error() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id().into();
+    let cxt = test_context().await;
+
+    // The second call fails with a `Balances` module error, which interrupts the batch
+    // but not the extrinsic itself.
+    let calls = vec![
+        Call::System(SystemCall::remark {
+            remark: b"batched".to_vec(),
+        }),
+        Call::Balances(BalancesCall::transfer {
+            dest: bob,
+            value: u128::MAX,
+        }),
+    ];
+
+    let events = cxt
+        .api
+        .tx()
+        .utility()
+        .batch(calls)
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let interrupted = events
+        .find_first_event::<utility::events::BatchInterrupted>()?
+        .expect("batch should be interrupted");
+    assert_eq!(interrupted.0, 1);
+    assert!(matches!(
+        interrupted.1,
+        runtime_types::sp_runtime::DispatchError::Module { .. }
+    ));
+    Ok(())
+}
+
+#[async_std::test]
+async fn force_batch_reports_the_outcome_of_each_call() -> Result<(), subxt::Error> {
+    let alice = PairSigner::<DefaultConfig, _>::new(AccountKeyring::Alice.pair());
+    let bob = AccountKeyring::Bob.to_account_id();
+    let cxt = test_context().await;
+
+    // The second call fails, but `force_batch` carries on with the third.
+    let calls = vec![
+        balances::calls::Transfer {
+            dest: bob.clone().into(),
+            value: 10_000,
+        },
+        balances::calls::Transfer {
+            dest: bob.clone().into(),
+            value: u128::MAX,
+        },
+        balances::calls::Transfer {
+            dest: bob.into(),
+            value: 20_000,
+        },
+    ];
+
+    let events = batch::force_batch(cxt.client(), calls)?
+        .sign_and_submit_then_watch(&alice)
+        .await?
+        .wait_for_finalized_success()
+        .await?;
+
+    let outcomes = batch::item_outcomes(cxt.client().metadata()?, &events)?;
+    assert_eq!(outcomes.len(), 3);
+    assert_eq!(outcomes[0], Ok(()));
+    assert!(matches!(
+        &outcomes[1],
+        Err(RuntimeError::Module(PalletError { pallet, .. })) if pallet == "Balances"
+    ));
+    assert_eq!(outcomes[2], Ok(()));
+    Ok(())
+}
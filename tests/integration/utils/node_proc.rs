@@ -172,13 +172,18 @@ impl TestNodeProcessBuilder {
                 }
             }
         };
-        match client {
-            Ok(client) => Ok(TestNodeProcess { proc, client }),
+        let client = match client {
+            Ok(client) => wait_until_ready(client).await,
             Err(err) => {
-                let err = format!(
+                Err(format!(
                     "Failed to connect to node rpc at {} after {} attempts: {}",
                     ws_url, attempts, err
-                );
+                ))
+            }
+        };
+        match client {
+            Ok(client) => Ok(TestNodeProcess { proc, client }),
+            Err(err) => {
                 log::error!("{}", err);
                 proc.kill().map_err(|e| {
                     format!("Error killing substrate process '{}': {}", proc.id(), e)
@@ -189,6 +194,37 @@ impl TestNodeProcessBuilder {
     }
 }
 
+/// Poll the node until it reports its health and serves the best header, so that tests do
+/// not race against the node still starting up.
+async fn wait_until_ready<R: Config>(client: Client<R>) -> Result<Client<R>, String> {
+    const MAX_ATTEMPTS: u32 = 20;
+    const POLL_INTERVAL: time::Duration = time::Duration::from_millis(500);
+    let mut last_err = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let ready = async {
+            client.rpc().system_health().await?;
+            client.rpc().header(None).await
+        };
+        match ready.await {
+            Ok(Some(_)) => return Ok(client),
+            Ok(None) => last_err = "no best header available yet".into(),
+            Err(err) => last_err = err.to_string(),
+        }
+        log::info!(
+            "Waiting for node to be ready, attempt {}/{}: {}",
+            attempt,
+            MAX_ATTEMPTS,
+            last_err
+        );
+        thread::sleep(POLL_INTERVAL);
+    }
+    Err(format!(
+        "Timed out after {:?} waiting for the node to be ready: {}",
+        POLL_INTERVAL * MAX_ATTEMPTS,
+        last_err
+    ))
+}
+
 /// The start of the port range to scan.
 const START_PORT: u16 = 9900;
 /// The end of the port range to scan.